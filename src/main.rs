@@ -1,11 +1,45 @@
 mod schema;
 #[cfg(test)]
 mod schema_utils;
+mod server;
+
+use server::config::{ConfigError, ServerConfig, USAGE};
+use server::schemas::ServedSchema;
 
 #[tokio::main]
 async fn main() {
-    let schema = schema::hello::create_schema();
+    let config = match ServerConfig::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(ConfigError::Help) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let schema = ServedSchema::build(&config).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    if let Some(path) = &config.write_sdl {
+        if let Err(err) = std::fs::write(path, schema.handle.schema().sdl()) {
+            eprintln!("can't write {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    let sdl = schema.sdl();
-    std::fs::write("schema.graphql", sdl).unwrap();
+    let listener = tokio::net::TcpListener::bind(config.bind)
+        .await
+        .unwrap_or_else(|err| {
+            eprintln!("can't bind {}: {}", config.bind, err);
+            std::process::exit(1);
+        });
+    println!("graphiql on http://{}", config.bind);
+    server::serve(listener, schema, &config, server::shutdown_signal())
+        .await
+        .unwrap();
 }
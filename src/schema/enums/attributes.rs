@@ -0,0 +1,269 @@
+use crate::schema::enums::utils::{EnumDefinition, EnumItemDefinition, EnumItemDeprecation};
+use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Enum, InputObject, Object, Register, Registry};
+use async_graphql::dynamic::FieldValue;
+use async_graphql::{dynamic, Context};
+use serde::{Deserialize, Deserializer};
+
+// user
+// mark as enum
+// mark rename_items = "SCREAMING_SNAKE_CASE"
+/// traffic light state
+#[derive(Clone)]
+pub enum Light {
+    Green,
+    // mark as description
+    /// slow down
+    Yellow,
+    Red,
+    // mark as rename = "BLINKING"
+    FlashingYellow,
+    // mark as deprecation = "use RED"
+    Off,
+}
+
+// mark as input
+#[derive(Deserialize)]
+struct CrossingInput {
+    lights: Vec<Light>,
+}
+
+struct Query;
+
+impl Query {
+    async fn resolve_next(&self, light: Light) -> Light {
+        match light {
+            Light::Green => Light::Yellow,
+            Light::Yellow => Light::Red,
+            Light::Red => Light::Green,
+            Light::FlashingYellow | Light::Off => Light::FlashingYellow,
+        }
+    }
+    async fn resolve_all(&self) -> Vec<Light> {
+        vec![Light::Green, Light::Yellow, Light::Red]
+    }
+    async fn resolve_can_cross(&self, input: CrossingInput) -> bool {
+        input
+            .lights
+            .iter()
+            .all(|light| matches!(light, Light::Green))
+    }
+}
+
+// generated
+
+impl Enum for Light {
+    const NAME: &'static str = "Light";
+}
+
+impl EnumDefinition for Light {
+    const DESCRIPTION: Option<&'static str> = Some("traffic light state");
+    const ITEMS: &'static [EnumItemDefinition<Self>] = &[
+        EnumItemDefinition::new("GREEN", Light::Green),
+        EnumItemDefinition {
+            description: Some("slow down"),
+            ..EnumItemDefinition::new("YELLOW", Light::Yellow)
+        },
+        EnumItemDefinition::new("RED", Light::Red),
+        EnumItemDefinition::new("BLINKING", Light::FlashingYellow),
+        EnumItemDefinition {
+            deprecation: EnumItemDeprecation::Deprecated(Some("use RED")),
+            ..EnumItemDefinition::new("OFF", Light::Off)
+        },
+    ];
+}
+
+impl Register for Light {
+    fn register(registry: Registry) -> Registry {
+        registry.register_enum(<Self as EnumDefinition>::create_enum())
+    }
+}
+
+impl<'de> Deserialize<'de> for Light {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <Self as EnumDefinition>::deserialize_item(deserializer)
+    }
+}
+
+impl<'a> ResolveOwned<'a> for Light {
+    fn resolve_owned(self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(self.to_field_value()))
+    }
+}
+
+impl<'a> ResolveRef<'a> for Light {
+    fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(self.to_field_value()))
+    }
+}
+
+impl InputObject for CrossingInput {
+    const NAME: &'static str = "CrossingInput";
+}
+
+impl Register for CrossingInput {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::InputObject::new(<Self as InputObject>::NAME);
+
+        // define lights field
+        let lights_field = dynamic::InputValue::new(
+            "lights",
+            dynamic::TypeRef::named_nn_list_nn(<Light as Enum>::NAME),
+        );
+        let object_type = object_type.field(lights_field);
+
+        registry.register_input_object(object_type)
+    }
+}
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define next field
+        let next_field = dynamic::Field::new(
+            "next",
+            dynamic::TypeRef::named_nn(<Light as Enum>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = Light::parse_value(ctx.args.try_get("light")?)?;
+                    let value = parent.resolve_next(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let next_field = next_field.argument(dynamic::InputValue::new(
+            "light",
+            dynamic::TypeRef::named_nn(<Light as Enum>::NAME),
+        ));
+        let object_type = object_type.field(next_field);
+
+        // define all field
+        let all_field = dynamic::Field::new(
+            "all",
+            dynamic::TypeRef::named_nn_list_nn(<Light as Enum>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_all().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(all_field);
+
+        // define can_cross field
+        let can_cross_field = dynamic::Field::new(
+            "can_cross",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::BOOLEAN),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("input")?.deserialize()?;
+                    let value = parent.resolve_can_cross(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let can_cross_field = can_cross_field.argument(dynamic::InputValue::new(
+            "input",
+            dynamic::TypeRef::named_nn(<CrossingInput as InputObject>::NAME),
+        ));
+        let object_type = object_type.field(can_cross_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new()
+        .register::<Query>()
+        .register::<Light>()
+        .register::<CrossingInput>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                input CrossingInput {
+                  lights: [Light!]!
+                }
+                """
+                traffic light state
+                """
+                enum Light {
+                  GREEN
+                  """
+                  slow down
+                  """
+                  YELLOW
+                  RED
+                  BLINKING
+                  OFF @deprecated(reason: "use RED")
+                }
+                type Query {
+                  next(light: Light!): Light!
+                  all: [Light!]!
+                  can_cross(input: CrossingInput!): Boolean!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                next(light: FLASHING_YELLOW)
+            }
+        "#;
+        let req = async_graphql::Request::new(query).root_value(FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        assert_eq!(res.errors.len(), 1);
+
+        let query = r#"
+            query {
+                yellow: next(light: GREEN)
+                blinking: next(light: OFF)
+                all
+                can_cross(input: { lights: [GREEN, YELLOW] })
+            }
+        "#;
+        let req = async_graphql::Request::new(query).root_value(FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "yellow": "YELLOW",
+                "blinking": "BLINKING",
+                "all": ["GREEN", "YELLOW", "RED"],
+                "can_cross": false,
+            }),
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub mod attributes;
+pub mod remote;
+pub mod simple;
+pub mod utils;
@@ -0,0 +1,230 @@
+use crate::schema::enums::utils::{EnumDefinition, EnumItemDefinition};
+use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Enum, Object, Register, Registry};
+use async_graphql::dynamic::FieldValue;
+use async_graphql::{dynamic, Context};
+
+// external crate
+mod compass {
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Direction {
+        North,
+        East,
+        South,
+        West,
+    }
+
+    impl Direction {
+        pub fn opposite(&self) -> Self {
+            match self {
+                Direction::North => Direction::South,
+                Direction::East => Direction::West,
+                Direction::South => Direction::North,
+                Direction::West => Direction::East,
+            }
+        }
+    }
+}
+
+// user
+// mark as enum
+// mark as remote = "compass::Direction"
+// mark rename_items = "SCREAMING_SNAKE_CASE"
+enum DirectionDef {
+    North,
+    East,
+    South,
+    West,
+}
+
+struct Query;
+
+impl Query {
+    async fn resolve_opposite(&self, direction: compass::Direction) -> compass::Direction {
+        direction.opposite()
+    }
+    async fn resolve_maybe_opposite(
+        &self,
+        direction: Option<compass::Direction>,
+    ) -> Option<compass::Direction> {
+        direction.map(|direction| direction.opposite())
+    }
+}
+
+// generated
+
+// ensure the mirror covers every variant of the remote enum
+impl From<compass::Direction> for DirectionDef {
+    fn from(value: compass::Direction) -> Self {
+        match value {
+            compass::Direction::North => DirectionDef::North,
+            compass::Direction::East => DirectionDef::East,
+            compass::Direction::South => DirectionDef::South,
+            compass::Direction::West => DirectionDef::West,
+        }
+    }
+}
+
+impl DirectionDef {
+    // the items are defined from the mirror, the remote enum has no const constructor to
+    // rely on
+    const fn into_remote(self) -> compass::Direction {
+        match self {
+            DirectionDef::North => compass::Direction::North,
+            DirectionDef::East => compass::Direction::East,
+            DirectionDef::South => compass::Direction::South,
+            DirectionDef::West => compass::Direction::West,
+        }
+    }
+}
+
+impl Enum for compass::Direction {
+    const NAME: &'static str = "Direction";
+}
+
+impl EnumDefinition for compass::Direction {
+    const ITEMS: &'static [EnumItemDefinition<Self>] = &[
+        EnumItemDefinition::new("NORTH", DirectionDef::North.into_remote()),
+        EnumItemDefinition::new("EAST", DirectionDef::East.into_remote()),
+        EnumItemDefinition::new("SOUTH", DirectionDef::South.into_remote()),
+        EnumItemDefinition::new("WEST", DirectionDef::West.into_remote()),
+    ];
+}
+
+impl Register for DirectionDef {
+    fn register(registry: Registry) -> Registry {
+        registry.register_enum(<compass::Direction as EnumDefinition>::create_enum())
+    }
+}
+
+impl<'a> ResolveOwned<'a> for compass::Direction {
+    fn resolve_owned(self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(self.to_field_value()))
+    }
+}
+
+impl<'a> ResolveRef<'a> for compass::Direction {
+    fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(self.to_field_value()))
+    }
+}
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define opposite field
+        let opposite_field = dynamic::Field::new(
+            "opposite",
+            dynamic::TypeRef::named_nn(<compass::Direction as Enum>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = compass::Direction::parse_value(ctx.args.try_get("direction")?)?;
+                    let value = parent.resolve_opposite(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let opposite_field = opposite_field.argument(dynamic::InputValue::new(
+            "direction",
+            dynamic::TypeRef::named_nn(<compass::Direction as Enum>::NAME),
+        ));
+        let object_type = object_type.field(opposite_field);
+
+        // define maybe_opposite field
+        let maybe_opposite_field = dynamic::Field::new(
+            "maybe_opposite",
+            dynamic::TypeRef::named(<compass::Direction as Enum>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = match ctx.args.get("direction") {
+                        Some(value) if !value.is_null() => {
+                            Some(compass::Direction::parse_value(value)?)
+                        }
+                        _ => None,
+                    };
+                    let value = parent.resolve_maybe_opposite(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let maybe_opposite_field = maybe_opposite_field.argument(dynamic::InputValue::new(
+            "direction",
+            dynamic::TypeRef::named(<compass::Direction as Enum>::NAME),
+        ));
+        let object_type = object_type.field(maybe_opposite_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new()
+        .register::<Query>()
+        .register::<DirectionDef>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                enum Direction {
+                  NORTH
+                  EAST
+                  SOUTH
+                  WEST
+                }
+                type Query {
+                  opposite(direction: Direction!): Direction!
+                  maybe_opposite(direction: Direction): Direction
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                opposite(direction: NORTH)
+                maybe_opposite(direction: EAST)
+                none: maybe_opposite
+            }
+        "#;
+        let req = async_graphql::Request::new(query).root_value(FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "opposite": "SOUTH",
+                "maybe_opposite": "WEST",
+                "none": null,
+            }),
+        );
+    }
+}
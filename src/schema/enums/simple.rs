@@ -1,12 +1,15 @@
+use crate::schema::enums::utils::{EnumDefinition, EnumItemDefinition};
 use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
 use crate::schema::registry::Object;
 use crate::schema::registry::{Enum, Register, Registry};
-use async_graphql::dynamic::{EnumItem, FieldValue};
+use async_graphql::dynamic::FieldValue;
 use async_graphql::{dynamic, Context};
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 // user
-#[derive(Deserialize, Clone)]
+// mark as enum
+#[derive(Clone)]
 pub enum Direction {
     North,
     East,
@@ -33,41 +36,36 @@ impl Enum for Direction {
     const NAME: &'static str = "Direction";
 }
 
+impl EnumDefinition for Direction {
+    const ITEMS: &'static [EnumItemDefinition<Self>] = &[
+        EnumItemDefinition::new("North", Direction::North),
+        EnumItemDefinition::new("East", Direction::East),
+        EnumItemDefinition::new("South", Direction::South),
+        EnumItemDefinition::new("West", Direction::West),
+    ];
+}
+
 impl Register for Direction {
     fn register(registry: Registry) -> Registry {
-        // define Direction enum
-        let direction_enum = async_graphql::dynamic::Enum::new("Direction");
-
-        let direction_enum = direction_enum.item(EnumItem::new("North"));
-        let direction_enum = direction_enum.item(EnumItem::new("East"));
-        let direction_enum = direction_enum.item(EnumItem::new("South"));
-        let direction_enum = direction_enum.item(EnumItem::new("West"));
-
-        // register Direction enum
-        registry.register_enum(direction_enum)
+        registry.register_enum(<Self as EnumDefinition>::create_enum())
     }
 }
 
-impl From<&Direction> for async_graphql::Value {
-    fn from(direction: &Direction) -> Self {
-        match direction {
-            Direction::North => async_graphql::Value::Enum(async_graphql::Name::new("North")),
-            Direction::East => async_graphql::Value::Enum(async_graphql::Name::new("East")),
-            Direction::South => async_graphql::Value::Enum(async_graphql::Name::new("South")),
-            Direction::West => async_graphql::Value::Enum(async_graphql::Name::new("West")),
-        }
+impl<'de> Deserialize<'de> for Direction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <Self as EnumDefinition>::deserialize_item(deserializer)
     }
 }
 
 impl<'a> ResolveOwned<'a> for Direction {
     fn resolve_owned(self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
-        Ok(Some(FieldValue::value(&self)))
+        Ok(Some(self.to_field_value()))
     }
 }
 
 impl<'a> ResolveRef<'a> for Direction {
     fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
-        Ok(Some(FieldValue::value(self)))
+        Ok(Some(self.to_field_value()))
     }
 }
 
@@ -86,9 +84,8 @@ impl Register for Query {
             async_graphql::dynamic::TypeRef::named_nn(Direction::NAME),
             |ctx| {
                 async_graphql::dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
-                    let arg_0 = ctx.args.try_get("direction")?.deserialize()?;
-                    // use borrowed_any because Direction is not value
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = Direction::parse_value(ctx.args.try_get("direction")?)?;
                     let value = parent.resolve_next(&ctx, arg_0).await;
                     value.resolve_owned(&ctx)
                 })
@@ -0,0 +1,119 @@
+use crate::schema::registry::{Enum, Registry};
+use async_graphql::dynamic::{self, FieldValue, ValueAccessor};
+use async_graphql::{Name, Value};
+use serde::{Deserialize, Deserializer};
+use std::mem::discriminant;
+
+pub enum EnumItemDeprecation {
+    NoDeprecated,
+    Deprecated(Option<&'static str>),
+}
+
+/// one graphql item of a rust enum, the only place where the item name is defined
+pub struct EnumItemDefinition<T: 'static> {
+    pub name: &'static str,
+    pub value: T,
+    pub description: Option<&'static str>,
+    pub deprecation: EnumItemDeprecation,
+    /// the item is dropped when the feature is disabled
+    pub feature: Option<&'static str>,
+}
+
+impl<T> EnumItemDefinition<T> {
+    pub const fn new(name: &'static str, value: T) -> Self {
+        Self {
+            name,
+            value,
+            description: None,
+            deprecation: EnumItemDeprecation::NoDeprecated,
+            feature: None,
+        }
+    }
+}
+
+/// enum type, schema items, output and input conversion are derived from `ITEMS`
+pub trait EnumDefinition: Enum + Clone + Sized + 'static {
+    const DESCRIPTION: Option<&'static str> = None;
+    const ITEMS: &'static [EnumItemDefinition<Self>];
+
+    fn create_enum() -> dynamic::Enum {
+        Self::create_enum_with(|_| true)
+    }
+
+    /// enum with the items of enabled features
+    fn create_enum_for(registry: &Registry) -> dynamic::Enum {
+        Self::create_enum_with(|feature| registry.feature_enabled(feature))
+    }
+
+    fn create_enum_with(enabled: impl Fn(&str) -> bool) -> dynamic::Enum {
+        let enum_type = dynamic::Enum::new(<Self as Enum>::NAME);
+        let enum_type = match Self::DESCRIPTION {
+            Some(description) => enum_type.description(description),
+            None => enum_type,
+        };
+        let items = Self::ITEMS
+            .iter()
+            .filter(|item| item.feature.is_none_or(&enabled));
+        items.fold(enum_type, |enum_type, item| {
+            let enum_item = dynamic::EnumItem::new(item.name);
+            let enum_item = match item.description {
+                Some(description) => enum_item.description(description),
+                None => enum_item,
+            };
+            let enum_item = match item.deprecation {
+                EnumItemDeprecation::NoDeprecated => enum_item,
+                EnumItemDeprecation::Deprecated(reason) => enum_item.deprecation(reason),
+            };
+            enum_type.item(enum_item)
+        })
+    }
+
+    fn item_name(&self) -> &'static str {
+        // only unit variants are supported, so the discriminant identifies the item
+        Self::ITEMS
+            .iter()
+            .find(|item| discriminant(&item.value) == discriminant(self))
+            .map(|item| item.name)
+            .unwrap_or_else(|| panic!("enum {} has a variant without item", <Self as Enum>::NAME))
+    }
+
+    fn from_item_name(name: &str) -> Option<Self> {
+        Self::ITEMS
+            .iter()
+            .find(|item| item.name == name)
+            .map(|item| item.value.clone())
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Enum(Name::new(self.item_name()))
+    }
+
+    fn to_field_value<'a>(&self) -> FieldValue<'a> {
+        FieldValue::value(self.to_value())
+    }
+
+    fn parse_value(value: ValueAccessor) -> async_graphql::Result<Self> {
+        let name = value.enum_name()?;
+        Self::from_item_name(name).ok_or_else(|| unknown_item::<Self>(name).into())
+    }
+
+    fn deserialize_item<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::from_item_name(&name)
+            .ok_or_else(|| serde::de::Error::custom(unknown_item::<Self>(&name)))
+    }
+}
+
+fn unknown_item<T: EnumDefinition>(name: &str) -> String {
+    let expected = T::ITEMS
+        .iter()
+        .map(|item| item.name)
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "unknown item `{}` for enum {}, expected one of {}",
+        name,
+        <T as Enum>::NAME,
+        expected
+    )
+}
@@ -0,0 +1,2 @@
+pub mod typed;
+pub mod utils;
@@ -0,0 +1,224 @@
+use crate::schema::errors::utils::ErrorCode;
+use crate::schema::output_types::utils::ResolveOwned;
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Object, Register, Registry};
+use async_graphql::dynamic;
+
+// user
+
+// mark as error
+#[derive(thiserror::Error, Debug)]
+enum EmailError {
+    // mark as code INVALID_EMAIL, field email
+    #[error("invalid email {0}")]
+    Invalid(String),
+    // mark as code EMAIL_SERVICE_UNAVAILABLE, retryable
+    #[error("email service is unavailable")]
+    Unavailable,
+}
+
+// mark as object
+struct Query;
+
+impl Query {
+    // mark as errors EmailError
+    async fn resolve_check_email(&self, email: String) -> Result<bool, EmailError> {
+        match email.as_str() {
+            "down@example.com" => Err(EmailError::Unavailable),
+            email if email.contains('@') => Ok(true),
+            email => Err(EmailError::Invalid(email.to_string())),
+        }
+    }
+    async fn resolve_crash(&self) -> Result<String, std::io::Error> {
+        Err(std::io::Error::other("connection to 10.0.0.1 refused"))
+    }
+}
+
+// generated
+
+impl ErrorCode for EmailError {
+    const CODES: &'static [&'static str] = &["INVALID_EMAIL", "EMAIL_SERVICE_UNAVAILABLE"];
+
+    fn code(&self) -> &'static str {
+        match self {
+            EmailError::Invalid(_) => "INVALID_EMAIL",
+            EmailError::Unavailable => "EMAIL_SERVICE_UNAVAILABLE",
+        }
+    }
+
+    fn retryable(&self) -> bool {
+        matches!(self, EmailError::Unavailable)
+    }
+
+    fn field(&self) -> Option<&'static str> {
+        match self {
+            EmailError::Invalid(_) => Some("email"),
+            EmailError::Unavailable => None,
+        }
+    }
+}
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define check_email field
+        let check_email_field = dynamic::Field::new(
+            "check_email",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::BOOLEAN),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("email")?.deserialize()?;
+                    let value = parent
+                        .resolve_check_email(arg_0)
+                        .await
+                        .map_err(ErrorCode::into_error);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let check_email_field = check_email_field.argument(dynamic::InputValue::new(
+            "email",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        ));
+        let object_type = object_type.field(check_email_field);
+
+        // define crash field
+        let crash_field = dynamic::Field::new(
+            "crash",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_crash().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(crash_field);
+
+        registry
+            .register_object(object_type)
+            .register_error_codes::<EmailError>(<Self as Object>::NAME, "check_email")
+    }
+}
+
+fn create_schema_with(registry: Registry) -> dynamic::Schema {
+    let registry = registry.register::<Query>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    create_schema_with(Registry::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::errors::utils::mask_internal_errors;
+    use async_graphql::dynamic::DynamicRequestExt;
+    use std::sync::{Arc, Mutex};
+
+    async fn execute(schema: &dynamic::Schema, query: &str) -> serde_json::Value {
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        serde_json::to_value(res.errors).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_extensions() {
+        let schema = create_schema();
+        let errors = execute(&schema, r#"{ check_email(email: "nope") }"#).await;
+        assert_eq!(
+            errors,
+            serde_json::json!([{
+                "message": "invalid email nope",
+                "locations": [{ "line": 1, "column": 3 }],
+                "extensions": { "code": "INVALID_EMAIL", "retryable": false, "field": "email" },
+            }]),
+        );
+        let errors = execute(&schema, r#"{ check_email(email: "down@example.com") }"#).await;
+        assert_eq!(
+            errors[0]["extensions"],
+            serde_json::json!({ "code": "EMAIL_SERVICE_UNAVAILABLE", "retryable": true }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_hook() {
+        let logged = Arc::new(Mutex::new(Vec::new()));
+        let log = logged.clone();
+        let schema = create_schema_with(Registry::new().error_hook(move |err| {
+            log.lock().unwrap().push(err.message.clone());
+            mask_internal_errors(err)
+        }));
+
+        let errors = execute(&schema, "{ crash }").await;
+        assert_eq!(errors[0]["message"], "internal error");
+        let errors = execute(&schema, r#"{ check_email(email: "nope") }"#).await;
+        assert_eq!(errors[0]["message"], "invalid email nope");
+        assert_eq!(
+            *logged.lock().unwrap(),
+            vec!["connection to 10.0.0.1 refused", "invalid email nope"],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_hook_invalid_query() {
+        let logged = Arc::new(Mutex::new(Vec::new()));
+        let log = logged.clone();
+        let schema = create_schema_with(Registry::new().error_hook(move |err| {
+            log.lock().unwrap().push(err.message.clone());
+            mask_internal_errors(err)
+        }));
+
+        let errors = execute(&schema, "{ missing }").await;
+        assert_eq!(
+            errors,
+            serde_json::json!([{
+                "message": "Unknown field \"missing\" on type \"Query\".",
+                "locations": [{ "line": 1, "column": 3 }],
+            }]),
+        );
+        let errors = execute(&schema, "{ crash").await;
+        assert_eq!(
+            errors[0]["locations"],
+            serde_json::json!([{ "line": 1, "column": 8 }])
+        );
+        assert_ne!(errors[0]["message"], "internal error");
+        assert!(logged.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_error_source() {
+        let sources = Arc::new(Mutex::new(Vec::new()));
+        let log = sources.clone();
+        let schema = create_schema_with(Registry::new().error_hook(move |err| {
+            if let Some(source) = err.source::<EmailError>() {
+                log.lock().unwrap().push(format!("{:?}", source));
+            }
+            err
+        }));
+        execute(&schema, r#"{ check_email(email: "nope") }"#).await;
+        assert_eq!(*sources.lock().unwrap(), vec![r#"Invalid("nope")"#]);
+    }
+
+    #[test]
+    fn test_error_catalog() {
+        let registry = Registry::new().register::<Query>();
+        assert_eq!(
+            registry.error_catalog(),
+            vec![(
+                "Query.check_email".to_string(),
+                vec!["INVALID_EMAIL", "EMAIL_SERVICE_UNAVAILABLE"],
+            )],
+        );
+    }
+}
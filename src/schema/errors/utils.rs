@@ -0,0 +1,80 @@
+use async_graphql::extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute};
+use async_graphql::{ErrorExtensions, Response, ServerError};
+use std::sync::Arc;
+
+/// domain error with a stable code, e.g. a `thiserror` enum, converted to a graphql error
+/// with `code`, `retryable` and `field` extensions
+pub trait ErrorCode: std::fmt::Display + Send + Sync + Sized + 'static {
+    /// codes of all variants, listed in the error catalog of the registry
+    const CODES: &'static [&'static str];
+
+    fn code(&self) -> &'static str;
+
+    fn retryable(&self) -> bool {
+        false
+    }
+
+    /// input field causing the error
+    fn field(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// the original error is kept as source, the error hook can downcast it
+    fn into_error(self) -> async_graphql::Error {
+        let code = self.code();
+        let retryable = self.retryable();
+        let field = self.field();
+        async_graphql::Error::new_with_source(self).extend_with(|_, extensions| {
+            extensions.set("code", code);
+            extensions.set("retryable", retryable);
+            if let Some(field) = field {
+                extensions.set("field", field);
+            }
+        })
+    }
+}
+
+/// called with every error of the execution of a request, see `Registry::error_hook`
+pub type ErrorHook = Arc<dyn Fn(ServerError) -> ServerError + Send + Sync>;
+
+/// error hook hiding the message of errors without a code
+pub fn mask_internal_errors(err: ServerError) -> ServerError {
+    let has_code = err
+        .extensions
+        .as_ref()
+        .is_some_and(|extensions| extensions.get("code").is_some());
+    if has_code {
+        return err;
+    }
+    ServerError {
+        message: "internal error".to_string(),
+        ..err
+    }
+}
+
+pub(crate) struct ErrorHookExtension(pub ErrorHook);
+
+impl ExtensionFactory for ErrorHookExtension {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(ErrorHookExtension(self.0.clone()))
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for ErrorHookExtension {
+    // parse and validation errors are returned before the execution and are not hooked
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let mut response = next.run(ctx, operation_name).await;
+        response.errors = response
+            .errors
+            .into_iter()
+            .map(|err| (self.0)(err))
+            .collect();
+        response
+    }
+}
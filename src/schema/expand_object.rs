@@ -1,4 +1,5 @@
 // user
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{ExpandObject, ExpandObjectContext, Object, Register, Registry};
 use async_graphql::dynamic::DynamicRequestExt;
 use async_graphql::dynamic::FieldValue;
@@ -65,23 +66,24 @@ impl Register for Query {
 
 impl Register for MeQuery {
     fn register(registry: Registry) -> Registry {
-        // define me field
-        let me_field = dynamic::Field::new("me", dynamic::TypeRef::named(User::NAME), |ctx| {
-            dynamic::FieldFuture::new(async move {
-                // todo: feature request for execute with root
-                // special case because Query is marked as root
-                let parent = ctx
-                    .parent_value
-                    .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-
-                Ok(Self::resolve_me(parent, &ctx)
-                    .await
-                    .map(FieldValue::owned_any))
-            })
-        });
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |query_object| query_object.field(me_field),
+            |query_object| {
+                // define me field
+                let me_field =
+                    dynamic::Field::new("me", dynamic::TypeRef::named(User::NAME), |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            // todo: feature request for execute with root
+                            // special case because Query is marked as root
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+
+                            Ok(Self::resolve_me(parent, &ctx)
+                                .await
+                                .map(FieldValue::owned_any))
+                        })
+                    });
+                query_object.field(me_field)
+            },
             ExpandObjectContext::new("MeQuery", "me"),
         )
     }
@@ -101,7 +103,7 @@ impl Register for User {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     Ok(Some(FieldValue::value(parent.resolve_id().to_owned())))
                 })
             },
@@ -114,7 +116,7 @@ impl Register for User {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     // use value because it's a String
                     Ok(Some(FieldValue::value(parent.resolve_name().to_owned())))
                 })
@@ -126,7 +128,7 @@ impl Register for User {
         let avatar_field =
             dynamic::Field::new("avatar", dynamic::TypeRef::named(Image::NAME), |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     // use map because avatar is optional
                     // use borrowed_any because Image is not value
                     Ok(parent
@@ -169,7 +171,7 @@ impl Register for Image {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     Ok(Some(FieldValue::value(parent.resolve_url().to_owned())))
                 })
             },
@@ -187,14 +189,22 @@ impl Image {
     }
 }
 
-pub fn create_schema() -> dynamic::Schema {
-    let registry = Registry::new()
+pub fn create_registry() -> Registry {
+    Registry::new()
         .register::<Query>()
         .register::<User>()
         .register::<MeQuery>()
-        .register::<Image>();
+        .register::<Image>()
+}
+
+/// root value of every request
+pub fn root_value() -> FieldValue<'static> {
+    FieldValue::owned_any(Query)
+}
+
+pub fn create_schema() -> dynamic::Schema {
     let schema = dynamic::Schema::build(Query::NAME, None, None);
-    registry.build_schema(schema).finish().unwrap()
+    create_registry().build_schema(schema).finish().unwrap()
 }
 
 #[cfg(test)]
@@ -258,4 +268,26 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_rebuild_registry() {
+        let registry = Registry::new()
+            .register::<Query>()
+            .register::<User>()
+            .register::<Image>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        let err = registry.clone().build_schema(schema).finish().unwrap_err();
+        assert_eq!(err.0, r#"Object "Query" must define one or more fields"#);
+
+        // every build replays the registrations
+        let registry = registry.register::<MeQuery>();
+        for _ in 0..2 {
+            let schema = dynamic::Schema::build(Query::NAME, None, None);
+            let schema = registry.clone().build_schema(schema).finish().unwrap();
+            assert_eq!(
+                normalize_schema(&schema.sdl()),
+                normalize_schema(&create_schema().sdl()),
+            );
+        }
+    }
 }
@@ -1,4 +1,5 @@
 // user
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{ExpandObject, ExpandObjectContext, Object, Register, Registry};
 use async_graphql::dynamic::FieldValue;
 use async_graphql::{dynamic, Context};
@@ -63,23 +64,24 @@ impl Register for Query {
 
 impl<'a> Register for MeQuery<'a> {
     fn register(registry: Registry) -> Registry {
-        // define me field
-        let me_field = dynamic::Field::new("me", dynamic::TypeRef::named(User::NAME), |ctx| {
-            dynamic::FieldFuture::new(async move {
-                // todo: feature request for execute with root
-                // special case because Query is marked as root
-                let parent = ctx
-                    .parent_value
-                    .try_downcast_ref::<<Self as ExpandObject>::Target>()?
-                    .into();
-
-                let value = MeQuery::resolve_me(&parent, &ctx).await;
-                Ok(Some(FieldValue::borrowed_any(value)))
-            })
-        });
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |query_object| query_object.field(me_field),
+            |query_object| {
+                // define me field
+                let me_field =
+                    dynamic::Field::new("me", dynamic::TypeRef::named(User::NAME), |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            // todo: feature request for execute with root
+                            // special case because Query is marked as root
+                            let parent =
+                                parent_value::<<Self as ExpandObject>::Target>(&ctx)?.into();
+
+                            let value = MeQuery::resolve_me(&parent, &ctx).await;
+                            Ok(Some(FieldValue::borrowed_any(value)))
+                        })
+                    });
+                query_object.field(me_field)
+            },
             ExpandObjectContext::new("MeQuery", "me"),
         )
     }
@@ -99,7 +101,7 @@ impl Register for User {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     Ok(Some(FieldValue::value(parent.resolve_id().to_owned())))
                 })
             },
@@ -112,7 +114,7 @@ impl Register for User {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     // use value because it's a String
                     Ok(Some(FieldValue::value(parent.resolve_name().to_owned())))
                 })
@@ -124,7 +126,7 @@ impl Register for User {
         let avatar_field =
             dynamic::Field::new("avatar", dynamic::TypeRef::named(Image::NAME), |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     // use map because avatar is optional
                     // use borrowed_any because Image is not value
                     Ok(parent
@@ -167,7 +169,7 @@ impl Register for Image {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     Ok(Some(FieldValue::value(parent.resolve_url().to_owned())))
                 })
             },
@@ -1,5 +1,6 @@
 // user
 
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{ExtendObject, Object, Register, Registry};
 use async_graphql::dynamic::DynamicRequestExt;
 use async_graphql::dynamic::FieldValue;
@@ -90,9 +91,7 @@ impl Register for MeQuery {
         // define me field
         let me_field = dynamic::Field::new("me", dynamic::TypeRef::named(User::NAME), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx
-                    .parent_value
-                    .try_downcast_ref::<<Self as ExtendObject>::Target>()?;
+                let parent = parent_value::<<Self as ExtendObject>::Target>(&ctx)?;
 
                 Ok(Self::resolve_me(parent, &ctx)
                     .await
@@ -111,9 +110,7 @@ impl Register for YouQuery {
         // define me field
         let me_field = dynamic::Field::new("you", dynamic::TypeRef::named(User::NAME), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx
-                    .parent_value
-                    .try_downcast_ref::<<Self as ExtendObject>::Target>()?;
+                let parent = parent_value::<<Self as ExtendObject>::Target>(&ctx)?;
 
                 Ok(Self::resolve_you(parent, &ctx)
                     .await
@@ -139,7 +136,7 @@ impl Register for User {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     Ok(Some(FieldValue::value(parent.resolve_id().to_owned())))
                 })
             },
@@ -152,7 +149,7 @@ impl Register for User {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     // use value because it's a String
                     Ok(Some(FieldValue::value(parent.resolve_name().to_owned())))
                 })
@@ -164,7 +161,7 @@ impl Register for User {
         let avatar_field =
             dynamic::Field::new("avatar", dynamic::TypeRef::named(Image::NAME), |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     // use map because avatar is optional
                     // use borrowed_any because Image is not value
                     Ok(parent
@@ -207,7 +204,7 @@ impl Register for Image {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     Ok(Some(FieldValue::value(parent.resolve_url().to_owned())))
                 })
             },
@@ -0,0 +1,411 @@
+use crate::schema::enums::utils::{EnumDefinition, EnumItemDefinition};
+use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Enum, Object, Register, Registry};
+use async_graphql::dynamic::FieldValue;
+use async_graphql::{dynamic, Context};
+
+// user
+
+// mark as enum
+#[derive(Clone, PartialEq)]
+enum Role {
+    Admin,
+    Member,
+    // mark as feature = "guests"
+    Guest,
+}
+
+// mark as object
+struct Query {
+    // mark as skip
+    users: Vec<User>,
+}
+
+impl Query {
+    async fn resolve_users(
+        &self,
+        // mark as feature = "roles"
+        role: Option<Role>,
+    ) -> Vec<&User> {
+        self.users
+            .iter()
+            .filter(|user| role.as_ref().is_none_or(|role| &user.role == role))
+            .collect()
+    }
+    // mark as feature = "home_feed"
+    async fn resolve_recommended(&self) -> Vec<Recommendation> {
+        vec![Recommendation {
+            title: "Getting started".to_string(),
+        }]
+    }
+}
+
+// mark as object
+struct User {
+    name: String,
+    role: Role,
+    // mark as feature = "bio"
+    bio: String,
+}
+
+impl User {
+    async fn resolve_name(&self) -> &String {
+        &self.name
+    }
+    async fn resolve_role(&self) -> &Role {
+        &self.role
+    }
+    async fn resolve_bio(&self) -> &String {
+        &self.bio
+    }
+}
+
+// mark as object
+// mark as feature = "recommendations"
+struct Recommendation {
+    title: String,
+}
+
+impl Recommendation {
+    async fn resolve_title(&self) -> &String {
+        &self.title
+    }
+}
+
+// generated
+
+impl Enum for Role {
+    const NAME: &'static str = "Role";
+}
+
+impl EnumDefinition for Role {
+    const ITEMS: &'static [EnumItemDefinition<Self>] = &[
+        EnumItemDefinition::new("ADMIN", Role::Admin),
+        EnumItemDefinition::new("MEMBER", Role::Member),
+        EnumItemDefinition {
+            feature: Some("guests"),
+            ..EnumItemDefinition::new("GUEST", Role::Guest)
+        },
+    ];
+}
+
+impl Register for Role {
+    fn register(registry: Registry) -> Registry {
+        let enum_type = <Self as EnumDefinition>::create_enum_for(&registry);
+        registry.register_enum(enum_type)
+    }
+}
+
+impl<'a> ResolveRef<'a> for Role {
+    fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(self.to_field_value()))
+    }
+}
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define users field
+        let users_field = dynamic::Field::new(
+            "users",
+            dynamic::TypeRef::named_nn_list_nn(User::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = match ctx.args.get("role") {
+                        Some(value) if !value.is_null() => Some(Role::parse_value(value)?),
+                        _ => None,
+                    };
+                    let value = parent.resolve_users(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let users_field = match registry.feature_enabled("roles") {
+            true => users_field.argument(dynamic::InputValue::new(
+                "role",
+                dynamic::TypeRef::named(<Role as Enum>::NAME),
+            )),
+            false => users_field,
+        };
+        let object_type = object_type.field(users_field);
+
+        // define recommended field
+        let recommended_field = dynamic::Field::new(
+            "recommended",
+            dynamic::TypeRef::named_nn_list_nn(Recommendation::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_recommended().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = match registry.feature_enabled("home_feed") {
+            true => object_type.field(recommended_field),
+            false => object_type,
+        };
+        let registry = match registry.feature_enabled("home_feed") {
+            true => registry.reference_type("Query.recommended", Recommendation::NAME),
+            false => registry,
+        };
+
+        registry
+            .reference_type("Query.users", User::NAME)
+            .register_object(object_type)
+    }
+}
+
+impl Object for User {
+    const NAME: &'static str = "User";
+}
+
+impl<'a> ResolveRef<'a> for User {
+    fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::borrowed_any(self)))
+    }
+}
+
+impl Register for User {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define name field
+        let name_field = dynamic::Field::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_name().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(name_field);
+
+        // define role field
+        let role_field = dynamic::Field::new(
+            "role",
+            dynamic::TypeRef::named_nn(<Role as Enum>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_role().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(role_field);
+
+        // define bio field
+        let bio_field = dynamic::Field::new(
+            "bio",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_bio().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = match registry.feature_enabled("bio") {
+            true => object_type.field(bio_field),
+            false => object_type,
+        };
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Object for Recommendation {
+    const NAME: &'static str = "Recommendation";
+}
+
+impl<'a> ResolveOwned<'a> for Recommendation {
+    fn resolve_owned(self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::owned_any(self)))
+    }
+}
+
+impl Register for Recommendation {
+    fn register(registry: Registry) -> Registry {
+        if !registry.feature_enabled("recommendations") {
+            return registry.disable_type(<Self as Object>::NAME, "recommendations");
+        }
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define title field
+        let title_field = dynamic::Field::new(
+            "title",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_title().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(title_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+fn create_registry() -> Registry {
+    Registry::new()
+        .register::<Role>()
+        .register::<Query>()
+        .register::<User>()
+        .register::<Recommendation>()
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    create_registry().finish_schema(schema).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::features::utils::enabled_features;
+    use crate::schema::handle::SchemaBuildError;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    fn create_schema_with(features: &[&'static str]) -> Result<dynamic::Schema, SchemaBuildError> {
+        let registry = create_registry().feature_flags(enabled_features(features.to_vec()));
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        registry.finish_schema(schema)
+    }
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                type Query {
+                  users(role: Role): [User!]!
+                  recommended: [Recommendation!]!
+                }
+                type Recommendation {
+                  title: String!
+                }
+                enum Role {
+                  ADMIN
+                  MEMBER
+                  GUEST
+                }
+                type User {
+                  name: String!
+                  role: Role!
+                  bio: String!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn test_disabled() {
+        let schema = create_schema_with(&[]).unwrap();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                type Query {
+                  users: [User!]!
+                }
+                enum Role {
+                  ADMIN
+                  MEMBER
+                }
+                type User {
+                  name: String!
+                  role: Role!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let schema = create_schema_with(&["roles", "guests"]).unwrap();
+        let users = vec![
+            User {
+                name: "ann".to_string(),
+                role: Role::Admin,
+                bio: "".to_string(),
+            },
+            User {
+                name: "bob".to_string(),
+                role: Role::Guest,
+                bio: "".to_string(),
+            },
+        ];
+        let query = "{ users(role: GUEST) { name role } }";
+        let req =
+            async_graphql::Request::new(query).root_value(FieldValue::owned_any(Query { users }));
+        let res = schema.execute(req).await;
+        assert_eq!(
+            res.data.into_json().unwrap(),
+            serde_json::json!({ "users": [{ "name": "bob", "role": "GUEST" }] }),
+        );
+
+        // the disabled field is rejected by the schema
+        let schema = create_schema_with(&["roles"]).unwrap();
+        let req = async_graphql::Request::new("{ users { bio } }")
+            .root_value(FieldValue::owned_any(Query { users: vec![] }));
+        let res = schema.execute(req).await;
+        assert_eq!(
+            res.errors[0].message,
+            r#"Unknown field "bio" on type "User"."#,
+        );
+    }
+
+    #[test]
+    fn test_dangling_reference() {
+        // the feed refers to recommendations of a disabled feature
+        let err = create_schema_with(&["home_feed"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"Type "Recommendation" is disabled by feature recommendations but still referenced by Query.recommended"#,
+        );
+        assert!(matches!(err, SchemaBuildError::Registry(_)));
+        assert!(create_schema_with(&["home_feed", "recommendations"]).is_ok());
+    }
+
+    #[test]
+    fn test_flags_detached() {
+        let registry =
+            create_registry().register_object(dynamic::Object::new(Recommendation::NAME));
+        let registry = registry.feature_flags(enabled_features(Vec::<&str>::new()));
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        let err = registry.finish_schema(schema).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Can't apply feature flags, registered outside of a step: Recommendation",
+        );
+        assert!(matches!(err, SchemaBuildError::Registry(_)));
+    }
+}
@@ -0,0 +1,15 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// decides whether a feature is enabled, see `Registry::feature_flags`
+pub type FeatureFlags = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// feature flags enabling the listed features, e.g. read from the config
+pub fn enabled_features<I, S>(features: I) -> impl Fn(&str) -> bool + Send + Sync + 'static
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let features = features.into_iter().map(Into::into).collect::<HashSet<_>>();
+    move |feature| features.contains(feature)
+}
@@ -0,0 +1,283 @@
+use crate::schema::registry::Registry;
+use async_graphql::dynamic::{self, DynamicRequest, SchemaBuilder};
+use async_graphql::Response;
+use futures_util::stream::{BoxStream, StreamExt};
+use std::sync::RwLock;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SchemaBuildError {
+    /// the registry is invalid, e.g. an expanded object is missing
+    #[error("{0}")]
+    Registry(String),
+    /// async-graphql rejected the schema
+    #[error("{0}")]
+    Schema(String),
+}
+
+/// schema serving requests while a new version is built, requests run on the schema that was
+/// current when they started
+pub struct SchemaHandle {
+    current: RwLock<(u64, dynamic::Schema)>,
+}
+
+impl SchemaHandle {
+    pub fn new(schema: dynamic::Schema) -> Self {
+        Self {
+            current: RwLock::new((1, schema)),
+        }
+    }
+
+    /// build the first version from a registry
+    pub fn build(
+        registry: Registry,
+        schema_builder: SchemaBuilder,
+    ) -> Result<Self, SchemaBuildError> {
+        registry.finish_schema(schema_builder).map(Self::new)
+    }
+
+    /// current version, starts at 1 and increases with every swap
+    pub fn version(&self) -> u64 {
+        self.current.read().unwrap().0
+    }
+
+    /// current schema, kept alive by the caller across later swaps
+    pub fn schema(&self) -> dynamic::Schema {
+        self.current.read().unwrap().1.clone()
+    }
+
+    pub async fn execute(&self, request: impl Into<DynamicRequest>) -> Response {
+        self.schema().execute(request).await
+    }
+
+    /// the subscription keeps the schema it started with
+    pub fn execute_stream(
+        &self,
+        request: impl Into<DynamicRequest>,
+    ) -> BoxStream<'static, Response> {
+        let schema = self.schema();
+        let request = request.into();
+        async_graphql::async_stream::stream! {
+            let mut stream = schema.execute_stream(request);
+            while let Some(response) = stream.next().await {
+                yield response;
+            }
+        }
+        .boxed()
+    }
+
+    /// replace the schema, returns the new version
+    pub fn swap(&self, schema: dynamic::Schema) -> u64 {
+        let mut current = self.current.write().unwrap();
+        *current = (current.0 + 1, schema);
+        current.0
+    }
+
+    /// build a new version from an updated registry and swap it in. The current schema keeps
+    /// serving when the build fails
+    pub fn rebuild(
+        &self,
+        registry: Registry,
+        schema_builder: SchemaBuilder,
+    ) -> Result<u64, SchemaBuildError> {
+        let schema = registry.finish_schema(schema_builder)?;
+        Ok(self.swap(schema))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::registry::ExpandObjectContext;
+    use async_graphql::Value;
+    use std::sync::Arc;
+    use tokio::sync::Semaphore;
+
+    fn field(name: &str, value: &'static str) -> dynamic::Field {
+        dynamic::Field::new(
+            name,
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            move |_| dynamic::FieldFuture::new(async move { Ok(Some(Value::from(value))) }),
+        )
+    }
+
+    fn registry<F>(fields: F) -> Registry
+    where
+        F: Fn() -> Vec<dynamic::Field> + Send + Sync + 'static,
+    {
+        Registry::new().register_with(move |registry| {
+            let query = fields()
+                .into_iter()
+                .fold(dynamic::Object::new("Query"), |query, field| {
+                    query.field(field)
+                });
+            registry.register_object(query)
+        })
+    }
+
+    fn schema_builder() -> SchemaBuilder {
+        dynamic::Schema::build("Query", None, None)
+    }
+
+    async fn execute(handle: &SchemaHandle, query: &str) -> serde_json::Value {
+        serde_json::to_value(handle.execute(query).await).unwrap()
+    }
+
+    /// registry with a `ticks` subscription yielding the values
+    fn ticks(values: &'static [i32]) -> Registry {
+        registry(|| vec![field("hello", "world")]).register_with(move |registry| {
+            let ticks_field = dynamic::SubscriptionField::new(
+                "ticks",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::INT),
+                move |_| {
+                    dynamic::SubscriptionFieldFuture::new(async move {
+                        Ok(futures_util::stream::iter(
+                            values.iter().map(|value| Ok(Value::from(*value))),
+                        ))
+                    })
+                },
+            );
+            registry.register_subscription(
+                dynamic::Subscription::new("Subscription").field(ticks_field),
+            )
+        })
+    }
+
+    fn subscription_builder() -> SchemaBuilder {
+        dynamic::Schema::build("Query", None, Some("Subscription"))
+    }
+
+    #[tokio::test]
+    async fn test_rebuild() {
+        let registry = registry(|| vec![field("hello", "world")]);
+        let handle = SchemaHandle::build(registry.clone(), schema_builder()).unwrap();
+        assert_eq!(handle.version(), 1);
+        assert_eq!(
+            execute(&handle, "{ hello }").await,
+            serde_json::json!({ "data": { "hello": "world" } }),
+        );
+
+        // the same registry with one more field
+        let registry = registry.register_with(|registry| {
+            registry.update_object(
+                "Query",
+                |query| query.field(field("bye", "now")),
+                ExpandObjectContext::new("Bye", "bye"),
+            )
+        });
+        assert_eq!(handle.rebuild(registry, schema_builder()).unwrap(), 2);
+        assert_eq!(
+            execute(&handle, "{ hello bye }").await,
+            serde_json::json!({ "data": { "hello": "world", "bye": "now" } }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_in_flight() {
+        let permits = Arc::new(Semaphore::new(0));
+        let wait = permits.clone();
+        let slow = move || {
+            let wait = wait.clone();
+            vec![dynamic::Field::new(
+                "slow",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                move |_| {
+                    let wait = wait.clone();
+                    dynamic::FieldFuture::new(async move {
+                        wait.acquire().await.unwrap().forget();
+                        Ok(Some(Value::from("old")))
+                    })
+                },
+            )]
+        };
+        let handle = Arc::new(SchemaHandle::build(registry(slow), schema_builder()).unwrap());
+        let in_flight = tokio::spawn({
+            let handle = handle.clone();
+            async move { execute(&handle, "{ slow }").await }
+        });
+        tokio::task::yield_now().await;
+
+        handle
+            .rebuild(registry(|| vec![field("fast", "new")]), schema_builder())
+            .unwrap();
+        assert_eq!(
+            execute(&handle, "{ fast }").await,
+            serde_json::json!({ "data": { "fast": "new" } }),
+        );
+        permits.add_permits(1);
+        assert_eq!(
+            in_flight.await.unwrap(),
+            serde_json::json!({ "data": { "slow": "old" } }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscription() {
+        let handle = SchemaHandle::build(ticks(&[1, 2]), subscription_builder()).unwrap();
+        let started = handle
+            .execute_stream("subscription { ticks }")
+            .map(|response| serde_json::to_value(response).unwrap());
+
+        handle.rebuild(ticks(&[3]), subscription_builder()).unwrap();
+        assert_eq!(
+            started.collect::<Vec<_>>().await,
+            vec![
+                serde_json::json!({ "data": { "ticks": 1 } }),
+                serde_json::json!({ "data": { "ticks": 2 } }),
+            ],
+        );
+        let responses = handle
+            .execute_stream("subscription { ticks }")
+            .map(|response| serde_json::to_value(response).unwrap());
+        assert_eq!(
+            responses.collect::<Vec<_>>().await,
+            vec![serde_json::json!({ "data": { "ticks": 3 } })],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected() {
+        let registry = registry(|| vec![field("hello", "world")]);
+        let handle = SchemaHandle::build(registry.clone(), schema_builder()).unwrap();
+
+        // the expanded object is missing
+        let registry = registry.update_object(
+            "User",
+            |object| object,
+            ExpandObjectContext::new("UserBio", "bio"),
+        );
+        let err = handle.rebuild(registry, schema_builder()).unwrap_err();
+        assert!(matches!(err, SchemaBuildError::Registry(_)));
+        assert_eq!(
+            err.to_string(),
+            r#"Can't find object: "Can't find User when defining bio in UserBio""#,
+        );
+
+        // the query root is missing
+        let err = handle
+            .rebuild(Registry::new(), schema_builder())
+            .unwrap_err();
+        assert!(matches!(err, SchemaBuildError::Schema(_)));
+        assert_eq!(err.to_string(), r#"Type "Query" not found"#);
+
+        assert_eq!(handle.version(), 1);
+        assert_eq!(
+            execute(&handle, "{ hello }").await,
+            serde_json::json!({ "data": { "hello": "world" } }),
+        );
+    }
+
+    #[test]
+    fn test_clone_detached() {
+        let registry = registry(|| vec![field("hello", "world")])
+            .register_object(dynamic::Object::new("User").field(field("name", "ann")));
+        assert_eq!(
+            registry
+                .clone()
+                .try_build_schema(schema_builder())
+                .err()
+                .unwrap(),
+            "Can't clone registry, registered outside of a step: User",
+        );
+        assert!(registry.try_build_schema(schema_builder()).is_ok());
+    }
+}
@@ -1,11 +1,16 @@
+use crate::schema::registry::Registry;
 use async_graphql::{dynamic::*, Value};
 
-pub fn create_schema() -> Schema {
+fn create_query() -> Object {
     let query = Object::new("Query");
     let field = Field::new("hello", TypeRef::named_nn(TypeRef::STRING), |_ctx| {
         FieldFuture::new(async move { Ok(Some(Value::from("world"))) })
     });
-    let query = query.field(field);
+    query.field(field)
+}
+
+pub fn create_schema() -> Schema {
+    let query = create_query();
 
     let schema = Schema::build(query.type_name(), None, None);
     let schema = schema.register(query);
@@ -13,6 +18,11 @@ pub fn create_schema() -> Schema {
     schema.finish().unwrap()
 }
 
+/// the same schema as a registry, e.g. for the server
+pub fn create_registry() -> Registry {
+    Registry::new().register_with(|registry| registry.register_object(create_query()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
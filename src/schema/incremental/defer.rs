@@ -0,0 +1,408 @@
+use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{ExpandObject, ExpandObjectContext, Object, Register, Registry};
+use async_graphql::dynamic::FieldValue;
+use async_graphql::{dynamic, Context};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+// user
+
+/// slow service, every bio waits for a permit
+struct BioService {
+    permits: Semaphore,
+}
+
+impl BioService {
+    async fn bio(&self, name: &str) -> String {
+        self.permits.acquire().await.unwrap().forget();
+        format!("{} writes software", name)
+    }
+}
+
+// mark as root
+// mark as object
+struct Query {
+    // mark as skip
+    me: User,
+}
+
+impl Query {
+    async fn resolve_me(&self) -> &User {
+        &self.me
+    }
+}
+
+// mark as object
+struct User {
+    // mark as skip
+    name: String,
+    // mark as skip
+    friends: Vec<User>,
+}
+
+impl User {
+    async fn resolve_name(&self) -> &String {
+        &self.name
+    }
+    async fn resolve_friends(&self) -> &Vec<User> {
+        &self.friends
+    }
+}
+
+struct UserBio;
+
+impl ExpandObject for UserBio {
+    type Target = User;
+}
+
+impl UserBio {
+    // mark User as Target
+    async fn resolve_bio(parent: &User, ctx: &Context<'_>) -> String {
+        ctx.data_unchecked::<Arc<BioService>>()
+            .bio(&parent.name)
+            .await
+    }
+}
+
+// generated
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define me field
+        let me_field = dynamic::Field::new("me", dynamic::TypeRef::named_nn(User::NAME), |ctx| {
+            dynamic::FieldFuture::new(async move {
+                let parent = parent_value::<Self>(&ctx)?;
+                let value = parent.resolve_me().await;
+                ResolveOwned::resolve_owned(value, &ctx)
+            })
+        });
+        let object_type = object_type.field(me_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Object for User {
+    const NAME: &'static str = "User";
+}
+
+impl<'a> ResolveRef<'a> for User {
+    fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::borrowed_any(self)))
+    }
+}
+
+impl Register for User {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define name field
+        let name_field = dynamic::Field::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_name().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(name_field);
+
+        // define friends field
+        let friends_field = dynamic::Field::new(
+            "friends",
+            dynamic::TypeRef::named_nn_list_nn(User::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_friends().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(friends_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Register for UserBio {
+    fn register(registry: Registry) -> Registry {
+        registry.update_object(
+            <<Self as ExpandObject>::Target as Object>::NAME,
+            |object_type| {
+                // define bio field
+                let bio_field = dynamic::Field::new(
+                    "bio",
+                    dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                    |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = Self::resolve_bio(parent, &ctx).await;
+                            ResolveOwned::resolve_owned(value, &ctx)
+                        })
+                    },
+                );
+                object_type.field(bio_field)
+            },
+            ExpandObjectContext::new("UserBio", "bio"),
+        )
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new()
+        .register::<Query>()
+        .register::<User>()
+        .register::<UserBio>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::incremental::utils::{
+        execute_incremental, multipart_response, MULTIPART_CONTENT_TYPE,
+    };
+    use async_graphql::dynamic::DynamicRequestExt;
+    use futures_util::StreamExt;
+    use std::time::Duration;
+
+    fn user(name: &str, friends: Vec<User>) -> User {
+        User {
+            name: name.to_string(),
+            friends,
+        }
+    }
+
+    fn request(query: &str, variables: serde_json::Value) -> async_graphql::Request {
+        async_graphql::Request::new(query).variables(async_graphql::Variables::from_json(variables))
+    }
+
+    fn prepare(
+        service: Arc<BioService>,
+    ) -> impl Fn(async_graphql::Request) -> dynamic::DynamicRequest + Send + Sync + 'static {
+        move |request| {
+            let me = user("ann", vec![user("bob", vec![]), user("cid", vec![])]);
+            request
+                .data(service.clone())
+                .root_value(FieldValue::owned_any(Query { me }))
+        }
+    }
+
+    fn service(permits: usize) -> Arc<BioService> {
+        Arc::new(BioService {
+            permits: Semaphore::new(permits),
+        })
+    }
+
+    async fn collect(
+        query: &'static str,
+        variables: serde_json::Value,
+        service: Arc<BioService>,
+    ) -> Vec<serde_json::Value> {
+        let schema = create_schema();
+        execute_incremental(&schema, request(query, variables), prepare(service))
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_defer() {
+        let schema = create_schema();
+        let service = service(0);
+        let query = r#"{ me { name ... @defer(label: "bio") { bio } } }"#;
+        let mut payloads = execute_incremental(
+            &schema,
+            request(query, serde_json::json!({})),
+            prepare(service.clone()),
+        );
+
+        // the initial payload does not wait for the deferred bio
+        let initial = tokio::time::timeout(Duration::from_secs(5), payloads.next()).await;
+        assert_eq!(
+            initial.unwrap().unwrap(),
+            serde_json::json!({ "data": { "me": { "name": "ann" } }, "hasNext": true }),
+        );
+        service.permits.add_permits(1);
+        assert_eq!(
+            payloads.next().await.unwrap(),
+            serde_json::json!({
+                "incremental": [{
+                    "data": { "bio": "ann writes software" },
+                    "path": ["me"],
+                    "label": "bio",
+                }],
+                "hasNext": false,
+            }),
+        );
+        assert!(payloads.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_defer_fragment_in_list() {
+        let query = r#"
+            query Friends($first: Boolean!) {
+                me {
+                    friends {
+                        name
+                        ...Bio @defer
+                    }
+                    ... @include(if: $first) { name }
+                }
+            }
+            fragment Bio on User { bio }
+        "#;
+        let payloads = collect(query, serde_json::json!({ "first": true }), service(2)).await;
+        assert_eq!(
+            payloads,
+            vec![
+                serde_json::json!({
+                    "data": { "me": { "friends": [{ "name": "bob" }, { "name": "cid" }], "name": "ann" } },
+                    "hasNext": true,
+                }),
+                serde_json::json!({
+                    "incremental": [
+                        { "data": { "bio": "bob writes software" }, "path": ["me", "friends", 0] },
+                        { "data": { "bio": "cid writes software" }, "path": ["me", "friends", 1] },
+                    ],
+                    "hasNext": false,
+                }),
+            ],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_defer_disabled() {
+        let query = r#"
+            query ($defer: Boolean!) {
+                me { ... @defer(if: $defer) { bio } }
+            }
+        "#;
+        let payloads = collect(query, serde_json::json!({ "defer": false }), service(1)).await;
+        assert_eq!(
+            payloads,
+            vec![serde_json::json!({
+                "data": { "me": { "bio": "ann writes software" } },
+                "hasNext": false,
+            })],
+        );
+
+        // a selection set with only deferred fields stays valid in the initial query
+        let payloads = collect(query, serde_json::json!({ "defer": true }), service(1)).await;
+        assert_eq!(
+            payloads,
+            vec![
+                serde_json::json!({ "data": { "me": {} }, "hasNext": true }),
+                serde_json::json!({
+                    "incremental": [{ "data": { "bio": "ann writes software" }, "path": ["me"] }],
+                    "hasNext": false,
+                }),
+            ],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream() {
+        // list items are not streamed
+        let query = r#"{ me { friends @stream(initialCount: 1) { name } } }"#;
+        let payloads = collect(query, serde_json::json!({}), service(0)).await;
+        assert_eq!(
+            payloads,
+            vec![serde_json::json!({
+                "data": null,
+                "errors": [{
+                    "message": r#"Unknown directive "stream""#,
+                    "locations": [{ "line": 1, "column": 16 }],
+                }],
+                "hasNext": false,
+            })],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reserved_alias() {
+        let query = r#"{ me { _defer0_name: name ... @defer { bio } } }"#;
+        let payloads = collect(query, serde_json::json!({}), service(1)).await;
+        assert_eq!(
+            payloads,
+            vec![serde_json::json!({
+                "data": null,
+                "errors": [{
+                    "message": r#"Response key "_defer0_name" is reserved for the fields of deferred fragments."#,
+                    "locations": [{ "line": 1, "column": 8 }],
+                }],
+                "hasNext": false,
+            })],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        // the whole document is validated before anything is sent
+        let payloads = collect(
+            "{ me { name ... @defer { missing } } }",
+            serde_json::json!({}),
+            service(0),
+        )
+        .await;
+        assert_eq!(
+            payloads,
+            vec![serde_json::json!({
+                "data": null,
+                "errors": [{
+                    "message": r#"Unknown field "missing" on type "User"."#,
+                    "locations": [{ "line": 1, "column": 26 }],
+                }],
+                "hasNext": false,
+            })],
+        );
+
+        let payloads = collect("{ me { ", serde_json::json!({}), service(0)).await;
+        assert_eq!(
+            payloads,
+            vec![serde_json::json!({
+                "data": null,
+                "errors": [{
+                    "message": " --> 1:8\n  |\n1 | { me { \n  |        ^---\n  |\n  = expected selection",
+                    "locations": [{ "line": 1, "column": 8 }],
+                }],
+                "hasNext": false,
+            })],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multipart() {
+        let query = r#"{ me { name ... @defer { bio } } }"#;
+        let schema = create_schema();
+        let payloads = execute_incremental(
+            &schema,
+            request(query, serde_json::json!({})),
+            prepare(service(1)),
+        );
+        let (content_type, body) = multipart_response(payloads);
+        assert_eq!(content_type, MULTIPART_CONTENT_TYPE);
+        let body = body.collect::<String>().await;
+        assert_eq!(
+            body,
+            concat!(
+                "\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n",
+                r#"{"data":{"me":{"name":"ann"}},"hasNext":true}"#,
+                "\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n",
+                r#"{"hasNext":false,"incremental":[{"data":{"bio":"ann writes software"},"path":["me"]}]}"#,
+                "\r\n-----\r\n",
+            ),
+        );
+    }
+}
@@ -0,0 +1,2 @@
+pub mod defer;
+pub mod utils;
@@ -0,0 +1,1141 @@
+use crate::schema::errors::utils::ErrorHook;
+use async_graphql::async_stream;
+use async_graphql::dynamic;
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextParseQuery, NextResolve, ResolveInfo,
+};
+use async_graphql::parser::types::{
+    Directive, DocumentOperations, ExecutableDocument, Field, FragmentDefinition, InlineFragment,
+    OperationDefinition, OperationType, Selection, SelectionSet, VariableDefinition,
+};
+use async_graphql::parser::Positioned;
+use async_graphql::{
+    Name, PathSegment, QueryPathNode, QueryPathSegment, Request, Response, ServerError,
+    ServerResult, Variables,
+};
+use async_graphql_value::{ConstValue, Value};
+use futures_util::stream::{BoxStream, Stream, StreamExt};
+use futures_util::task::ArcWake;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// content type of the body of `multipart_response`
+pub const MULTIPART_CONTENT_TYPE: &str = "multipart/mixed; boundary=\"-\"; deferSpec=20220824";
+
+/// alias prefix of the fields of a deferred fragment, `_defer{fragment}_{response key}`. The
+/// resolve hook finds them in the path and keeps their values out of the initial data, such
+/// response keys of the document are rejected
+const DEFER_ALIAS: &str = "_defer";
+
+/// `__typename` of the initial data, it is resolved without the resolve hook
+struct Typename {
+    // response keys of the enclosing fields
+    path: Vec<String>,
+    key: String,
+    condition: Option<Name>,
+}
+
+/// incremental delivery of the selected operation, read from the document when it is parsed
+#[derive(Default)]
+struct Plan {
+    // label of every deferred fragment, by index
+    labels: Vec<Option<String>>,
+    typenames: Vec<Typename>,
+    // root fields that are not deferred, the initial data waits for all of them
+    root_keys: Vec<String>,
+}
+
+/// field or fragment enclosing a selection set
+struct Wrapper {
+    // response key of a field
+    key: Option<String>,
+    condition: Option<Name>,
+    included: bool,
+}
+
+/// rewrites an operation for a single execution: spread fragments are inlined, `@defer` is
+/// removed and the fields of deferred fragments get the `DEFER_ALIAS`
+struct Planner<'a> {
+    fragments: &'a HashMap<Name, Positioned<FragmentDefinition>>,
+    definitions: &'a [Positioned<VariableDefinition>],
+    variables: &'a Variables,
+    root_type: Option<&'a str>,
+    wrappers: Vec<Wrapper>,
+    // inlined fragments of the current selection set, guards against cycles
+    spreads: Vec<Name>,
+    inlined: HashSet<Name>,
+    // deferred fragment of the current selection set, its fields are aliased
+    deferred: Option<usize>,
+    plan: Plan,
+}
+
+impl<'a> Planner<'a> {
+    fn selection_set(
+        &mut self,
+        selection_set: &mut SelectionSet,
+        incremental: bool,
+    ) -> ServerResult<()> {
+        for selection in &mut selection_set.items {
+            match &mut selection.node {
+                Selection::Field(field) => self.field(&mut field.node, incremental)?,
+                Selection::InlineFragment(fragment) => {
+                    let fragment = &mut fragment.node;
+                    let condition = fragment
+                        .type_condition
+                        .as_ref()
+                        .map(|condition| condition.node.on.node.clone());
+                    self.fragment(
+                        condition,
+                        &mut fragment.directives,
+                        &mut fragment.selection_set.node,
+                        incremental,
+                    )?;
+                }
+                Selection::FragmentSpread(spread) => {
+                    let name = spread.node.fragment_name.node.clone();
+                    // unknown fragments and cycles are reported by the validation
+                    let Some(definition) = self.fragments.get(&name) else {
+                        continue;
+                    };
+                    if self.spreads.contains(&name) {
+                        continue;
+                    }
+                    let mut fragment = InlineFragment {
+                        type_condition: Some(definition.node.type_condition.clone()),
+                        directives: spread.node.directives.clone(),
+                        selection_set: definition.node.selection_set.clone(),
+                    };
+                    let pos = spread.pos;
+                    self.spreads.push(name.clone());
+                    let planned = self.fragment(
+                        Some(definition.node.type_condition.node.on.node.clone()),
+                        &mut fragment.directives,
+                        &mut fragment.selection_set.node,
+                        incremental,
+                    );
+                    self.spreads.pop();
+                    planned?;
+                    self.inlined.insert(name);
+                    selection.node = Selection::InlineFragment(Positioned::new(fragment, pos));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn field(&mut self, field: &mut Field, incremental: bool) -> ServerResult<()> {
+        let key = field.response_key().node.to_string();
+        if deferred_key(&key).is_some() {
+            return Err(ServerError::new(
+                format!(
+                    r#"Response key "{}" is reserved for the fields of deferred fragments."#,
+                    key
+                ),
+                Some(field.response_key().pos),
+            ));
+        }
+        if field.name.node == "__typename" {
+            if (incremental || self.deferred.is_some()) && self.is_selected(&field.directives)? {
+                self.plan.typenames.push(Typename {
+                    path: self.path(),
+                    key,
+                    condition: self.condition(),
+                });
+            }
+            return Ok(());
+        }
+        let at_root = self.wrappers.iter().all(|wrapper| wrapper.key.is_none());
+        if incremental
+            && at_root
+            && self.deferred.is_none()
+            && self.is_selected(&field.directives)?
+            && self
+                .condition()
+                .is_none_or(|condition| self.root_type.is_some_and(|root| condition == root))
+            && !self.plan.root_keys.contains(&key)
+        {
+            self.plan.root_keys.push(key.clone());
+        }
+        if let Some(fragment) = self.deferred {
+            let pos = field
+                .alias
+                .as_ref()
+                .map_or(field.name.pos, |alias| alias.pos);
+            let alias = Name::new(format!("{}{}_{}", DEFER_ALIAS, fragment, key));
+            field.alias = Some(Positioned::new(alias, pos));
+        }
+        if field.selection_set.node.items.is_empty() {
+            return Ok(());
+        }
+        self.wrappers.push(Wrapper {
+            key: Some(key),
+            condition: None,
+            included: true,
+        });
+        let deferred = self.deferred.take();
+        let planned = self.selection_set(&mut field.selection_set.node, incremental);
+        self.deferred = deferred;
+        self.wrappers.pop();
+        planned
+    }
+
+    fn fragment(
+        &mut self,
+        condition: Option<Name>,
+        directives: &mut Vec<Positioned<Directive>>,
+        selection_set: &mut SelectionSet,
+        incremental: bool,
+    ) -> ServerResult<()> {
+        let included = self.is_included(directives)?;
+        let label = match find_directive(directives, "defer") {
+            Some(defer) if incremental && self.is_enabled(defer)? => Some(self.label(defer)?),
+            _ => None,
+        };
+        directives.retain(|directive| directive.node.name.node != "defer");
+        self.wrappers.push(Wrapper {
+            key: None,
+            condition,
+            included,
+        });
+        let planned = match label {
+            Some(label) => {
+                self.plan.labels.push(label);
+                let deferred = self.deferred.replace(self.plan.labels.len() - 1);
+                // nested `@defer` of a deferred fragment are ignored
+                let planned = self.selection_set(selection_set, false);
+                self.deferred = deferred;
+                planned
+            }
+            None => self.selection_set(selection_set, incremental),
+        };
+        self.wrappers.pop();
+        planned
+    }
+
+    fn path(&self) -> Vec<String> {
+        self.wrappers
+            .iter()
+            .filter_map(|wrapper| wrapper.key.clone())
+            .collect()
+    }
+
+    /// innermost type condition since the enclosing field
+    fn condition(&self) -> Option<Name> {
+        self.wrappers
+            .iter()
+            .rev()
+            .take_while(|wrapper| wrapper.key.is_none())
+            .find_map(|wrapper| wrapper.condition.clone())
+    }
+
+    /// the selection and the fragments enclosing it since the enclosing field are not skipped
+    fn is_selected(&self, directives: &[Positioned<Directive>]) -> ServerResult<bool> {
+        let fragments = self
+            .wrappers
+            .iter()
+            .rev()
+            .take_while(|wrapper| wrapper.key.is_none())
+            .all(|wrapper| wrapper.included);
+        Ok(fragments && self.is_included(directives)?)
+    }
+
+    fn is_included(&self, directives: &[Positioned<Directive>]) -> ServerResult<bool> {
+        let skip = match find_directive(directives, "skip") {
+            Some(skip) => self.is_enabled(skip)?,
+            None => false,
+        };
+        let include = match find_directive(directives, "include") {
+            Some(include) => self.is_enabled(include)?,
+            None => true,
+        };
+        Ok(!skip && include)
+    }
+
+    fn argument(&self, directive: &Directive, name: &str) -> Option<ConstValue> {
+        let (_, value) = directive
+            .arguments
+            .iter()
+            .find(|(argument, _)| argument.node == name)?;
+        value
+            .node
+            .clone()
+            .into_const_with(|variable| {
+                self.variables
+                    .get(&variable)
+                    .cloned()
+                    .or_else(|| {
+                        self.definitions
+                            .iter()
+                            .find(|definition| definition.node.name.node == variable)
+                            .and_then(|definition| definition.node.default_value().cloned())
+                    })
+                    .ok_or(())
+            })
+            .ok()
+    }
+
+    fn is_enabled(&self, directive: &Positioned<Directive>) -> ServerResult<bool> {
+        match self.argument(&directive.node, "if") {
+            None | Some(ConstValue::Null) => Ok(true),
+            Some(ConstValue::Boolean(value)) => Ok(value),
+            Some(value) => Err(ServerError::new(
+                format!(
+                    r#"Invalid value for argument "if" of "@{}", expected type "Boolean", found {}."#,
+                    directive.node.name.node, value
+                ),
+                Some(directive.pos),
+            )),
+        }
+    }
+
+    fn label(&self, directive: &Positioned<Directive>) -> ServerResult<Option<String>> {
+        match self.argument(&directive.node, "label") {
+            None | Some(ConstValue::Null) => Ok(None),
+            Some(ConstValue::String(label)) => Ok(Some(label)),
+            Some(value) => Err(ServerError::new(
+                format!(
+                    r#"Invalid value for argument "label" of "@{}", expected type "String", found {}."#,
+                    directive.node.name.node, value
+                ),
+                Some(directive.pos),
+            )),
+        }
+    }
+}
+
+/// rewrite every operation of the document with a `Planner`, only the selected query is
+/// delivered incrementally. Variables left unused by the removed directives are removed too
+fn plan_document(
+    document: &mut ExecutableDocument,
+    operation_name: Option<&str>,
+    variables: &Variables,
+    registry: &async_graphql::registry::Registry,
+) -> ServerResult<Plan> {
+    let fragments = document.fragments.clone();
+    let operations = match &mut document.operations {
+        DocumentOperations::Single(operation) => vec![(true, operation)],
+        DocumentOperations::Multiple(operations) => {
+            let single = operations.len() == 1;
+            operations
+                .iter_mut()
+                .map(|(name, operation)| {
+                    let selected = match operation_name {
+                        Some(operation_name) => name == operation_name,
+                        None => single,
+                    };
+                    (selected, operation)
+                })
+                .collect()
+        }
+    };
+    let mut plan = Plan::default();
+    let mut inlined = HashSet::new();
+    for (selected, operation) in operations {
+        let OperationDefinition {
+            ty,
+            variable_definitions,
+            selection_set,
+            ..
+        } = &mut operation.node;
+        // introspection fields skip the resolve hook, such queries are executed as a whole
+        let incremental = selected
+            && *ty == OperationType::Query
+            && !selects_introspection(&selection_set.node, &fragments, &mut Vec::new());
+        let root_type = match ty {
+            OperationType::Query => Some(registry.query_type.as_str()),
+            OperationType::Mutation => registry.mutation_type.as_deref(),
+            OperationType::Subscription => registry.subscription_type.as_deref(),
+        };
+        let mut used = BTreeSet::new();
+        collect_variables(&selection_set.node, &fragments, &mut used, &mut Vec::new());
+        let mut planner = Planner {
+            fragments: &fragments,
+            definitions: variable_definitions,
+            variables,
+            root_type,
+            wrappers: Vec::new(),
+            spreads: Vec::new(),
+            inlined: HashSet::new(),
+            deferred: None,
+            plan: Plan::default(),
+        };
+        planner.selection_set(&mut selection_set.node, incremental)?;
+        inlined.extend(planner.inlined);
+        if selected {
+            plan = planner.plan;
+        }
+        let mut still_used = BTreeSet::new();
+        collect_variables(
+            &selection_set.node,
+            &fragments,
+            &mut still_used,
+            &mut Vec::new(),
+        );
+        variable_definitions.retain(|definition| {
+            let name = &definition.node.name.node;
+            !used.contains(name) || still_used.contains(name)
+        });
+    }
+
+    // inlined fragments stay when a spread is left, e.g. in a cycle
+    let mut spread = HashSet::new();
+    for (_, operation) in document.operations.iter() {
+        collect_spreads(&operation.node.selection_set.node, &mut spread);
+    }
+    loop {
+        let kept = document
+            .fragments
+            .iter()
+            .filter(|(name, _)| !inlined.contains(*name) || spread.contains(*name))
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        let count = spread.len();
+        for name in &kept {
+            collect_spreads(
+                &document.fragments[name].node.selection_set.node,
+                &mut spread,
+            );
+        }
+        if spread.len() == count {
+            break;
+        }
+    }
+    document
+        .fragments
+        .retain(|name, _| !inlined.contains(name) || spread.contains(name));
+    Ok(plan)
+}
+
+fn find_directive<'a>(
+    directives: &'a [Positioned<Directive>],
+    name: &str,
+) -> Option<&'a Positioned<Directive>> {
+    directives
+        .iter()
+        .find(|directive| directive.node.name.node == name)
+}
+
+fn selects_introspection(
+    selection_set: &SelectionSet,
+    fragments: &HashMap<Name, Positioned<FragmentDefinition>>,
+    spreads: &mut Vec<Name>,
+) -> bool {
+    selection_set
+        .items
+        .iter()
+        .any(|selection| match &selection.node {
+            Selection::Field(field) => {
+                let name = field.node.name.node.as_str();
+                name.starts_with("__") && name != "__typename"
+            }
+            Selection::InlineFragment(fragment) => {
+                selects_introspection(&fragment.node.selection_set.node, fragments, spreads)
+            }
+            Selection::FragmentSpread(spread) => {
+                let name = &spread.node.fragment_name.node;
+                match fragments.get(name) {
+                    Some(definition) if !spreads.contains(name) => {
+                        spreads.push(name.clone());
+                        let selects = selects_introspection(
+                            &definition.node.selection_set.node,
+                            fragments,
+                            spreads,
+                        );
+                        spreads.pop();
+                        selects
+                    }
+                    _ => false,
+                }
+            }
+        })
+}
+
+/// variables of the arguments and directives of a selection set, with its spread fragments
+fn collect_variables(
+    selection_set: &SelectionSet,
+    fragments: &HashMap<Name, Positioned<FragmentDefinition>>,
+    variables: &mut BTreeSet<Name>,
+    spreads: &mut Vec<Name>,
+) {
+    let directive_variables = |directives: &[Positioned<Directive>], variables: &mut _| {
+        for directive in directives {
+            for (_, value) in &directive.node.arguments {
+                collect_value_variables(&value.node, variables);
+            }
+        }
+    };
+    for selection in &selection_set.items {
+        match &selection.node {
+            Selection::Field(field) => {
+                for (_, value) in &field.node.arguments {
+                    collect_value_variables(&value.node, variables);
+                }
+                directive_variables(&field.node.directives, variables);
+                collect_variables(
+                    &field.node.selection_set.node,
+                    fragments,
+                    variables,
+                    spreads,
+                );
+            }
+            Selection::InlineFragment(fragment) => {
+                directive_variables(&fragment.node.directives, variables);
+                collect_variables(
+                    &fragment.node.selection_set.node,
+                    fragments,
+                    variables,
+                    spreads,
+                );
+            }
+            Selection::FragmentSpread(spread) => {
+                directive_variables(&spread.node.directives, variables);
+                let name = &spread.node.fragment_name.node;
+                if let Some(definition) = fragments.get(name) {
+                    if !spreads.contains(name) {
+                        spreads.push(name.clone());
+                        collect_variables(
+                            &definition.node.selection_set.node,
+                            fragments,
+                            variables,
+                            spreads,
+                        );
+                        spreads.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn collect_value_variables(value: &Value, variables: &mut BTreeSet<Name>) {
+    match value {
+        Value::Variable(name) => {
+            variables.insert(name.clone());
+        }
+        Value::List(items) => items
+            .iter()
+            .for_each(|item| collect_value_variables(item, variables)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|field| collect_value_variables(field, variables)),
+        _ => {}
+    }
+}
+
+fn collect_spreads(selection_set: &SelectionSet, spreads: &mut HashSet<Name>) {
+    for selection in &selection_set.items {
+        match &selection.node {
+            Selection::Field(field) => collect_spreads(&field.node.selection_set.node, spreads),
+            Selection::InlineFragment(fragment) => {
+                collect_spreads(&fragment.node.selection_set.node, spreads)
+            }
+            Selection::FragmentSpread(spread) => {
+                spreads.insert(spread.node.fragment_name.node.clone());
+            }
+        }
+    }
+}
+
+/// (fragment, response key) of a field of a deferred fragment
+fn deferred_key(key: &str) -> Option<(usize, &str)> {
+    let (fragment, key) = key.strip_prefix(DEFER_ALIAS)?.split_once('_')?;
+    Some((fragment.parse().ok()?, key))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+impl Segment {
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Segment::Key(key) => key.clone().into(),
+            Segment::Index(index) => (*index).into(),
+        }
+    }
+}
+
+fn path_of(node: &QueryPathNode) -> Vec<Segment> {
+    let mut path = std::iter::once(node)
+        .chain(node.parents())
+        .map(|node| match node.segment {
+            QueryPathSegment::Index(index) => Segment::Index(index),
+            QueryPathSegment::Name(name) => Segment::Key(name.to_string()),
+        })
+        .collect::<Vec<_>>();
+    path.reverse();
+    path
+}
+
+fn json_path(path: &[Segment]) -> serde_json::Value {
+    path.iter().map(Segment::to_json).collect()
+}
+
+/// replace the alias of a deferred field in the path of the error, returns the deferred
+/// fragment and the path of its object
+fn unalias(error: &mut ServerError) -> Option<(usize, Vec<Segment>)> {
+    let mut instance = None;
+    let mut object = Vec::new();
+    for segment in &mut error.path {
+        match segment {
+            PathSegment::Field(field) => match deferred_key(field) {
+                Some((fragment, key)) if instance.is_none() => {
+                    instance = Some(fragment);
+                    *field = key.to_string();
+                }
+                _ => object.push(Segment::Key(field.clone())),
+            },
+            PathSegment::Index(index) => object.push(Segment::Index(*index)),
+        }
+        if instance.is_some() {
+            break;
+        }
+    }
+    instance.map(|fragment| (fragment, object))
+}
+
+/// remove the fields of deferred fragments, the resolve hook resolved them to null
+fn remove_deferred(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            fields.retain(|key, _| deferred_key(key).is_none());
+            fields.values_mut().for_each(remove_deferred);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(remove_deferred),
+        _ => {}
+    }
+}
+
+/// field of a deferred fragment, the resolve hook keeps its value out of the response
+struct DeferredField {
+    fragment: usize,
+    object: Vec<Segment>,
+    key: String,
+    non_null: bool,
+    result: Option<ServerResult<ConstValue>>,
+}
+
+#[derive(Default)]
+struct Records {
+    plan: Option<Plan>,
+    // bumped by every record, the execution made progress when it changes
+    generation: u64,
+    // fields and list items of the initial data, `None` while they are resolved
+    values: HashMap<Vec<Segment>, Option<ConstValue>>,
+    // started fields and list items of the initial data under a path
+    children: HashMap<Vec<Segment>, Vec<Segment>>,
+    // type of the objects with started fields, deferred or not
+    object_types: HashMap<Vec<Segment>, String>,
+    deferred: Vec<DeferredField>,
+}
+
+/// progress of a request of `execute_incremental`, in the request data
+struct Progress {
+    operation_name: Option<String>,
+    records: Mutex<Records>,
+}
+
+/// what `execute_incremental` has sent
+#[derive(Default)]
+struct Delivery {
+    initial: bool,
+    // deferred fragments by object
+    instances: HashSet<(usize, Vec<Segment>)>,
+}
+
+impl Records {
+    fn start(&mut self, path: &[Segment], parent_type: &str) {
+        self.generation += 1;
+        let (segment, parent) = path.split_last().unwrap();
+        if let Segment::Key(_) = segment {
+            self.object_types
+                .entry(parent.to_vec())
+                .or_insert_with(|| parent_type.to_string());
+        }
+        self.children
+            .entry(parent.to_vec())
+            .or_default()
+            .push(segment.clone());
+        self.values.insert(path.to_vec(), None);
+    }
+
+    fn end(&mut self, path: Vec<Segment>, value: ConstValue) {
+        self.generation += 1;
+        self.values.insert(path, Some(value));
+    }
+
+    fn start_deferred(&mut self, field: DeferredField, parent_type: &str) -> usize {
+        self.generation += 1;
+        self.object_types
+            .entry(field.object.clone())
+            .or_insert_with(|| parent_type.to_string());
+        self.deferred.push(field);
+        self.deferred.len() - 1
+    }
+
+    fn end_deferred(&mut self, index: usize, result: ServerResult<ConstValue>) {
+        self.generation += 1;
+        self.deferred[index].result = Some(result);
+    }
+
+    /// data of the initial payload, `None` while fields that are not deferred are resolved
+    fn initial_data(&self, plan: &Plan) -> Option<serde_json::Value> {
+        let root_started = plan
+            .root_keys
+            .iter()
+            .all(|key| self.values.contains_key(&[Segment::Key(key.clone())][..]));
+        match root_started {
+            true => self.data(&mut Vec::new(), plan),
+            false => None,
+        }
+    }
+
+    fn data(&self, path: &mut Vec<Segment>, plan: &Plan) -> Option<serde_json::Value> {
+        if let Some(Some(value)) = self.values.get(path.as_slice()) {
+            let mut value = value.clone().into_json().ok()?;
+            remove_deferred(&mut value);
+            return Some(value);
+        }
+        let children = self
+            .children
+            .get(path.as_slice())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        match self.object_types.get(path.as_slice()) {
+            Some(object_type) => {
+                let mut fields = serde_json::Map::new();
+                for child in children {
+                    let Segment::Key(key) = child else {
+                        continue;
+                    };
+                    path.push(child.clone());
+                    fields.insert(key.clone(), self.data(path, plan)?);
+                    path.pop();
+                }
+                let keys = path
+                    .iter()
+                    .filter_map(|segment| match segment {
+                        Segment::Key(key) => Some(key.clone()),
+                        Segment::Index(_) => None,
+                    })
+                    .collect::<Vec<_>>();
+                for typename in &plan.typenames {
+                    let applies = typename
+                        .condition
+                        .as_ref()
+                        .is_none_or(|condition| condition == object_type);
+                    if typename.path == keys && applies {
+                        fields.insert(typename.key.clone(), object_type.clone().into());
+                    }
+                }
+                Some(serde_json::Value::Object(fields))
+            }
+            None if !children.is_empty() => {
+                let mut items = Vec::with_capacity(children.len());
+                for index in 0..children.len() {
+                    path.push(Segment::Index(index));
+                    items.push(self.data(path, plan)?);
+                    path.pop();
+                }
+                Some(serde_json::Value::Array(items))
+            }
+            // the resolver is running
+            None => None,
+        }
+    }
+
+    /// deferred fragments by object, in the order they started
+    fn instances(&self) -> Vec<(usize, Vec<Segment>)> {
+        let mut instances = Vec::new();
+        for field in &self.deferred {
+            let instance = (field.fragment, field.object.clone());
+            if !instances.contains(&instance) {
+                instances.push(instance);
+            }
+        }
+        instances
+    }
+
+    fn fields<'r>(
+        &'r self,
+        instance: &'r (usize, Vec<Segment>),
+    ) -> impl Iterator<Item = &'r DeferredField> + 'r {
+        self.deferred
+            .iter()
+            .filter(move |field| field.fragment == instance.0 && field.object == instance.1)
+    }
+
+    fn entry(
+        &self,
+        instance: &(usize, Vec<Segment>),
+        plan: &Plan,
+        mut added: Vec<ServerError>,
+    ) -> serde_json::Value {
+        let mut data = serde_json::Map::new();
+        let mut errors = Vec::new();
+        let mut null = false;
+        for field in self.fields(instance) {
+            match &field.result {
+                Some(Ok(value)) => {
+                    let value = value.clone().into_json().unwrap_or_default();
+                    data.insert(field.key.clone(), value);
+                }
+                Some(Err(error)) => {
+                    let mut error = error.clone();
+                    unalias(&mut error);
+                    errors.push(error);
+                    null |= field.non_null;
+                    data.insert(field.key.clone(), serde_json::Value::Null);
+                }
+                None => {}
+            }
+        }
+        errors.append(&mut added);
+        let data = match null {
+            true => serde_json::Value::Null,
+            false => serde_json::Value::Object(data),
+        };
+        let mut entry = serde_json::json!({ "data": data, "path": json_path(&instance.1) });
+        if !errors.is_empty() {
+            entry["errors"] = serde_json::to_value(errors).unwrap();
+        }
+        with_label(entry, &plan.labels[instance.0])
+    }
+
+    /// payloads that are ready while the execution waits
+    fn ready_payloads(&self, delivery: &mut Delivery) -> Vec<serde_json::Value> {
+        let mut payloads = Vec::new();
+        let Some(plan) = &self.plan else {
+            return payloads;
+        };
+        if plan.labels.is_empty() {
+            return payloads;
+        }
+        if !delivery.initial {
+            let Some(data) = self.initial_data(plan) else {
+                return payloads;
+            };
+            payloads.push(serde_json::json!({ "data": data, "hasNext": true }));
+            delivery.initial = true;
+        }
+        let mut entries = Vec::new();
+        for instance in self.instances() {
+            let resolved = self.fields(&instance).all(|field| field.result.is_some());
+            if resolved && !delivery.instances.contains(&instance) {
+                entries.push(self.entry(&instance, plan, Vec::new()));
+                delivery.instances.insert(instance);
+            }
+        }
+        if !entries.is_empty() {
+            payloads.push(serde_json::json!({ "incremental": entries, "hasNext": true }));
+        }
+        payloads
+    }
+
+    /// payloads left when the execution is done. Errors of the response belong to the initial
+    /// payload or to a deferred fragment, those reported after their payload was sent come
+    /// with the last payload
+    fn last_payloads(
+        &self,
+        mut response: Response,
+        delivery: &mut Delivery,
+    ) -> Vec<serde_json::Value> {
+        let mut initial_errors = Vec::new();
+        let mut added = HashMap::<_, Vec<_>>::new();
+        let mut late_errors = Vec::new();
+        for mut error in std::mem::take(&mut response.errors) {
+            match unalias(&mut error) {
+                Some(instance) if !delivery.instances.contains(&instance) => {
+                    added.entry(instance).or_default().push(error)
+                }
+                None if !delivery.initial => initial_errors.push(error),
+                _ => late_errors.push(error),
+            }
+        }
+        let Some(plan) = self
+            .plan
+            .as_ref()
+            .filter(|_| response.data != ConstValue::Null)
+        else {
+            // not executed
+            response.errors = initial_errors;
+            response.errors.extend(added.into_values().flatten());
+            response.errors.append(&mut late_errors);
+            return vec![with_has_next(
+                serde_json::to_value(response).unwrap(),
+                false,
+            )];
+        };
+
+        let mut entries = Vec::new();
+        for instance in self.instances() {
+            if !delivery.instances.contains(&instance) {
+                let errors = added.remove(&instance).unwrap_or_default();
+                entries.push(self.entry(&instance, plan, errors));
+                delivery.instances.insert(instance);
+            }
+        }
+        late_errors.extend(added.into_values().flatten());
+
+        let mut payloads = Vec::new();
+        if !delivery.initial {
+            response.errors = initial_errors;
+            let mut initial = serde_json::to_value(response).unwrap();
+            remove_deferred(&mut initial["data"]);
+            payloads.push(with_has_next(initial, !entries.is_empty()));
+            if !entries.is_empty() {
+                payloads.push(serde_json::json!({ "incremental": entries, "hasNext": false }));
+            }
+            delivery.initial = true;
+        } else {
+            let mut payload = serde_json::json!({ "hasNext": false });
+            if !entries.is_empty() {
+                payload["incremental"] = entries.into();
+            }
+            payloads.push(payload);
+        }
+        if !late_errors.is_empty() {
+            let last = payloads.last_mut().unwrap();
+            last["errors"] = serde_json::to_value(late_errors).unwrap();
+        }
+        payloads
+    }
+}
+
+/// records the progress of the requests of `execute_incremental` for the registry built
+/// schemas, other requests are not affected
+pub(crate) struct IncrementalExtension(pub Option<ErrorHook>);
+
+impl ExtensionFactory for IncrementalExtension {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(IncrementalExtension(self.0.clone()))
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for IncrementalExtension {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let mut document = next.run(ctx, query, variables).await?;
+        if let Some(progress) = ctx.data_opt::<Arc<Progress>>() {
+            let plan = plan_document(
+                &mut document,
+                progress.operation_name.as_deref(),
+                variables,
+                &ctx.schema_env.registry,
+            )?;
+            progress.records.lock().unwrap().plan = Some(plan);
+        }
+        Ok(document)
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<ConstValue>> {
+        let progress = ctx.data_opt::<Arc<Progress>>().filter(|progress| {
+            let records = progress.records.lock().unwrap();
+            records
+                .plan
+                .as_ref()
+                .is_some_and(|plan| !plan.labels.is_empty())
+        });
+        let Some(progress) = progress else {
+            return next.run(ctx, info).await;
+        };
+        let path = path_of(info.path_node);
+        let deferred = path.iter().position(|segment| match segment {
+            Segment::Key(key) => deferred_key(key).is_some(),
+            Segment::Index(_) => false,
+        });
+        match deferred {
+            // the value of a deferred field is sent in a subsequent payload, its errors don't
+            // reach the fields that are not deferred
+            Some(position) if position + 1 == path.len() => {
+                let Some(Segment::Key(key)) = path.last() else {
+                    unreachable!()
+                };
+                let (fragment, key) = deferred_key(key).unwrap();
+                let field = DeferredField {
+                    fragment,
+                    object: path[..position].to_vec(),
+                    key: key.to_string(),
+                    non_null: info.return_type.ends_with('!'),
+                    result: None,
+                };
+                let index = progress
+                    .records
+                    .lock()
+                    .unwrap()
+                    .start_deferred(field, info.parent_type);
+                let result = next.run(ctx, info).await;
+                let result = result
+                    .map(Option::unwrap_or_default)
+                    .map_err(|err| match &self.0 {
+                        Some(hook) => hook(err),
+                        None => err,
+                    });
+                progress.records.lock().unwrap().end_deferred(index, result);
+                Ok(None)
+            }
+            Some(_) => next.run(ctx, info).await,
+            None => {
+                progress
+                    .records
+                    .lock()
+                    .unwrap()
+                    .start(&path, info.parent_type);
+                let result = next.run(ctx, info).await;
+                if let Ok(value) = &result {
+                    let value = value.clone().unwrap_or_default();
+                    progress.records.lock().unwrap().end(path, value);
+                }
+                result
+            }
+        }
+    }
+}
+
+/// wakes the task polling the execution and remembers it was woken
+struct TrackingWaker {
+    woken: AtomicBool,
+    waker: Waker,
+}
+
+impl ArcWake for TrackingWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.woken.store(true, Ordering::SeqCst);
+        arc_self.waker.wake_by_ref();
+    }
+}
+
+/// `Ready(None)` when the execution waits for something else than itself and the resolve
+/// hook recorded progress since the last time
+fn poll_execution(
+    execution: Pin<&mut (dyn Future<Output = Response> + Send)>,
+    progress: &Progress,
+    checked: &mut u64,
+    cx: &mut Context<'_>,
+) -> Poll<Option<Response>> {
+    let tracking = Arc::new(TrackingWaker {
+        woken: AtomicBool::new(false),
+        waker: cx.waker().clone(),
+    });
+    let waker = futures_util::task::waker(tracking.clone());
+    match execution.poll(&mut Context::from_waker(&waker)) {
+        Poll::Ready(response) => Poll::Ready(Some(response)),
+        Poll::Pending if tracking.woken.load(Ordering::SeqCst) => Poll::Pending,
+        Poll::Pending => {
+            let generation = progress.records.lock().unwrap().generation;
+            match generation == *checked {
+                true => Poll::Pending,
+                false => {
+                    *checked = generation;
+                    Poll::Ready(None)
+                }
+            }
+        }
+    }
+}
+
+fn with_label(mut entry: serde_json::Value, label: &Option<String>) -> serde_json::Value {
+    if let Some(label) = label {
+        entry["label"] = label.clone().into();
+    }
+    entry
+}
+
+/// execute a request with `@defer` fragments on a schema built by the registry. The document
+/// is validated and executed once, the first payload has the data of the fields that are not
+/// deferred and is sent while deferred fragments are resolved. `prepare` adds the data and the
+/// root value to the request. Payloads follow the incremental delivery format of graphql-js:
+/// the initial `{ data, hasNext }` and subsequent `{ incremental: [{ data, path, label }],
+/// hasNext }`.
+///
+/// async-graphql resolves a list as a whole, `@stream` is not supported. Root fields are
+/// resolved one after the other, the initial payload waits for deferred root fields selected
+/// before the others. Operations other than queries and queries selecting `__schema` or
+/// `__type` are executed without incremental delivery
+pub fn execute_incremental<F, R>(
+    schema: &dynamic::Schema,
+    request: Request,
+    prepare: F,
+) -> BoxStream<'static, serde_json::Value>
+where
+    F: FnOnce(Request) -> R,
+    R: Into<dynamic::DynamicRequest>,
+{
+    let progress = Arc::new(Progress {
+        operation_name: request.operation_name.clone(),
+        records: Default::default(),
+    });
+    let request: dynamic::DynamicRequest = prepare(request.data(progress.clone())).into();
+    let schema = schema.clone();
+    let mut execution: Pin<Box<dyn Future<Output = Response> + Send>> =
+        Box::pin(async move { schema.execute(request).await });
+    let stream = async_stream::stream! {
+        let mut checked = 0;
+        let mut delivery = Delivery::default();
+        loop {
+            let polled = futures_util::future::poll_fn(|cx| {
+                poll_execution(execution.as_mut(), &progress, &mut checked, cx)
+            })
+            .await;
+            match polled {
+                Some(response) => {
+                    let payloads = progress
+                        .records
+                        .lock()
+                        .unwrap()
+                        .last_payloads(response, &mut delivery);
+                    for payload in payloads {
+                        yield payload;
+                    }
+                    break;
+                }
+                None => {
+                    let payloads = progress.records.lock().unwrap().ready_payloads(&mut delivery);
+                    for payload in payloads {
+                        yield payload;
+                    }
+                }
+            }
+        }
+    };
+    stream.boxed()
+}
+
+fn with_has_next(mut response: serde_json::Value, has_next: bool) -> serde_json::Value {
+    response["hasNext"] = has_next.into();
+    response
+}
+
+/// content type and body of a `multipart/mixed` response with a part for every payload
+pub fn multipart_response(
+    payloads: impl Stream<Item = serde_json::Value> + Send + 'static,
+) -> (&'static str, impl Stream<Item = String> + Send + 'static) {
+    let body = payloads
+        .map(|payload| {
+            format!(
+                "\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{}",
+                payload
+            )
+        })
+        .chain(futures_util::stream::once(async {
+            "\r\n-----\r\n".to_string()
+        }));
+    (MULTIPART_CONTENT_TYPE, body)
+}
@@ -1,4 +1,5 @@
 use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{InputObject, Object, Register, Registry};
 use async_graphql::dynamic::DynamicRequestExt;
 use async_graphql::{dynamic, Context, ID};
@@ -60,7 +61,7 @@ impl Register for Query {
         // define foo field
         let foo_field = dynamic::Field::new("foo", dynamic::TypeRef::named_nn(Foo::NAME), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_foo().await;
                 ResolveRef::resolve_ref(value, &ctx)
             })
@@ -121,7 +122,7 @@ impl Register for Foo {
         let by_object_field =
             dynamic::Field::new("by_object", dynamic::TypeRef::named_nn("String"), |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0: Option<_> = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent
                         .resolve_by_object(arg_0.unwrap_or(BarInput {
@@ -146,7 +147,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0: Option<_> = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent
                         .resolve_by_string(arg_0.unwrap_or_else(|| "default".to_string()))
@@ -168,7 +169,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0: Option<_> = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_int(arg_0.unwrap_or(1)).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -190,7 +191,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0: Option<_> = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_float(arg_0.unwrap_or(1.0)).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -212,7 +213,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0: Option<_> = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_bool(arg_0.unwrap_or(true)).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -234,7 +235,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0: Option<_> = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent
                         .resolve_by_id(arg_0.unwrap_or_else(|| ID("default".to_string())))
@@ -0,0 +1,321 @@
+use crate::schema::input::utils::{ComposedInputObject, InputField, InputFields};
+use crate::schema::output_types::utils::ResolveOwned;
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{InputObject, Object, Register, Registry};
+use async_graphql::dynamic;
+use serde::Deserialize;
+
+// user
+
+// mark as input
+#[derive(Deserialize)]
+struct PaginationInput {
+    // mark as description
+    /// page size, 10 when omitted
+    limit: Option<i32>,
+    offset: Option<i32>,
+}
+
+// mark as input
+#[derive(Deserialize)]
+struct AuditFields {
+    reason: String,
+}
+
+// mark as input
+#[derive(Deserialize)]
+struct UserFilterInput {
+    name: Option<String>,
+    // mark as flatten
+    #[serde(flatten)]
+    pagination: PaginationInput,
+}
+
+// mark as input
+#[derive(Deserialize)]
+struct DeletePostsInput {
+    tag: String,
+    // mark as flatten
+    #[serde(flatten)]
+    pagination: PaginationInput,
+    // mark as flatten
+    #[serde(flatten)]
+    audit: AuditFields,
+}
+
+struct Query;
+
+impl PaginationInput {
+    fn describe(&self) -> String {
+        format!(
+            "limit: {}, offset: {}",
+            self.limit.unwrap_or(10),
+            self.offset.unwrap_or(0)
+        )
+    }
+}
+
+impl Query {
+    async fn resolve_users(&self, filter: UserFilterInput) -> String {
+        format!(
+            "users name: {}, {}",
+            filter.name.unwrap_or_default(),
+            filter.pagination.describe()
+        )
+    }
+    async fn resolve_delete_posts(&self, input: DeletePostsInput) -> String {
+        format!(
+            "delete posts tag: {}, {}, reason: {}",
+            input.tag,
+            input.pagination.describe(),
+            input.audit.reason
+        )
+    }
+}
+
+// generated
+
+impl InputObject for PaginationInput {
+    const NAME: &'static str = "PaginationInput";
+}
+
+impl InputFields for PaginationInput {
+    fn input_fields() -> Vec<InputField> {
+        vec![
+            InputField::new("limit", dynamic::TypeRef::named(dynamic::TypeRef::INT))
+                .description("page size, 10 when omitted"),
+            InputField::new("offset", dynamic::TypeRef::named(dynamic::TypeRef::INT)),
+        ]
+    }
+}
+
+impl Register for PaginationInput {
+    fn register(registry: Registry) -> Registry {
+        let object_type = ComposedInputObject::new(<Self as InputObject>::NAME);
+        let object_type = <Self as InputFields>::input_fields()
+            .into_iter()
+            .fold(object_type, |object_type, field| object_type.field(field));
+        registry.register_composed_input_object(object_type)
+    }
+}
+
+impl InputObject for AuditFields {
+    const NAME: &'static str = "AuditFields";
+}
+
+impl InputFields for AuditFields {
+    fn input_fields() -> Vec<InputField> {
+        vec![InputField::new(
+            "reason",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        )]
+    }
+}
+
+impl InputObject for UserFilterInput {
+    const NAME: &'static str = "UserFilterInput";
+}
+
+impl Register for UserFilterInput {
+    fn register(registry: Registry) -> Registry {
+        let object_type = ComposedInputObject::new(<Self as InputObject>::NAME);
+
+        // define name field
+        let name_field = InputField::new("name", dynamic::TypeRef::named(dynamic::TypeRef::STRING));
+        let object_type = object_type.field(name_field);
+
+        // flatten pagination field
+        let object_type = object_type.flatten::<PaginationInput>();
+
+        registry.register_composed_input_object(object_type)
+    }
+}
+
+impl InputObject for DeletePostsInput {
+    const NAME: &'static str = "DeletePostsInput";
+}
+
+impl Register for DeletePostsInput {
+    fn register(registry: Registry) -> Registry {
+        let object_type = ComposedInputObject::new(<Self as InputObject>::NAME);
+
+        // define tag field
+        let tag_field =
+            InputField::new("tag", dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING));
+        let object_type = object_type.field(tag_field);
+
+        // flatten pagination field
+        let object_type = object_type.flatten::<PaginationInput>();
+
+        // flatten audit field
+        let object_type = object_type.flatten::<AuditFields>();
+
+        registry.register_composed_input_object(object_type)
+    }
+}
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define users field
+        let users_field = dynamic::Field::new(
+            "users",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("filter")?.deserialize()?;
+                    let value = parent.resolve_users(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let users_field = users_field.argument(dynamic::InputValue::new(
+            "filter",
+            dynamic::TypeRef::named_nn(<UserFilterInput as InputObject>::NAME),
+        ));
+        let object_type = object_type.field(users_field);
+
+        // define delete_posts field
+        let delete_posts_field = dynamic::Field::new(
+            "delete_posts",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("input")?.deserialize()?;
+                    let value = parent.resolve_delete_posts(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let delete_posts_field = delete_posts_field.argument(dynamic::InputValue::new(
+            "input",
+            dynamic::TypeRef::named_nn(<DeletePostsInput as InputObject>::NAME),
+        ));
+        let object_type = object_type.field(delete_posts_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new()
+        .register::<Query>()
+        .register::<PaginationInput>()
+        .register::<UserFilterInput>()
+        .register::<DeletePostsInput>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                input DeletePostsInput {
+                  tag: String!
+                  """
+                  page size, 10 when omitted
+                  """
+                  limit: Int
+                  offset: Int
+                  reason: String!
+                }
+                input PaginationInput {
+                  """
+                  page size, 10 when omitted
+                  """
+                  limit: Int
+                  offset: Int
+                }
+                type Query {
+                  users(filter: UserFilterInput!): String!
+                  delete_posts(input: DeletePostsInput!): String!
+                }
+                input UserFilterInput {
+                  name: String
+                  """
+                  page size, 10 when omitted
+                  """
+                  limit: Int
+                  offset: Int
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                users(filter: { name: "john", limit: 5 })
+                delete_posts(input: { tag: "spam", offset: 20, reason: "cleanup" })
+            }
+        "#;
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "users": "users name: john, limit: 5, offset: 0",
+                "delete_posts": "delete posts tag: spam, limit: 10, offset: 20, reason: cleanup",
+            }),
+        );
+    }
+
+    // mark as input
+    struct ConflictInput;
+
+    impl InputObject for ConflictInput {
+        const NAME: &'static str = "ConflictInput";
+    }
+
+    impl Register for ConflictInput {
+        fn register(registry: Registry) -> Registry {
+            let object_type = ComposedInputObject::new(<Self as InputObject>::NAME);
+            let object_type = object_type.field(InputField::new(
+                "limit",
+                dynamic::TypeRef::named(dynamic::TypeRef::STRING),
+            ));
+            let object_type = object_type.flatten::<PaginationInput>();
+            registry.register_composed_input_object(object_type)
+        }
+    }
+
+    #[test]
+    fn test_conflict() {
+        let registry = Registry::new()
+            .register::<Query>()
+            .register::<UserFilterInput>()
+            .register::<DeletePostsInput>()
+            .register::<ConflictInput>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        assert_eq!(
+            registry.try_build_schema(schema).err().unwrap(),
+            "Conflicting input object fields: \"field ConflictInput.limit from PaginationInput conflicts with field from ConflictInput\"",
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{InputObject, Object, Register, Registry};
 use async_graphql::dynamic::DynamicRequestExt;
 use async_graphql::{dynamic, Context, ID};
@@ -101,7 +102,7 @@ impl Register for Query {
         // define foo field
         let foo_field = dynamic::Field::new("foo", dynamic::TypeRef::named_nn(Foo::NAME), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_foo().await;
                 ResolveRef::resolve_ref(value, &ctx)
             })
@@ -162,7 +163,7 @@ impl Register for Foo {
         let by_object_field =
             dynamic::Field::new("by_objects", dynamic::TypeRef::named_nn("String"), |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0 = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_objects(arg_0).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -182,7 +183,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0 = ctx.args.try_get("input")?.deserialize();
 
                     let value = parent.resolve_by_strings(arg_0?).await;
@@ -203,7 +204,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::INT),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0 = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_ints(arg_0).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -225,7 +226,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::FLOAT),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0 = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_floats(arg_0).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -247,7 +248,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::BOOLEAN),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0 = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_bools(arg_0).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -269,7 +270,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::ID),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0 = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_ids(arg_0).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -1,6 +1,9 @@
 pub mod default_value;
+pub mod flatten;
 pub mod list;
 pub mod oneof;
+pub mod oneof_nested;
 pub mod optional;
 pub mod reference;
 pub mod simple;
+pub mod utils;
@@ -1,13 +1,14 @@
+use crate::schema::input::utils::{oneof_variant, OneofField, OneofInput, OneofInputObject};
 use crate::schema::output_types::utils::ResolveOwned;
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{InputObject, Object, Register, Registry};
 use async_graphql::dynamic;
 use async_graphql::dynamic::DynamicRequestExt;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 struct Query {}
 
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
+// mark as oneof input
 enum BarInput {
     A(i32),
     B(i32),
@@ -37,6 +38,28 @@ impl InputObject for BarInput {
     const NAME: &'static str = "BarInput";
 }
 
+impl OneofInput for BarInput {
+    const FIELDS: &'static [&'static str] = &["a", "b"];
+
+    fn from_field(name: &str, value: async_graphql::Value) -> Result<Self, String> {
+        match name {
+            "a" => oneof_variant(<Self as InputObject>::NAME, name, value).map(BarInput::A),
+            "b" => oneof_variant(<Self as InputObject>::NAME, name, value).map(BarInput::B),
+            _ => Err(format!(
+                "{}: unknown field {}",
+                <Self as InputObject>::NAME,
+                name
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BarInput {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <Self as OneofInput>::deserialize_oneof(deserializer)
+    }
+}
+
 impl Register for Query {
     fn register(registry: Registry) -> Registry {
         // define Query object
@@ -48,7 +71,7 @@ impl Register for Query {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let input = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_bar(input).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -70,20 +93,18 @@ impl Register for Query {
 impl Register for BarInput {
     fn register(registry: Registry) -> Registry {
         // define BarInput object
-        let bar_input_object = dynamic::InputObject::new("BarInput");
-        let bar_input_object = bar_input_object.oneof();
+        let bar_input_object = OneofInputObject::new("BarInput");
 
         // define a field
-        let a_field = dynamic::InputValue::new("a", dynamic::TypeRef::named(dynamic::TypeRef::INT));
+        let a_field = OneofField::new("a", dynamic::TypeRef::named(dynamic::TypeRef::INT));
         let bar_input_object = bar_input_object.field(a_field);
 
         // define b field
-
-        let b_field = dynamic::InputValue::new("b", dynamic::TypeRef::named(dynamic::TypeRef::INT));
+        let b_field = OneofField::new("b", dynamic::TypeRef::named(dynamic::TypeRef::INT));
         let bar_input_object = bar_input_object.field(b_field);
 
         // register BarInput object
-        registry.register_input_object(bar_input_object)
+        registry.register_oneof_input_object(bar_input_object)
     }
 }
 
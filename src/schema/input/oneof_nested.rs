@@ -0,0 +1,443 @@
+use crate::schema::enums::utils::{EnumDefinition, EnumItemDefinition};
+use crate::schema::input::utils::{
+    oneof_unit_variant, oneof_variant, OneofField, OneofInput, OneofInputObject,
+};
+use crate::schema::output_types::utils::ResolveOwned;
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Enum, InputObject, Object, Register, Registry};
+use async_graphql::{dynamic, ID};
+use serde::{Deserialize, Deserializer};
+
+// user
+
+// mark as enum
+#[derive(Clone)]
+enum Order {
+    Asc,
+    Desc,
+}
+
+// mark as input
+#[derive(Deserialize)]
+struct NameInput {
+    first: String,
+    last: Option<String>,
+}
+
+// mark as oneof input
+enum UserBy {
+    Id(ID),
+    Name(NameInput),
+    Ids(Vec<ID>),
+    Sort(Order),
+    Email {
+        address: String,
+        verified: Option<bool>,
+    },
+    Me,
+}
+
+struct Query;
+
+impl Query {
+    async fn resolve_user(&self, by: UserBy) -> String {
+        match by {
+            UserBy::Id(id) => format!("id: {}", id.0),
+            UserBy::Name(name) => match name.last {
+                Some(last) => format!("name: {} {}", name.first, last),
+                None => format!("name: {}", name.first),
+            },
+            UserBy::Ids(ids) => format!(
+                "ids: {}",
+                ids.into_iter()
+                    .map(|id| id.0)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            UserBy::Sort(Order::Asc) => "sort: first".to_string(),
+            UserBy::Sort(Order::Desc) => "sort: last".to_string(),
+            UserBy::Email { address, verified } => {
+                format!("email: {} ({})", address, verified.unwrap_or(false))
+            }
+            UserBy::Me => "me".to_string(),
+        }
+    }
+    async fn resolve_users(&self, by: Vec<UserBy>) -> Vec<String> {
+        let mut users = Vec::new();
+        for by in by {
+            users.push(self.resolve_user(by).await);
+        }
+        users
+    }
+}
+
+// generated
+
+impl Enum for Order {
+    const NAME: &'static str = "Order";
+}
+
+impl EnumDefinition for Order {
+    const ITEMS: &'static [EnumItemDefinition<Self>] = &[
+        EnumItemDefinition::new("ASC", Order::Asc),
+        EnumItemDefinition::new("DESC", Order::Desc),
+    ];
+}
+
+impl<'de> Deserialize<'de> for Order {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <Self as EnumDefinition>::deserialize_item(deserializer)
+    }
+}
+
+impl Register for Order {
+    fn register(registry: Registry) -> Registry {
+        registry.register_enum(<Self as EnumDefinition>::create_enum())
+    }
+}
+
+impl InputObject for NameInput {
+    const NAME: &'static str = "NameInput";
+}
+
+impl Register for NameInput {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::InputObject::new(<Self as InputObject>::NAME);
+
+        // define first field
+        let first_field = dynamic::InputValue::new(
+            "first",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        let object_type = object_type.field(first_field);
+
+        // define last field
+        let last_field =
+            dynamic::InputValue::new("last", dynamic::TypeRef::named(dynamic::TypeRef::STRING));
+        let object_type = object_type.field(last_field);
+
+        registry.register_input_object(object_type)
+    }
+}
+
+// struct variant UserBy::Email
+#[derive(Deserialize)]
+struct UserByEmailInput {
+    address: String,
+    verified: Option<bool>,
+}
+
+impl InputObject for UserByEmailInput {
+    const NAME: &'static str = "UserByEmailInput";
+}
+
+impl Register for UserByEmailInput {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::InputObject::new(<Self as InputObject>::NAME);
+
+        // define address field
+        let address_field = dynamic::InputValue::new(
+            "address",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        let object_type = object_type.field(address_field);
+
+        // define verified field
+        let verified_field = dynamic::InputValue::new(
+            "verified",
+            dynamic::TypeRef::named(dynamic::TypeRef::BOOLEAN),
+        );
+        let object_type = object_type.field(verified_field);
+
+        registry.register_input_object(object_type)
+    }
+}
+
+impl InputObject for UserBy {
+    const NAME: &'static str = "UserBy";
+}
+
+impl OneofInput for UserBy {
+    const FIELDS: &'static [&'static str] = &["id", "name", "ids", "sort", "email", "me"];
+
+    fn from_field(name: &str, value: async_graphql::Value) -> Result<Self, String> {
+        let object = <Self as InputObject>::NAME;
+        match name {
+            "id" => oneof_variant(object, name, value).map(UserBy::Id),
+            "name" => oneof_variant(object, name, value).map(UserBy::Name),
+            "ids" => oneof_variant(object, name, value).map(UserBy::Ids),
+            "sort" => oneof_variant(object, name, value).map(UserBy::Sort),
+            "email" => {
+                oneof_variant(object, name, value).map(|input: UserByEmailInput| UserBy::Email {
+                    address: input.address,
+                    verified: input.verified,
+                })
+            }
+            "me" => oneof_unit_variant(object, name, value).map(|_| UserBy::Me),
+            _ => Err(format!("{}: unknown field {}", object, name)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for UserBy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <Self as OneofInput>::deserialize_oneof(deserializer)
+    }
+}
+
+impl Register for UserBy {
+    fn register(registry: Registry) -> Registry {
+        // variants with own input types
+        let registry = registry.register::<UserByEmailInput>();
+
+        let object_type = OneofInputObject::new(<Self as InputObject>::NAME);
+
+        // define id field
+        let id_field = OneofField::new("id", dynamic::TypeRef::named(dynamic::TypeRef::ID));
+        let object_type = object_type.field(id_field);
+
+        // define name field
+        let name_field = OneofField::new(
+            "name",
+            dynamic::TypeRef::named(<NameInput as InputObject>::NAME),
+        );
+        let object_type = object_type.field(name_field);
+
+        // define ids field
+        let ids_field =
+            OneofField::new("ids", dynamic::TypeRef::named_nn_list(dynamic::TypeRef::ID));
+        let object_type = object_type.field(ids_field);
+
+        // define sort field
+        let sort_field = OneofField::new("sort", dynamic::TypeRef::named(<Order as Enum>::NAME));
+        let object_type = object_type.field(sort_field);
+
+        // define email field
+        let email_field = OneofField::new(
+            "email",
+            dynamic::TypeRef::named(<UserByEmailInput as InputObject>::NAME),
+        );
+        let object_type = object_type.field(email_field);
+
+        // define me field
+        let me_field = OneofField::new("me", dynamic::TypeRef::named(dynamic::TypeRef::BOOLEAN));
+        let object_type = object_type.field(me_field);
+
+        registry.register_oneof_input_object(object_type)
+    }
+}
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define user field
+        let user_field = dynamic::Field::new(
+            "user",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("by")?.deserialize()?;
+                    let value = parent.resolve_user(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let user_field = user_field.argument(dynamic::InputValue::new(
+            "by",
+            dynamic::TypeRef::named_nn(<UserBy as InputObject>::NAME),
+        ));
+        let object_type = object_type.field(user_field);
+
+        // define users field
+        let users_field = dynamic::Field::new(
+            "users",
+            dynamic::TypeRef::named_nn_list_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("by")?.deserialize()?;
+                    let value = parent.resolve_users(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let users_field = users_field.argument(dynamic::InputValue::new(
+            "by",
+            dynamic::TypeRef::named_nn_list_nn(<UserBy as InputObject>::NAME),
+        ));
+        let object_type = object_type.field(users_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new()
+        .register::<Query>()
+        .register::<Order>()
+        .register::<NameInput>()
+        .register::<UserBy>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                directive @oneOf on INPUT_OBJECT
+                input NameInput {
+                  first: String!
+                  last: String
+                }
+                enum Order {
+                  ASC
+                  DESC
+                }
+                type Query {
+                  user(by: UserBy!): String!
+                  users(by: [UserBy!]!): [String!]!
+                }
+                input UserBy @oneOf {
+                  id: ID
+                  name: NameInput
+                  ids: [ID!]
+                  sort: Order
+                  email: UserByEmailInput
+                  me: Boolean
+                }
+                input UserByEmailInput {
+                  address: String!
+                  verified: Boolean
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                users(by: [
+                    { id: "1" },
+                    { name: { first: "John", last: "Doe" } },
+                    { ids: ["1", "2"] },
+                    { sort: DESC },
+                    { email: { address: "john@example.com", verified: true } },
+                    { me: true },
+                ])
+            }
+        "#;
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "users": [
+                    "id: 1",
+                    "name: John Doe",
+                    "ids: 1, 2",
+                    "sort: last",
+                    "email: john@example.com (true)",
+                    "me",
+                ]
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_variant_error() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                user(by: { me: false })
+            }
+        "#;
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        assert_eq!(res.errors.len(), 1);
+        assert_eq!(res.errors[0].message, "internal: UserBy.me: expected true");
+    }
+
+    #[test]
+    fn test_field_count_errors() {
+        let cases = [
+            (
+                serde_json::json!({ "id": null }),
+                "UserBy requires exactly one of id, name, ids, sort, email, me, none supplied",
+            ),
+            (
+                serde_json::json!({ "id": "1", "me": true }),
+                "UserBy requires exactly one field, supplied id, me",
+            ),
+        ];
+        for (value, message) in cases {
+            let value = async_graphql::Value::from_json(value).unwrap();
+            let err = async_graphql::from_value::<UserBy>(value).err().unwrap();
+            assert_eq!(err.to_string(), message);
+        }
+    }
+
+    struct InvalidQuery;
+
+    impl Object for InvalidQuery {
+        const NAME: &'static str = "Query";
+    }
+
+    impl Register for InvalidQuery {
+        fn register(registry: Registry) -> Registry {
+            let object_type = dynamic::Object::new(<Self as Object>::NAME);
+            let object_type = object_type.field(dynamic::Field::new(
+                "user",
+                dynamic::TypeRef::named(dynamic::TypeRef::STRING),
+                |_ctx| dynamic::FieldFuture::new(async move { Ok(None::<dynamic::FieldValue>) }),
+            ));
+            let input = OneofInputObject::new("InvalidBy");
+            let input = input.field(OneofField::new(
+                "id",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::ID),
+            ));
+            let input = input.field(OneofField::new(
+                "name",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            ));
+            registry
+                .register_object(object_type)
+                .register_oneof_input_object(input)
+        }
+    }
+
+    #[test]
+    fn test_invalid_oneof() {
+        let registry = Registry::new().register::<InvalidQuery>();
+        let schema = dynamic::Schema::build(InvalidQuery::NAME, None, None);
+        assert_eq!(
+            registry.try_build_schema(schema).err().unwrap(),
+            "Invalid oneof input object: \"oneof field InvalidBy.id must be nullable, found ID!, oneof field InvalidBy.name must be nullable, found String!\"",
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{InputObject, Object, Register, Registry};
 use async_graphql::dynamic::DynamicRequestExt;
 use async_graphql::{dynamic, Context, ID};
@@ -62,7 +63,7 @@ impl Register for Query {
         // define foo field
         let foo_field = dynamic::Field::new("foo", dynamic::TypeRef::named_nn(Foo::NAME), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_foo().await;
                 ResolveRef::resolve_ref(value, &ctx)
             })
@@ -123,7 +124,7 @@ impl Register for Foo {
         let by_object_field =
             dynamic::Field::new("by_object", dynamic::TypeRef::named_nn("String"), |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0 = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_object(&arg_0).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -143,7 +144,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0 = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_string(&arg_0).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -162,7 +163,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0: String = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_str(&arg_0).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -182,7 +183,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::INT),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0 = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_int(&arg_0).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -204,7 +205,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::FLOAT),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0 = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_float(&arg_0).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -226,7 +227,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::BOOLEAN),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0 = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_bool(&arg_0).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -248,7 +249,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::ID),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let arg_0 = ctx.args.try_get("input")?.deserialize()?;
                     let value = parent.resolve_by_id(&arg_0).await;
                     ResolveOwned::resolve_owned(value, &ctx)
@@ -0,0 +1,221 @@
+use crate::schema::registry::InputObject;
+use async_graphql::dynamic;
+use async_graphql::Value;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+
+/// field of a oneof input object, it can't be given a default value
+pub struct OneofField {
+    name: String,
+    ty: dynamic::TypeRef,
+}
+
+impl OneofField {
+    pub fn new(name: impl Into<String>, ty: impl Into<dynamic::TypeRef>) -> Self {
+        Self {
+            name: name.into(),
+            ty: ty.into(),
+        }
+    }
+}
+
+/// oneof input object, checked by the registry before it is converted to `dynamic::InputObject`
+pub struct OneofInputObject {
+    name: String,
+    fields: Vec<OneofField>,
+}
+
+impl OneofInputObject {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn field(mut self, field: OneofField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    pub fn type_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn check(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for field in &self.fields {
+            // TypeRef does not expose nullability, the sdl form ends with `!` for non-null
+            if field.ty.to_string().ends_with('!') {
+                errors.push(format!(
+                    "oneof field {}.{} must be nullable, found {}",
+                    self.name, field.name, field.ty
+                ));
+            }
+        }
+        errors
+    }
+
+    pub fn into_input_object(self) -> dynamic::InputObject {
+        let object = dynamic::InputObject::new(self.name).oneof();
+        self.fields.into_iter().fold(object, |object, field| {
+            object.field(dynamic::InputValue::new(field.name, field.ty))
+        })
+    }
+}
+
+/// rust enum that is deserialized from a oneof input object, one variant per field
+pub trait OneofInput: InputObject + Sized {
+    const FIELDS: &'static [&'static str];
+
+    fn from_field(name: &str, value: Value) -> Result<Self, String>;
+
+    fn deserialize_oneof<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = match Value::deserialize(deserializer)? {
+            Value::Object(fields) => fields,
+            _ => {
+                return Err(serde::de::Error::custom(format!(
+                    "{} must be an object",
+                    <Self as InputObject>::NAME
+                )))
+            }
+        };
+        let mut supplied = fields
+            .into_iter()
+            .filter(|(_, value)| !matches!(value, Value::Null))
+            .collect::<Vec<_>>();
+        if supplied.len() != 1 {
+            let names = supplied
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>();
+            let message = if names.is_empty() {
+                format!(
+                    "{} requires exactly one of {}, none supplied",
+                    <Self as InputObject>::NAME,
+                    Self::FIELDS.join(", ")
+                )
+            } else {
+                format!(
+                    "{} requires exactly one field, supplied {}",
+                    <Self as InputObject>::NAME,
+                    names.join(", ")
+                )
+            };
+            return Err(serde::de::Error::custom(message));
+        }
+        let (name, value) = supplied.remove(0);
+        Self::from_field(name.as_str(), value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// parse the value of a oneof field, errors are prefixed with the variant
+pub fn oneof_variant<T: DeserializeOwned>(
+    object: &str,
+    field: &str,
+    value: Value,
+) -> Result<T, String> {
+    async_graphql::from_value(value).map_err(|err| format!("{}.{}: {}", object, field, err))
+}
+
+/// unit variants are exposed as `Boolean` fields that must be `true`
+pub fn oneof_unit_variant(object: &str, field: &str, value: Value) -> Result<(), String> {
+    match value {
+        Value::Boolean(true) => Ok(()),
+        _ => Err(format!("{}.{}: expected true", object, field)),
+    }
+}
+
+#[derive(Clone)]
+pub struct InputField {
+    name: String,
+    ty: dynamic::TypeRef,
+    description: Option<String>,
+}
+
+impl InputField {
+    pub fn new(name: impl Into<String>, ty: impl Into<dynamic::TypeRef>) -> Self {
+        Self {
+            name: name.into(),
+            ty: ty.into(),
+            description: None,
+        }
+    }
+
+    pub fn description(self, description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    fn into_input_value(self) -> dynamic::InputValue {
+        let input_value = dynamic::InputValue::new(self.name, self.ty);
+        match self.description {
+            Some(description) => input_value.description(description),
+            None => input_value,
+        }
+    }
+}
+
+/// input object whose fields can be embedded in other input objects
+pub trait InputFields: InputObject {
+    fn input_fields() -> Vec<InputField>;
+}
+
+/// input object with own fields and fields flattened from other input objects
+pub struct ComposedInputObject {
+    name: String,
+    // (field, defined by)
+    fields: Vec<(InputField, String)>,
+}
+
+impl ComposedInputObject {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn field(mut self, field: InputField) -> Self {
+        let source = self.name.clone();
+        self.fields.push((field, source));
+        self
+    }
+
+    pub fn flatten<T: InputFields>(mut self) -> Self {
+        let fields = T::input_fields()
+            .into_iter()
+            .map(|field| (field, <T as InputObject>::NAME.to_string()));
+        self.fields.extend(fields);
+        self
+    }
+
+    pub fn type_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn check(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (index, (field, source)) in self.fields.iter().enumerate() {
+            let conflict = self.fields[..index]
+                .iter()
+                .find(|(other, _)| other.name == field.name);
+            if let Some((_, other_source)) = conflict {
+                errors.push(format!(
+                    "field {}.{} from {} conflicts with field from {}",
+                    self.name, field.name, source, other_source
+                ));
+            }
+        }
+        errors
+    }
+
+    pub fn into_input_object(self) -> dynamic::InputObject {
+        let object = dynamic::InputObject::new(self.name);
+        self.fields.into_iter().fold(object, |object, (field, _)| {
+            object.field(field.into_input_value())
+        })
+    }
+}
@@ -1,6 +1,8 @@
+use crate::schema::interface::utils::register_interface_fields;
 use crate::schema::output_types::utils::ResolveOwned;
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{
-    ExpandObject, ExpandObjectContext, Interface, Object, Register, Registry,
+    ExpandObject, ExpandObjectContext, Interface, InterfaceFields, Object, Register, Registry,
 };
 use async_graphql::dynamic;
 use async_graphql::dynamic::FieldValue;
@@ -75,19 +77,23 @@ impl Interface for NodeInterface {
     const NAME: &'static str = "Node";
 
     fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+        register_interface_fields::<Self>(interface)
+    }
+}
+
+impl InterfaceFields for NodeInterface {
+    fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
         let id_field = dynamic::InterfaceField::new(
             "id",
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
         );
-        interface.field(id_field)
+        vec![("id", id_field)]
     }
 }
 
 impl Register for NodeInterface {
     fn register(registry: Registry) -> Registry {
-        let interface = dynamic::Interface::new(Self::NAME);
-        let interface = Self::register_fields(interface);
-        registry.register_interface(interface)
+        registry.register_interface_type::<Self>()
     }
 }
 
@@ -95,25 +101,26 @@ struct NamedNodeInterface;
 
 impl Interface for NamedNodeInterface {
     const NAME: &'static str = "NamedNode";
+    const IMPLEMENTS: &'static [&'static str] = &[NodeInterface::NAME];
 
     fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+        register_interface_fields::<Self>(interface)
+    }
+}
+
+impl InterfaceFields for NamedNodeInterface {
+    fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
         let name_field = dynamic::InterfaceField::new(
             "name",
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
         );
-        let interface = interface.field(name_field);
-
-        // register parent interface
-        NodeInterface::register_fields(interface)
+        vec![("name", name_field)]
     }
 }
 
 impl Register for NamedNodeInterface {
     fn register(registry: Registry) -> Registry {
-        let interface = dynamic::Interface::new(Self::NAME);
-        let interface = interface.implement(NodeInterface::NAME);
-        let interface = Self::register_fields(interface);
-        registry.register_interface(interface)
+        registry.register_interface_type::<Self>()
     }
 }
 
@@ -123,19 +130,23 @@ impl Interface for AgedInterface {
     const NAME: &'static str = "Aged";
 
     fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+        register_interface_fields::<Self>(interface)
+    }
+}
+
+impl InterfaceFields for AgedInterface {
+    fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
         let age_field = dynamic::InterfaceField::new(
             "age",
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
         );
-        interface.field(age_field)
+        vec![("age", age_field)]
     }
 }
 
 impl Register for AgedInterface {
     fn register(registry: Registry) -> Registry {
-        let interface = dynamic::Interface::new(Self::NAME);
-        let interface = Self::register_fields(interface);
-        registry.register_interface(interface)
+        registry.register_interface_type::<Self>()
     }
 }
 
@@ -149,7 +160,7 @@ impl Register for Query {
         let user_field =
             dynamic::Field::new("user", dynamic::TypeRef::named_nn(User::NAME), |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     // use borrowed_any because Image is not value
                     Ok(Some(FieldValue::borrowed_any(parent.resolve_user())))
                 })
@@ -222,25 +233,24 @@ impl ExpandObject for NodeExpandUser {
 impl Register for NodeExpandUser {
     fn register(registry: Registry) -> Registry {
         println!("register NodeExpandUser for User");
-        // define id field
-        let id_field = dynamic::Field::new(
-            "id",
-            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
-            |ctx| {
-                dynamic::FieldFuture::new(async move {
-                    let parent = ctx
-                        .parent_value
-                        .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-                    let value = parent.resolve_id();
-                    value.resolve_owned(&ctx)
-                })
-            },
-        );
-
         // register Node object
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |query_object| query_object.field(id_field).implement(NodeInterface::NAME),
+            |query_object| {
+                // define id field
+                let id_field = dynamic::Field::new(
+                    "id",
+                    dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                    |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = parent.resolve_id();
+                            value.resolve_owned(&ctx)
+                        })
+                    },
+                );
+                query_object.field(id_field).implement(NodeInterface::NAME)
+            },
             ExpandObjectContext::new("NodeExpandUser", "id"),
         )
     }
@@ -258,25 +268,22 @@ impl ExpandObject for NamedNodeExpandUser {
 impl Register for NamedNodeExpandUser {
     fn register(registry: Registry) -> Registry {
         println!("register NamedNode for User");
-        // define name field
-        let name_field = dynamic::Field::new(
-            "name",
-            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
-            |ctx| {
-                dynamic::FieldFuture::new(async move {
-                    let parent = ctx
-                        .parent_value
-                        .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-                    let value = parent.resolve_name();
-                    value.resolve_owned(&ctx)
-                })
-            },
-        );
-
         // register NamedNode object
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
             |query_object| {
+                // define name field
+                let name_field = dynamic::Field::new(
+                    "name",
+                    dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                    |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = parent.resolve_name();
+                            value.resolve_owned(&ctx)
+                        })
+                    },
+                );
                 query_object
                     .field(name_field)
                     .implement(NamedNodeInterface::NAME)
@@ -298,25 +305,24 @@ impl ExpandObject for AgedExpandUser {
 impl Register for AgedExpandUser {
     fn register(registry: Registry) -> Registry {
         println!("register Aged for User");
-        // define age field
-        let age_field = dynamic::Field::new(
-            "age",
-            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
-            |ctx| {
-                dynamic::FieldFuture::new(async move {
-                    let parent = ctx
-                        .parent_value
-                        .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-                    let value = parent.resolve_age();
-                    value.resolve_owned(&ctx)
-                })
-            },
-        );
-
         // register Aged object
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |query_object| query_object.field(age_field).implement(AgedInterface::NAME),
+            |query_object| {
+                // define age field
+                let age_field = dynamic::Field::new(
+                    "age",
+                    dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                    |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = parent.resolve_age();
+                            value.resolve_owned(&ctx)
+                        })
+                    },
+                );
+                query_object.field(age_field).implement(AgedInterface::NAME)
+            },
             ExpandObjectContext::new("AgedExpandUser", "age"),
         )
     }
@@ -346,7 +352,7 @@ mod tests {
         let schema = create_schema();
         let sdl = schema.sdl();
 
-        // todo: interface NamedNode should be interface NamedNode implements Node
+        // the sdl export of async-graphql does not print `implements` of interfaces
         assert_eq!(
             normalize_schema(&sdl),
             normalize_schema(
@@ -355,8 +361,8 @@ mod tests {
                       age: String!
                     }
                     interface NamedNode {
-                      name: String!
                       id: String!
+                      name: String!
                     }
                     interface Node {
                       id: String!
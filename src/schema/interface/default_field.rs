@@ -0,0 +1,452 @@
+use crate::schema::interface::utils::{downcast_object, register_interface_fields, InterfaceCast};
+use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{
+    ExtendInterface, Interface, InterfaceFields, Object, Register, Registry,
+};
+use async_graphql::{dynamic, Context};
+
+// user
+
+// mark as interface
+trait NamedNode {
+    fn resolve_id(&self) -> String;
+    fn resolve_name(&self) -> String;
+    // mark as default field
+    fn resolve_display_name(&self) -> String {
+        format!("{} ({})", self.resolve_name(), self.resolve_id())
+    }
+}
+
+// mark as object, implements NamedNode
+struct User {
+    // mark as skip
+    id: String,
+    // mark as skip
+    name: String,
+}
+
+impl NamedNode for User {
+    fn resolve_id(&self) -> String {
+        self.id.clone()
+    }
+    fn resolve_name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+// mark as object, implements NamedNode, override display_name
+struct Bot {
+    // mark as skip
+    id: String,
+    // mark as skip
+    name: String,
+}
+
+impl Bot {
+    async fn resolve_display_name(&self) -> String {
+        format!("[bot] {}", self.name)
+    }
+}
+
+impl NamedNode for Bot {
+    fn resolve_id(&self) -> String {
+        self.id.clone()
+    }
+    fn resolve_name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+// mark as object
+struct Query {
+    // mark as skip
+    user: User,
+    // mark as skip
+    bot: Bot,
+}
+
+impl Query {
+    async fn resolve_user(&self) -> &User {
+        &self.user
+    }
+    async fn resolve_bot(&self) -> &Bot {
+        &self.bot
+    }
+}
+
+// generated
+
+struct NamedNodeInterface;
+
+impl Interface for NamedNodeInterface {
+    const NAME: &'static str = "NamedNode";
+
+    fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+        register_interface_fields::<Self>(interface)
+    }
+}
+
+impl InterfaceFields for NamedNodeInterface {
+    fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
+        let id_field = dynamic::InterfaceField::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        let name_field = dynamic::InterfaceField::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        vec![("id", id_field), ("name", name_field)]
+    }
+}
+
+impl Register for NamedNodeInterface {
+    fn register(registry: Registry) -> Registry {
+        registry
+            .register_interface_type::<Self>()
+            .register_interface_default_field::<NamedNodeDisplayNameField>()
+    }
+}
+
+// default display_name field of NamedNode
+struct NamedNodeDisplayNameField;
+
+impl ExtendInterface for NamedNodeDisplayNameField {
+    type Interface = dyn NamedNode;
+    const INTERFACE: &'static str = NamedNodeInterface::NAME;
+    const FIELD: &'static str = "display_name";
+
+    fn interface_field() -> dynamic::InterfaceField {
+        dynamic::InterfaceField::new(
+            Self::FIELD,
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        )
+    }
+
+    fn object_field(cast: InterfaceCast<dyn NamedNode>) -> dynamic::Field {
+        dynamic::Field::new(
+            Self::FIELD,
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            move |ctx| {
+                let cast = cast.clone();
+                dynamic::FieldFuture::new(async move {
+                    let parent = cast(ctx.parent_value)
+                        .ok_or_else(|| async_graphql::Error::new("internal: not a NamedNode"))?;
+                    let value = parent.resolve_display_name();
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        )
+    }
+}
+
+impl Object for User {
+    const NAME: &'static str = "User";
+}
+
+impl<'a> ResolveRef<'a> for User {
+    fn resolve_ref(
+        &'a self,
+        _ctx: &Context,
+    ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+        Ok(Some(dynamic::FieldValue::borrowed_any(self)))
+    }
+}
+
+impl Register for User {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define id field
+        let id_field = dynamic::Field::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = NamedNode::resolve_id(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(id_field);
+
+        // define name field
+        let name_field = dynamic::Field::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = NamedNode::resolve_name(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(name_field);
+
+        registry
+            .register_object(object_type)
+            .object_fields(<Self as Object>::NAME, &["id", "name"])
+            .implement_interface_as::<dyn NamedNode>(
+                <Self as Object>::NAME,
+                NamedNodeInterface::NAME,
+                |value| downcast_object::<Self>(value).map(|value| value as &dyn NamedNode),
+            )
+    }
+}
+
+impl Object for Bot {
+    const NAME: &'static str = "Bot";
+}
+
+impl<'a> ResolveRef<'a> for Bot {
+    fn resolve_ref(
+        &'a self,
+        _ctx: &Context,
+    ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+        Ok(Some(dynamic::FieldValue::borrowed_any(self)))
+    }
+}
+
+impl Register for Bot {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define id field
+        let id_field = dynamic::Field::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = NamedNode::resolve_id(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(id_field);
+
+        // define name field
+        let name_field = dynamic::Field::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = NamedNode::resolve_name(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(name_field);
+
+        // define display_name field, overrides the NamedNode default
+        let display_name_field = dynamic::Field::new(
+            "display_name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_display_name().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(display_name_field);
+
+        registry
+            .register_object(object_type)
+            .object_fields(<Self as Object>::NAME, &["id", "name", "display_name"])
+            .implement_interface_as::<dyn NamedNode>(
+                <Self as Object>::NAME,
+                NamedNodeInterface::NAME,
+                |value| downcast_object::<Self>(value).map(|value| value as &dyn NamedNode),
+            )
+            .override_interface_field(
+                <Self as Object>::NAME,
+                NamedNodeInterface::NAME,
+                "display_name",
+            )
+    }
+}
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define user field
+        let user_field = dynamic::Field::new(
+            "user",
+            dynamic::TypeRef::named_nn(<User as Object>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_user().await;
+                    ResolveRef::resolve_ref(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(user_field);
+
+        // define bot field
+        let bot_field = dynamic::Field::new(
+            "bot",
+            dynamic::TypeRef::named_nn(<Bot as Object>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_bot().await;
+                    ResolveRef::resolve_ref(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(bot_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+fn create_registry() -> Registry {
+    Registry::new()
+        .register::<Query>()
+        .register::<NamedNodeInterface>()
+        .register::<User>()
+        .register::<Bot>()
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    create_registry().build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+    use async_graphql::Value;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                type Bot implements NamedNode {
+                  id: String!
+                  name: String!
+                  display_name: String!
+                }
+                interface NamedNode {
+                  id: String!
+                  name: String!
+                  display_name: String!
+                }
+                type Query {
+                  user: User!
+                  bot: Bot!
+                }
+                type User implements NamedNode {
+                  id: String!
+                  name: String!
+                  display_name: String!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                user { display_name }
+                bot { display_name }
+            }
+        "#;
+        let root = Query {
+            user: User {
+                id: "user-1".to_string(),
+                name: "john".to_string(),
+            },
+            bot: Bot {
+                id: "bot-1".to_string(),
+                name: "helper".to_string(),
+            },
+        };
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(root));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "user": { "display_name": "john (user-1)" },
+                "bot": { "display_name": "[bot] helper" },
+            }),
+        );
+    }
+
+    #[test]
+    fn test_default_report() {
+        let registry = create_registry();
+        assert_eq!(
+            registry.default_interface_fields(),
+            vec![("User".to_string(), "NamedNode.display_name".to_string())],
+        );
+    }
+
+    // mark as object, implements NamedNode
+    struct Robot;
+
+    impl Object for Robot {
+        const NAME: &'static str = "Robot";
+    }
+
+    impl Register for Robot {
+        fn register(registry: Registry) -> Registry {
+            let field = |name: &str| {
+                dynamic::Field::new(
+                    name,
+                    dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                    |_| dynamic::FieldFuture::new(async { Ok(Some(Value::from("robot"))) }),
+                )
+            };
+            // defines display_name without overriding the default
+            let object_type = dynamic::Object::new(<Self as Object>::NAME)
+                .field(field("id"))
+                .field(field("name"))
+                .field(field("display_name"));
+            registry
+                .register_object(object_type)
+                .object_fields(<Self as Object>::NAME, &["id", "name", "display_name"])
+                .implement_interface_as::<dyn NamedNode>(
+                    <Self as Object>::NAME,
+                    NamedNodeInterface::NAME,
+                    |value| downcast_object::<Bot>(value).map(|value| value as &dyn NamedNode),
+                )
+        }
+    }
+
+    #[test]
+    fn test_defined_by_object() {
+        let registry = create_registry().register::<Robot>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        assert_eq!(
+            registry.try_build_schema(schema).err().unwrap(),
+            "Invalid interface field: \"Can't install NamedNode.display_name on Robot, the object already defines display_name, override it with override_interface_field\"",
+        );
+    }
+}
@@ -0,0 +1,493 @@
+use crate::schema::interface::utils::{
+    downcast_object, register_interface_fields, upcast_interface, InterfaceCast,
+};
+use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{
+    ExtendInterface, Interface, InterfaceFields, Object, Register, Registry,
+};
+use async_graphql::{dynamic, Context};
+use std::any::Any;
+use std::sync::Arc;
+
+// user
+
+// mark as interface
+trait Node {
+    fn resolve_id(&self) -> String;
+}
+
+// mark as interface, implements Node
+trait NamedNode: Node {
+    fn resolve_name(&self) -> String;
+}
+
+// mark as object, implements NamedNode
+struct User {
+    // mark as skip
+    id: String,
+    // mark as skip
+    name: String,
+}
+
+impl Node for User {
+    fn resolve_id(&self) -> String {
+        self.id.clone()
+    }
+}
+
+impl NamedNode for User {
+    fn resolve_name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+// mark as object, implements Node
+struct Post {
+    // mark as skip
+    id: String,
+}
+
+impl Node for Post {
+    fn resolve_id(&self) -> String {
+        self.id.clone()
+    }
+}
+
+// mark as object
+struct Query {
+    // mark as skip
+    user: User,
+    // mark as skip
+    post: Arc<Post>,
+}
+
+impl Query {
+    async fn resolve_user(&self) -> &User {
+        &self.user
+    }
+    async fn resolve_post(&self) -> Arc<Post> {
+        self.post.clone()
+    }
+}
+
+/// extension defined in another module, it does not know the implementers of `Node`
+mod global_id {
+    use super::*;
+
+    // mark as interface field of Node
+    fn resolve_global_id(node: &dyn Node) -> String {
+        format!("node:{}", node.resolve_id())
+    }
+
+    // generated
+
+    pub(super) struct NodeGlobalIdField;
+
+    impl ExtendInterface for NodeGlobalIdField {
+        type Interface = dyn Node;
+        const INTERFACE: &'static str = NodeInterface::NAME;
+        const FIELD: &'static str = "global_id";
+
+        fn interface_field() -> dynamic::InterfaceField {
+            dynamic::InterfaceField::new(
+                Self::FIELD,
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            )
+        }
+
+        fn object_field(cast: InterfaceCast<dyn Node>) -> dynamic::Field {
+            dynamic::Field::new(
+                Self::FIELD,
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                move |ctx| {
+                    let cast = cast.clone();
+                    dynamic::FieldFuture::new(async move {
+                        let parent = cast(ctx.parent_value)
+                            .ok_or_else(|| async_graphql::Error::new("internal: not a Node"))?;
+                        let value = resolve_global_id(parent);
+                        ResolveOwned::resolve_owned(value, &ctx)
+                    })
+                },
+            )
+        }
+    }
+
+    impl Register for NodeGlobalIdField {
+        fn register(registry: Registry) -> Registry {
+            registry.register_interface_field::<Self>()
+        }
+    }
+}
+
+// generated
+
+struct NodeInterface;
+
+impl Interface for NodeInterface {
+    const NAME: &'static str = "Node";
+
+    fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+        register_interface_fields::<Self>(interface)
+    }
+}
+
+impl InterfaceFields for NodeInterface {
+    fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
+        let id_field = dynamic::InterfaceField::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        vec![("id", id_field)]
+    }
+}
+
+impl Register for NodeInterface {
+    fn register(registry: Registry) -> Registry {
+        registry.register_interface_type::<Self>()
+    }
+}
+
+struct NamedNodeInterface;
+
+impl Interface for NamedNodeInterface {
+    const NAME: &'static str = "NamedNode";
+    const IMPLEMENTS: &'static [&'static str] = &[NodeInterface::NAME];
+
+    fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+        register_interface_fields::<Self>(interface)
+    }
+
+    fn parent_cast(parent: &str, cast: &dyn Any) -> Option<Arc<dyn Any>> {
+        match parent {
+            NodeInterface::NAME => upcast_interface::<dyn NamedNode, dyn Node>(cast, |value| value),
+            _ => None,
+        }
+    }
+}
+
+impl InterfaceFields for NamedNodeInterface {
+    fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
+        let name_field = dynamic::InterfaceField::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        vec![("name", name_field)]
+    }
+}
+
+impl Register for NamedNodeInterface {
+    fn register(registry: Registry) -> Registry {
+        registry.register_interface_type::<Self>()
+    }
+}
+
+impl Object for User {
+    const NAME: &'static str = "User";
+}
+
+impl<'a> ResolveRef<'a> for User {
+    fn resolve_ref(
+        &'a self,
+        _ctx: &Context,
+    ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+        Ok(Some(dynamic::FieldValue::borrowed_any(self)))
+    }
+}
+
+impl Register for User {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define id field
+        let id_field = dynamic::Field::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = Node::resolve_id(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(id_field);
+
+        // define name field
+        let name_field = dynamic::Field::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = NamedNode::resolve_name(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(name_field);
+
+        registry
+            .register_object(object_type)
+            .implement_interface_as::<dyn NamedNode>(
+                <Self as Object>::NAME,
+                NamedNodeInterface::NAME,
+                |value| downcast_object::<Self>(value).map(|value| value as &dyn NamedNode),
+            )
+    }
+}
+
+impl Object for Post {
+    const NAME: &'static str = "Post";
+}
+
+impl<'a> ResolveRef<'a> for Post {
+    fn resolve_ref(
+        &'a self,
+        _ctx: &Context,
+    ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+        Ok(Some(dynamic::FieldValue::borrowed_any(self)))
+    }
+}
+
+impl Register for Post {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define id field
+        let id_field = dynamic::Field::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = Node::resolve_id(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(id_field);
+
+        registry
+            .register_object(object_type)
+            .implement_interface_as::<dyn Node>(
+                <Self as Object>::NAME,
+                NodeInterface::NAME,
+                |value| downcast_object::<Self>(value).map(|value| value as &dyn Node),
+            )
+    }
+}
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define user field
+        let user_field = dynamic::Field::new(
+            "user",
+            dynamic::TypeRef::named_nn(<User as Object>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_user().await;
+                    ResolveRef::resolve_ref(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(user_field);
+
+        // define post field
+        let post_field = dynamic::Field::new(
+            "post",
+            dynamic::TypeRef::named_nn(<Post as Object>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_post().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(post_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new()
+        .register::<Query>()
+        .register::<NodeInterface>()
+        .register::<NamedNodeInterface>()
+        .register::<User>()
+        .register::<Post>()
+        .register::<global_id::NodeGlobalIdField>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                interface NamedNode {
+                  id: String!
+                  name: String!
+                  global_id: String!
+                }
+                interface Node {
+                  id: String!
+                  global_id: String!
+                }
+                type Post implements Node {
+                  id: String!
+                  global_id: String!
+                }
+                type Query {
+                  user: User!
+                  post: Post!
+                }
+                type User implements NamedNode & Node {
+                  id: String!
+                  name: String!
+                  global_id: String!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                user { id name global_id }
+                post { id global_id }
+            }
+        "#;
+        let root = Query {
+            user: User {
+                id: "user-1".to_string(),
+                name: "john".to_string(),
+            },
+            // shared with `Arc`, resolved through the Node cast of Post
+            post: Arc::new(Post {
+                id: "post-1".to_string(),
+            }),
+        };
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(root));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "user": { "id": "user-1", "name": "john", "global_id": "node:user-1" },
+                "post": { "id": "post-1", "global_id": "node:post-1" },
+            }),
+        );
+    }
+
+    // mark as object, implements Node
+    struct Comment;
+
+    impl Object for Comment {
+        const NAME: &'static str = "Comment";
+    }
+
+    impl Register for Comment {
+        fn register(registry: Registry) -> Registry {
+            let object_type = dynamic::Object::new(<Self as Object>::NAME);
+            // implemented without a trait object cast
+            registry
+                .register_object(object_type)
+                .implement_interface(<Self as Object>::NAME, NodeInterface::NAME)
+        }
+    }
+
+    #[test]
+    fn test_missing_cast() {
+        let registry = Registry::new()
+            .register::<Query>()
+            .register::<NodeInterface>()
+            .register::<Comment>()
+            .register::<global_id::NodeGlobalIdField>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        assert_eq!(
+            registry.try_build_schema(schema).err().unwrap(),
+            "Invalid interface field: \"Can't install Node.global_id on Comment, the object is not registered as Node trait object\"",
+        );
+    }
+
+    // mark as interface field of Node
+    struct NodeIdField;
+
+    impl ExtendInterface for NodeIdField {
+        type Interface = dyn Node;
+        const INTERFACE: &'static str = NodeInterface::NAME;
+        // defined by Node itself
+        const FIELD: &'static str = "id";
+
+        fn interface_field() -> dynamic::InterfaceField {
+            dynamic::InterfaceField::new(
+                Self::FIELD,
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            )
+        }
+
+        fn object_field(cast: InterfaceCast<dyn Node>) -> dynamic::Field {
+            dynamic::Field::new(
+                Self::FIELD,
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                move |ctx| {
+                    let cast = cast.clone();
+                    dynamic::FieldFuture::new(async move {
+                        let parent = cast(ctx.parent_value)
+                            .ok_or_else(|| async_graphql::Error::new("internal: not a Node"))?;
+                        ResolveOwned::resolve_owned(parent.resolve_id(), &ctx)
+                    })
+                },
+            )
+        }
+    }
+
+    impl Register for NodeIdField {
+        fn register(registry: Registry) -> Registry {
+            registry.register_interface_field::<Self>()
+        }
+    }
+
+    #[test]
+    fn test_existing_field() {
+        let registry = Registry::new()
+            .register::<Query>()
+            .register::<NodeInterface>()
+            .register::<NamedNodeInterface>()
+            .register::<User>()
+            .register::<Post>()
+            .register::<NodeIdField>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        assert_eq!(
+            registry.try_build_schema(schema).err().unwrap(),
+            "Invalid interface field: \"Can't define Node.id, the field already exists on Node, NamedNode\"",
+        );
+    }
+}
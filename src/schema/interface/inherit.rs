@@ -0,0 +1,616 @@
+use crate::schema::interface::utils::register_interface_fields;
+use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Interface, InterfaceFields, Object, Register, Registry};
+use async_graphql::{dynamic, Context};
+
+// user
+
+// mark as interface
+trait Node {
+    fn resolve_id(&self) -> &str;
+}
+
+// mark as interface
+trait NamedNode: Node {
+    fn resolve_name(&self) -> &str;
+}
+
+// mark as interface
+trait Person: NamedNode {
+    fn resolve_email(&self) -> &str;
+}
+
+// mark as object, implements Person
+struct User {
+    // mark as skip
+    id: String,
+    // mark as skip
+    name: String,
+    // mark as skip
+    email: String,
+}
+
+impl Node for User {
+    fn resolve_id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl NamedNode for User {
+    fn resolve_name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Person for User {
+    fn resolve_email(&self) -> &str {
+        &self.email
+    }
+}
+
+// mark as object, implements Node
+struct Tag {
+    // mark as skip
+    id: String,
+}
+
+impl Node for Tag {
+    fn resolve_id(&self) -> &str {
+        &self.id
+    }
+}
+
+// mark as object
+struct Query {
+    // mark as skip
+    user: User,
+    // mark as skip
+    tag: Tag,
+}
+
+impl Query {
+    async fn resolve_user(&self) -> &User {
+        &self.user
+    }
+    async fn resolve_tag(&self) -> &Tag {
+        &self.tag
+    }
+}
+
+// generated
+
+struct NodeInterface;
+
+impl Interface for NodeInterface {
+    const NAME: &'static str = "Node";
+
+    fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+        register_interface_fields::<Self>(interface)
+    }
+}
+
+impl InterfaceFields for NodeInterface {
+    fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
+        let id_field = dynamic::InterfaceField::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        vec![("id", id_field)]
+    }
+}
+
+impl Register for NodeInterface {
+    fn register(registry: Registry) -> Registry {
+        registry.register_interface_type::<Self>()
+    }
+}
+
+struct NamedNodeInterface;
+
+impl Interface for NamedNodeInterface {
+    const NAME: &'static str = "NamedNode";
+    const IMPLEMENTS: &'static [&'static str] = &[NodeInterface::NAME];
+
+    fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+        register_interface_fields::<Self>(interface)
+    }
+}
+
+impl InterfaceFields for NamedNodeInterface {
+    fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
+        let name_field = dynamic::InterfaceField::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        vec![("name", name_field)]
+    }
+}
+
+impl Register for NamedNodeInterface {
+    fn register(registry: Registry) -> Registry {
+        registry.register_interface_type::<Self>()
+    }
+}
+
+struct PersonInterface;
+
+impl Interface for PersonInterface {
+    const NAME: &'static str = "Person";
+    const IMPLEMENTS: &'static [&'static str] = &[NamedNodeInterface::NAME];
+
+    fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+        register_interface_fields::<Self>(interface)
+    }
+}
+
+impl InterfaceFields for PersonInterface {
+    fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
+        let email_field = dynamic::InterfaceField::new(
+            "email",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        vec![("email", email_field)]
+    }
+}
+
+impl Register for PersonInterface {
+    fn register(registry: Registry) -> Registry {
+        registry.register_interface_type::<Self>()
+    }
+}
+
+impl Object for User {
+    const NAME: &'static str = "User";
+}
+
+impl<'a> ResolveRef<'a> for User {
+    fn resolve_ref(
+        &'a self,
+        _ctx: &Context,
+    ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+        Ok(Some(dynamic::FieldValue::borrowed_any(self)))
+    }
+}
+
+impl Register for User {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define id field
+        let id_field = dynamic::Field::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = Node::resolve_id(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(id_field);
+
+        // define name field
+        let name_field = dynamic::Field::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = NamedNode::resolve_name(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(name_field);
+
+        // define email field
+        let email_field = dynamic::Field::new(
+            "email",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = Person::resolve_email(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(email_field);
+
+        // NamedNode and Node are added by the registry
+        registry
+            .register_object(object_type)
+            .implement_interface(<Self as Object>::NAME, PersonInterface::NAME)
+    }
+}
+
+impl Object for Tag {
+    const NAME: &'static str = "Tag";
+}
+
+impl<'a> ResolveRef<'a> for Tag {
+    fn resolve_ref(
+        &'a self,
+        _ctx: &Context,
+    ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+        Ok(Some(dynamic::FieldValue::borrowed_any(self)))
+    }
+}
+
+impl Register for Tag {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define id field
+        let id_field = dynamic::Field::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = Node::resolve_id(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(id_field);
+
+        registry
+            .register_object(object_type)
+            .implement_interface(<Self as Object>::NAME, NodeInterface::NAME)
+    }
+}
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define user field
+        let user_field = dynamic::Field::new(
+            "user",
+            dynamic::TypeRef::named_nn(<User as Object>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_user().await;
+                    ResolveRef::resolve_ref(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(user_field);
+
+        // define tag field
+        let tag_field = dynamic::Field::new(
+            "tag",
+            dynamic::TypeRef::named_nn(<Tag as Object>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_tag().await;
+                    ResolveRef::resolve_ref(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(tag_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new()
+        .register::<Query>()
+        .register::<NodeInterface>()
+        .register::<NamedNodeInterface>()
+        .register::<PersonInterface>()
+        .register::<User>()
+        .register::<Tag>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+    use async_graphql::Value;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        // the sdl export of async-graphql does not print `implements` of interfaces
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                interface NamedNode {
+                  id: String!
+                  name: String!
+                }
+                interface Node {
+                  id: String!
+                }
+                interface Person {
+                  id: String!
+                  name: String!
+                  email: String!
+                }
+                type Query {
+                  user: User!
+                  tag: Tag!
+                }
+                type Tag implements Node {
+                  id: String!
+                }
+                type User implements Person & NamedNode & Node {
+                  id: String!
+                  name: String!
+                  email: String!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    fn create_root() -> Query {
+        Query {
+            user: User {
+                id: "user-1".to_string(),
+                name: "john".to_string(),
+                email: "john@example.com".to_string(),
+            },
+            tag: Tag {
+                id: "tag-1".to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                user { id name email }
+                tag { id }
+            }
+        "#;
+        let req = async_graphql::Request::new(query)
+            .root_value(dynamic::FieldValue::owned_any(create_root()));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "user": { "id": "user-1", "name": "john", "email": "john@example.com" },
+                "tag": { "id": "tag-1" },
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_introspection() {
+        let schema = create_schema();
+        // dynamic schemas of async-graphql only match fragments on the concrete object type,
+        // so the inherited implementations are checked through introspection
+        let query = r#"
+            query {
+                user: __type(name: "User") { interfaces { name } }
+                node: __type(name: "Node") { possibleTypes { name } }
+                named_node: __type(name: "NamedNode") { possibleTypes { name } }
+            }
+        "#;
+        let res = schema.execute(query).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data["user"],
+            serde_json::json!({
+                "interfaces": [{ "name": "Person" }, { "name": "NamedNode" }, { "name": "Node" }]
+            }),
+        );
+        // possible types are collected from a hash map, compare them sorted
+        let mut node_types = data["node"]["possibleTypes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|ty| ty["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        node_types.sort();
+        assert_eq!(node_types, vec!["Tag", "User"]);
+        assert_eq!(
+            data["named_node"],
+            serde_json::json!({ "possibleTypes": [{ "name": "User" }] }),
+        );
+    }
+
+    // mark as interface, implements Loop
+    struct LoopInterface;
+
+    impl Interface for LoopInterface {
+        const NAME: &'static str = "Loop";
+        const IMPLEMENTS: &'static [&'static str] = &["Loop"];
+
+        fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+            register_interface_fields::<Self>(interface)
+        }
+    }
+
+    impl InterfaceFields for LoopInterface {
+        fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn test_cycle() {
+        let registry = Registry::new()
+            .register::<Query>()
+            .register_interface_type::<LoopInterface>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        assert_eq!(
+            registry.try_build_schema(schema).err().unwrap(),
+            "Invalid interface hierarchy: \"interface Loop implements itself through Loop\"",
+        );
+    }
+
+    #[test]
+    fn test_missing_parent() {
+        let registry = Registry::new()
+            .register::<Query>()
+            .register::<NamedNodeInterface>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        assert_eq!(
+            registry.try_build_schema(schema).err().unwrap(),
+            "Invalid interface hierarchy: \"Can't find interface Node implemented by NamedNode\"",
+        );
+    }
+
+    #[test]
+    fn test_plain_parent() {
+        let registry = Registry::new()
+            .register::<Query>()
+            .register_interface(NodeInterface::register_fields(dynamic::Interface::new(
+                NodeInterface::NAME,
+            )))
+            .register::<NamedNodeInterface>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        assert_eq!(
+            registry.try_build_schema(schema).err().unwrap(),
+            "Invalid interface hierarchy: \"Can't inherit interface Node in NamedNode, it is not registered with register_interface_type\"",
+        );
+    }
+
+    // mark as interface, implements Node
+    struct LabeledNodeInterface;
+
+    impl Interface for LabeledNodeInterface {
+        const NAME: &'static str = "LabeledNode";
+        const IMPLEMENTS: &'static [&'static str] = &[NodeInterface::NAME];
+
+        fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+            register_interface_fields::<Self>(interface)
+        }
+    }
+
+    impl InterfaceFields for LabeledNodeInterface {
+        fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
+            // redefines the id of Node
+            let id_field = dynamic::InterfaceField::new(
+                "id",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            )
+            .description("label of the node");
+            vec![("id", id_field)]
+        }
+    }
+
+    // mark as object, implements NamedNode
+    struct Account;
+
+    impl Object for Account {
+        const NAME: &'static str = "Account";
+    }
+
+    impl Register for Account {
+        fn register(registry: Registry) -> Registry {
+            let field = |name: &str| {
+                dynamic::Field::new(
+                    name,
+                    dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                    |_| dynamic::FieldFuture::new(async { Ok(Some(Value::from("account"))) }),
+                )
+            };
+            // implements Node itself, the registry only adds NamedNode
+            let object_type = dynamic::Object::new(<Self as Object>::NAME)
+                .field(field("id"))
+                .field(field("name"))
+                .implement(NodeInterface::NAME);
+            registry
+                .register_object(object_type)
+                .object_implements(<Self as Object>::NAME, &[NodeInterface::NAME])
+                .implement_interface(<Self as Object>::NAME, NamedNodeInterface::NAME)
+        }
+    }
+
+    fn create_schema_with(registry: Registry) -> Result<dynamic::Schema, String> {
+        let registry = registry
+            .register::<Query>()
+            .register::<NodeInterface>()
+            .register::<NamedNodeInterface>()
+            .register::<PersonInterface>()
+            .register::<User>()
+            .register::<Tag>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        Ok(registry.try_build_schema(schema)?.finish().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_redefined_field() {
+        // the interface is only exported with an implementer
+        let registry = Registry::new()
+            .register_interface_type::<LabeledNodeInterface>()
+            .implement_interface(<Tag as Object>::NAME, LabeledNodeInterface::NAME);
+        let schema = create_schema_with(registry).unwrap();
+        let query = r#"{ __type(name: "LabeledNode") { fields { name description } } }"#;
+        let res = schema.execute(query).await;
+        assert_eq!(
+            res.data.into_json().unwrap(),
+            serde_json::json!({
+                "__type": { "fields": [{ "name": "id", "description": "label of the node" }] }
+            }),
+        );
+    }
+
+    #[test]
+    fn test_field_defined_twice() {
+        struct TwiceInterface;
+
+        impl Interface for TwiceInterface {
+            const NAME: &'static str = "Twice";
+
+            fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+                register_interface_fields::<Self>(interface)
+            }
+        }
+
+        impl InterfaceFields for TwiceInterface {
+            fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
+                let ty = || dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING);
+                vec![
+                    ("id", dynamic::InterfaceField::new("id", ty())),
+                    ("id", dynamic::InterfaceField::new("id", ty())),
+                ]
+            }
+        }
+
+        let registry = Registry::new().register_interface_type::<TwiceInterface>();
+        assert_eq!(
+            create_schema_with(registry).err().unwrap(),
+            "Invalid interface hierarchy: \"interface Twice defines field id twice\"",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_implemented_by_object() {
+        let registry = Registry::new().register::<Account>();
+        let schema = create_schema_with(registry).unwrap();
+        let query = r#"{ __type(name: "Account") { interfaces { name } } }"#;
+        let res = schema.execute(query).await;
+        assert_eq!(
+            res.data.into_json().unwrap(),
+            serde_json::json!({
+                "__type": { "interfaces": [{ "name": "Node" }, { "name": "NamedNode" }] }
+            }),
+        );
+    }
+}
@@ -1,7 +1,12 @@
 pub mod auto;
+pub mod default_field;
+pub mod extension;
+pub mod inherit;
 pub mod output;
 pub mod output_list;
 pub mod output_ref;
 pub mod resolved;
 pub mod simple;
+pub mod trait_object;
+pub mod type_map;
 pub mod utils;
@@ -1,5 +1,6 @@
 use crate::schema::interface::utils::AnyBox;
 use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{
     ExpandObject, ExpandObjectContext, Interface, Object, Register, Registry,
 };
@@ -49,9 +50,22 @@ impl Node<'_> for BarNode {
     }
 }
 
-struct Query;
+struct Query {
+    pinned: FooNode,
+}
 
 impl Query {
+    fn resolve_pinned(&self) -> NodeInterface<'_> {
+        NodeInterface::new_borrowed(&self.pinned)
+    }
+    fn resolve_nodes(&self) -> Vec<NodeInterface<'_>> {
+        vec![
+            NodeInterface::new_borrowed(&self.pinned),
+            NodeInterface::new_owned(BarNode {
+                id: "bar-2".to_string(),
+            }),
+        ]
+    }
     fn resolve_node(&self, id: &str) -> Option<NodeInterface> {
         match id {
             "foo-1" => Some(NodeInterface::new_owned(FooNode {
@@ -103,12 +117,11 @@ impl NodeInterface<'_> {
 }
 
 impl NodeInterface<'_> {
-    #[allow(dead_code)]
-    fn new_borrowed<'a, T>(_value: &'a T) -> NodeInterface<'a>
+    fn new_borrowed<'a, T>(value: &'a T) -> NodeInterface<'a>
     where
-        T: Node<'a> + Send + Sync + Object,
+        T: Node<'a> + Object + Send + Sync + 'static,
     {
-        todo!("NodeInterface::new_borrowed")
+        NodeInterface(AnyBox::new_borrowed(value, <T as Object>::NAME.to_string()))
     }
 }
 
@@ -151,7 +164,7 @@ impl Register for FooNode {
         // name field
         let name_field = dynamic::Field::new("name", dynamic::TypeRef::named_nn("String"), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_name().await;
                 value.resolve_owned(&ctx)
             })
@@ -161,7 +174,7 @@ impl Register for FooNode {
         // foo field
         let foo_field = dynamic::Field::new("foo", dynamic::TypeRef::named_nn("String"), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_foo().await;
                 value.resolve_owned(&ctx)
             })
@@ -180,18 +193,19 @@ impl ExpandObject for NodeExpandFooNode {
 
 impl Register for NodeExpandFooNode {
     fn register(registry: Registry) -> Registry {
-        let id_field = dynamic::Field::new("id", dynamic::TypeRef::named_nn("String"), |ctx| {
-            dynamic::FieldFuture::new(async move {
-                let parent = ctx
-                    .parent_value
-                    .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-                let value = parent.resolve_id();
-                ResolveOwned::resolve_owned(value, &ctx)
-            })
-        });
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |object| object.field(id_field),
+            |object| {
+                let id_field =
+                    dynamic::Field::new("id", dynamic::TypeRef::named_nn("String"), |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = parent.resolve_id();
+                            ResolveOwned::resolve_owned(value, &ctx)
+                        })
+                    });
+                object.field(id_field)
+            },
             ExpandObjectContext::new("NodeExpandFooNode", "id"),
         )
     }
@@ -211,7 +225,7 @@ impl Register for BarNode {
         // name field
         let name_field = dynamic::Field::new("name", dynamic::TypeRef::named_nn("String"), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_name().await;
                 value.resolve_owned(&ctx)
             })
@@ -222,7 +236,7 @@ impl Register for BarNode {
 
         let bar_field = dynamic::Field::new("bar", dynamic::TypeRef::named_nn("String"), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_bar().await;
                 value.resolve_owned(&ctx)
             })
@@ -242,18 +256,19 @@ impl ExpandObject for NodeExpandBarNode {
 
 impl Register for NodeExpandBarNode {
     fn register(registry: Registry) -> Registry {
-        let id_field = dynamic::Field::new("id", dynamic::TypeRef::named_nn("String"), |ctx| {
-            dynamic::FieldFuture::new(async move {
-                let parent = ctx
-                    .parent_value
-                    .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-                let value = parent.resolve_id();
-                ResolveOwned::resolve_owned(value, &ctx)
-            })
-        });
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |object| object.field(id_field),
+            |object| {
+                let id_field =
+                    dynamic::Field::new("id", dynamic::TypeRef::named_nn("String"), |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = parent.resolve_id();
+                            ResolveOwned::resolve_owned(value, &ctx)
+                        })
+                    });
+                object.field(id_field)
+            },
             ExpandObjectContext::new("NodeExpandBarNode", "id"),
         )
     }
@@ -272,7 +287,7 @@ impl Register for Query {
         let node_field = dynamic::Field::new("node", dynamic::TypeRef::named("Node"), |ctx| {
             dynamic::FieldFuture::new(async move {
                 let id: String = ctx.args.try_get("id")?.deserialize()?;
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_node(&id);
                 ResolveOwned::resolve_owned(value, &ctx)
             })
@@ -283,6 +298,28 @@ impl Register for Query {
 
         let object_type = object_type.field(node_field);
 
+        // pinned field
+        let pinned_field =
+            dynamic::Field::new("pinned", dynamic::TypeRef::named_nn("Node"), |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_pinned();
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            });
+        let object_type = object_type.field(pinned_field);
+
+        // nodes field
+        let nodes_field =
+            dynamic::Field::new("nodes", dynamic::TypeRef::named_nn_list_nn("Node"), |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_nodes();
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            });
+        let object_type = object_type.field(nodes_field);
+
         registry.register_object(object_type)
     }
 }
@@ -329,6 +366,8 @@ mod tests {
                 }
                 type Query {
                   node(id: String!): Node
+                  pinned: Node!
+                  nodes: [Node!]!
                 }
                 schema {
                   query: Query
@@ -340,7 +379,11 @@ mod tests {
     }
 
     fn create_root() -> Query {
-        Query
+        Query {
+            pinned: FooNode {
+                id: "foo-pinned".to_string(),
+            },
+        }
     }
 
     #[tokio::test]
@@ -445,4 +488,35 @@ mod tests {
             }),
         );
     }
+
+    #[tokio::test]
+    async fn test_borrowed_query() {
+        let schema = create_schema();
+        let query = r#"
+        query {
+            pinned {
+                __typename
+                id
+            }
+            nodes {
+                __typename
+                id
+            }
+        }
+       "#;
+        let req =
+            async_graphql::Request::new(query).root_value(FieldValue::owned_any(create_root()));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "pinned": { "__typename": "FooNode", "id": "foo-pinned" },
+                "nodes": [
+                    { "__typename": "FooNode", "id": "foo-pinned" },
+                    { "__typename": "BarNode", "id": "bar-2" },
+                ],
+            }),
+        );
+    }
 }
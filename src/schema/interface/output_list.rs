@@ -1,5 +1,6 @@
 use crate::schema::interface::utils::AnyBox;
 use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{
     ExpandObject, ExpandObjectContext, Interface, Object, Register, Registry,
 };
@@ -158,7 +159,7 @@ impl Register for FooNode {
         // name field
         let name_field = dynamic::Field::new("name", dynamic::TypeRef::named_nn("String"), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_name().await;
                 value.resolve_owned(&ctx)
             })
@@ -168,7 +169,7 @@ impl Register for FooNode {
         // foo field
         let foo_field = dynamic::Field::new("foo", dynamic::TypeRef::named_nn("String"), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_foo().await;
                 value.resolve_owned(&ctx)
             })
@@ -187,18 +188,19 @@ impl ExpandObject for NodeExpandFooNode {
 
 impl Register for NodeExpandFooNode {
     fn register(registry: Registry) -> Registry {
-        let id_field = dynamic::Field::new("id", dynamic::TypeRef::named_nn("String"), |ctx| {
-            dynamic::FieldFuture::new(async move {
-                let parent = ctx
-                    .parent_value
-                    .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-                let value = parent.resolve_id();
-                ResolveOwned::resolve_owned(value, &ctx)
-            })
-        });
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |object| object.field(id_field),
+            |object| {
+                let id_field =
+                    dynamic::Field::new("id", dynamic::TypeRef::named_nn("String"), |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = parent.resolve_id();
+                            ResolveOwned::resolve_owned(value, &ctx)
+                        })
+                    });
+                object.field(id_field)
+            },
             ExpandObjectContext::new("NodeExpandFooNode", "id"),
         )
     }
@@ -218,7 +220,7 @@ impl Register for BarNode {
         // name field
         let name_field = dynamic::Field::new("name", dynamic::TypeRef::named_nn("String"), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_name().await;
                 value.resolve_owned(&ctx)
             })
@@ -229,7 +231,7 @@ impl Register for BarNode {
 
         let bar_field = dynamic::Field::new("bar", dynamic::TypeRef::named_nn("String"), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_bar().await;
                 value.resolve_owned(&ctx)
             })
@@ -249,18 +251,19 @@ impl ExpandObject for NodeExpandBarNode {
 
 impl Register for NodeExpandBarNode {
     fn register(registry: Registry) -> Registry {
-        let id_field = dynamic::Field::new("id", dynamic::TypeRef::named_nn("String"), |ctx| {
-            dynamic::FieldFuture::new(async move {
-                let parent = ctx
-                    .parent_value
-                    .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-                let value = parent.resolve_id();
-                ResolveOwned::resolve_owned(value, &ctx)
-            })
-        });
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |object| object.field(id_field),
+            |object| {
+                let id_field =
+                    dynamic::Field::new("id", dynamic::TypeRef::named_nn("String"), |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = parent.resolve_id();
+                            ResolveOwned::resolve_owned(value, &ctx)
+                        })
+                    });
+                object.field(id_field)
+            },
             ExpandObjectContext::new("NodeExpandBarNode", "id"),
         )
     }
@@ -279,7 +282,7 @@ impl Register for Query {
         let node_field =
             dynamic::Field::new("nodes", dynamic::TypeRef::named_nn_list_nn("Node"), |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_nodes();
                     ResolveOwned::resolve_owned(value, &ctx)
                 })
@@ -294,7 +297,7 @@ impl Register for Query {
             dynamic::TypeRef::named_nn_list_nn("Node"),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_new_nodes();
                     ResolveOwned::resolve_owned(value, &ctx)
                 })
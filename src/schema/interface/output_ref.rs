@@ -1,5 +1,6 @@
 use crate::schema::interface::utils::AnyBox;
 use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{
     ExpandObject, ExpandObjectContext, Interface, Object, Register, Registry,
 };
@@ -150,7 +151,7 @@ impl Register for FooNode {
         // name field
         let name_field = dynamic::Field::new("name", dynamic::TypeRef::named_nn("String"), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_name().await;
                 value.resolve_owned(&ctx)
             })
@@ -160,7 +161,7 @@ impl Register for FooNode {
         // foo field
         let foo_field = dynamic::Field::new("foo", dynamic::TypeRef::named_nn("String"), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_foo().await;
                 value.resolve_owned(&ctx)
             })
@@ -179,18 +180,19 @@ impl ExpandObject for NodeExpandFooNode {
 
 impl Register for NodeExpandFooNode {
     fn register(registry: Registry) -> Registry {
-        let id_field = dynamic::Field::new("id", dynamic::TypeRef::named_nn("String"), |ctx| {
-            dynamic::FieldFuture::new(async move {
-                let parent = ctx
-                    .parent_value
-                    .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-                let value = parent.resolve_id();
-                ResolveOwned::resolve_owned(value, &ctx)
-            })
-        });
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |object| object.field(id_field),
+            |object| {
+                let id_field =
+                    dynamic::Field::new("id", dynamic::TypeRef::named_nn("String"), |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = parent.resolve_id();
+                            ResolveOwned::resolve_owned(value, &ctx)
+                        })
+                    });
+                object.field(id_field)
+            },
             ExpandObjectContext::new("NodeExpandFooNode", "id"),
         )
     }
@@ -210,7 +212,7 @@ impl Register for BarNode {
         // name field
         let name_field = dynamic::Field::new("name", dynamic::TypeRef::named_nn("String"), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_name().await;
                 value.resolve_owned(&ctx)
             })
@@ -221,7 +223,7 @@ impl Register for BarNode {
 
         let bar_field = dynamic::Field::new("bar", dynamic::TypeRef::named_nn("String"), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_bar().await;
                 value.resolve_owned(&ctx)
             })
@@ -241,18 +243,19 @@ impl ExpandObject for NodeExpandBarNode {
 
 impl Register for NodeExpandBarNode {
     fn register(registry: Registry) -> Registry {
-        let id_field = dynamic::Field::new("id", dynamic::TypeRef::named_nn("String"), |ctx| {
-            dynamic::FieldFuture::new(async move {
-                let parent = ctx
-                    .parent_value
-                    .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-                let value = parent.resolve_id();
-                ResolveOwned::resolve_owned(value, &ctx)
-            })
-        });
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |object| object.field(id_field),
+            |object| {
+                let id_field =
+                    dynamic::Field::new("id", dynamic::TypeRef::named_nn("String"), |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = parent.resolve_id();
+                            ResolveOwned::resolve_owned(value, &ctx)
+                        })
+                    });
+                object.field(id_field)
+            },
             ExpandObjectContext::new("NodeExpandBarNode", "id"),
         )
     }
@@ -271,7 +274,7 @@ impl Register for Query {
         let node_field = dynamic::Field::new("node", dynamic::TypeRef::named("Node"), |ctx| {
             dynamic::FieldFuture::new(async move {
                 let id: String = ctx.args.try_get("id")?.deserialize()?;
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_node(&id);
                 ResolveOwned::resolve_owned(value, &ctx)
             })
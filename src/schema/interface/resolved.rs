@@ -1,4 +1,5 @@
 use crate::schema::output_types::utils::ResolveOwned;
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{
     ExpandObject, ExpandObjectContext, Interface, Object, Register, Registry,
 };
@@ -149,7 +150,7 @@ impl Register for Query {
         let user_field =
             dynamic::Field::new("user", dynamic::TypeRef::named_nn(User::NAME), |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     // use borrowed_any because Image is not value
                     Ok(Some(FieldValue::borrowed_any(parent.resolve_user())))
                 })
@@ -189,25 +190,24 @@ impl ExpandObject for NodeExpandUser {
 
 impl Register for NodeExpandUser {
     fn register(registry: Registry) -> Registry {
-        // define id field
-        let id_field = dynamic::Field::new(
-            "id",
-            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
-            |ctx| {
-                dynamic::FieldFuture::new(async move {
-                    let parent = ctx
-                        .parent_value
-                        .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-                    let value = parent.resolve_id();
-                    value.resolve_owned(&ctx)
-                })
-            },
-        );
-
         // register Node object
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |query_object| query_object.field(id_field).implement(NodeInterface::NAME),
+            |query_object| {
+                // define id field
+                let id_field = dynamic::Field::new(
+                    "id",
+                    dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                    |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = parent.resolve_id();
+                            value.resolve_owned(&ctx)
+                        })
+                    },
+                );
+                query_object.field(id_field).implement(NodeInterface::NAME)
+            },
             ExpandObjectContext::new("NodeExpandUser", "id"),
         )
     }
@@ -222,25 +222,22 @@ impl ExpandObject for NamedNodeExpandUser {
 
 impl Register for NamedNodeExpandUser {
     fn register(registry: Registry) -> Registry {
-        // define name field
-        let name_field = dynamic::Field::new(
-            "name",
-            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
-            |ctx| {
-                dynamic::FieldFuture::new(async move {
-                    let parent = ctx
-                        .parent_value
-                        .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-                    let value = parent.resolve_name();
-                    value.resolve_owned(&ctx)
-                })
-            },
-        );
-
         // register NamedNode object
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
             |query_object| {
+                // define name field
+                let name_field = dynamic::Field::new(
+                    "name",
+                    dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                    |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = parent.resolve_name();
+                            value.resolve_owned(&ctx)
+                        })
+                    },
+                );
                 query_object
                     .field(name_field)
                     .implement(NamedNodeInterface::NAME)
@@ -259,25 +256,24 @@ impl ExpandObject for AgedExpandUser {
 
 impl Register for AgedExpandUser {
     fn register(registry: Registry) -> Registry {
-        // define age field
-        let age_field = dynamic::Field::new(
-            "age",
-            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
-            |ctx| {
-                dynamic::FieldFuture::new(async move {
-                    let parent = ctx
-                        .parent_value
-                        .try_downcast_ref::<<Self as ExpandObject>::Target>()?;
-                    let value = parent.resolve_age();
-                    value.resolve_owned(&ctx)
-                })
-            },
-        );
-
         // register Aged object
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |query_object| query_object.field(age_field).implement(AgedInterface::NAME),
+            |query_object| {
+                // define age field
+                let age_field = dynamic::Field::new(
+                    "age",
+                    dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                    |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = parent.resolve_age();
+                            value.resolve_owned(&ctx)
+                        })
+                    },
+                );
+                query_object.field(age_field).implement(AgedInterface::NAME)
+            },
             ExpandObjectContext::new("AgedExpandUser", "age"),
         )
     }
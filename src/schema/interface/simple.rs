@@ -1,4 +1,5 @@
 use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{Interface, Object, Register, Registry};
 use async_graphql::dynamic;
 use async_graphql::dynamic::FieldValue;
@@ -120,7 +121,7 @@ impl Register for Query {
         let user_field =
             dynamic::Field::new("user", dynamic::TypeRef::named_nn(User::NAME), |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     // use borrowed_any because Image is not value
                     Ok(Some(FieldValue::borrowed_any(parent.resolve_user())))
                 })
@@ -151,7 +152,7 @@ impl Register for User {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_id();
                     value.resolve_ref(&ctx)
                 })
@@ -167,7 +168,7 @@ impl Register for User {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_name();
                     value.resolve_ref(&ctx)
                 })
@@ -183,7 +184,7 @@ impl Register for User {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_age();
                     value.resolve_owned(&ctx)
                 })
@@ -0,0 +1,601 @@
+use crate::schema::interface::utils::{resolve_dyn_owned, resolve_dyn_ref, InterfaceObject};
+use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Interface, Object, Register, Registry};
+use async_graphql::{dynamic, Context};
+
+// user
+
+// mark as interface
+trait Node: InterfaceObject {
+    fn resolve_id(&self) -> &str;
+    fn resolve_name(&self) -> String;
+}
+
+// mark as object, implements Node
+struct FooNode {
+    id: String,
+}
+
+impl FooNode {
+    async fn resolve_foo(&self) -> String {
+        "foo".to_string()
+    }
+}
+
+impl Node for FooNode {
+    fn resolve_id(&self) -> &str {
+        &self.id
+    }
+    fn resolve_name(&self) -> String {
+        format!("foo {}", self.id)
+    }
+}
+
+// mark as object, implements Node
+struct BarNode {
+    id: String,
+    bar: i32,
+}
+
+impl BarNode {
+    async fn resolve_bar(&self) -> i32 {
+        self.bar
+    }
+}
+
+impl Node for BarNode {
+    fn resolve_id(&self) -> &str {
+        &self.id
+    }
+    fn resolve_name(&self) -> String {
+        format!("bar {}", self.id)
+    }
+}
+
+// mark as object
+struct Group {
+    // mark as skip
+    owner: Box<dyn Node>,
+    // mark as skip
+    members: Vec<Box<dyn Node>>,
+}
+
+impl Group {
+    async fn resolve_owner(&self) -> &dyn Node {
+        self.owner.as_ref()
+    }
+    async fn resolve_members(&self) -> &Vec<Box<dyn Node>> {
+        &self.members
+    }
+}
+
+// mark as object
+struct Query {
+    // mark as skip
+    pinned: Option<FooNode>,
+    // mark as skip
+    group: Group,
+}
+
+impl Query {
+    async fn resolve_pinned(&self) -> Option<&dyn Node> {
+        self.pinned.as_ref().map(|node| node as &dyn Node)
+    }
+    async fn resolve_search(&self, prefix: String) -> Vec<&dyn Node> {
+        let members = self.group.members.iter().map(|node| node.as_ref());
+        std::iter::once(self.group.owner.as_ref())
+            .chain(members)
+            .filter(|node| node.resolve_id().starts_with(&prefix))
+            .collect()
+    }
+    async fn resolve_group(&self) -> &Group {
+        &self.group
+    }
+    async fn resolve_node(&self, id: String) -> Option<Box<dyn Node>> {
+        match id.as_str() {
+            "foo-1" => Some(Box::new(FooNode { id })),
+            "bar-1" => Some(Box::new(BarNode { id, bar: 1 })),
+            _ => None,
+        }
+    }
+    async fn resolve_nodes(&self) -> Vec<Box<dyn Node>> {
+        vec![
+            Box::new(FooNode {
+                id: "foo-1".to_string(),
+            }),
+            Box::new(BarNode {
+                id: "bar-2".to_string(),
+                bar: 2,
+            }),
+        ]
+    }
+}
+
+// generated
+
+struct NodeInterface;
+
+impl Interface for NodeInterface {
+    const NAME: &'static str = "Node";
+
+    fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+        let id_field = dynamic::InterfaceField::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        let name_field = dynamic::InterfaceField::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        interface.field(id_field).field(name_field)
+    }
+}
+
+impl Register for NodeInterface {
+    fn register(registry: Registry) -> Registry {
+        let interface = dynamic::Interface::new(Self::NAME);
+        let interface = Self::register_fields(interface);
+        registry.register_interface(interface)
+    }
+}
+
+impl NodeInterface {
+    /// add the interface fields to an implementer, resolved through the `Node` trait
+    fn implement<T: Node + Object>(object: dynamic::Object) -> dynamic::Object {
+        // define id field
+        let id_field = dynamic::Field::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<T>(&ctx)?;
+                    let value = Node::resolve_id(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+
+        // define name field
+        let name_field = dynamic::Field::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<T>(&ctx)?;
+                    let value = Node::resolve_name(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+
+        object
+            .implement(Self::NAME)
+            .field(id_field)
+            .field(name_field)
+    }
+}
+
+impl<'a> ResolveOwned<'a> for Box<dyn Node> {
+    fn resolve_owned(
+        self,
+        _ctx: &Context,
+    ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+        Ok(Some(resolve_dyn_owned(self)))
+    }
+}
+
+impl<'a> ResolveRef<'a> for Box<dyn Node> {
+    fn resolve_ref(
+        &'a self,
+        _ctx: &Context,
+    ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+        Ok(Some(resolve_dyn_ref(self.as_ref())))
+    }
+}
+
+impl<'a> ResolveOwned<'a> for &'a dyn Node {
+    fn resolve_owned(
+        self,
+        _ctx: &Context,
+    ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+        Ok(Some(resolve_dyn_ref(self)))
+    }
+}
+
+impl Object for Group {
+    const NAME: &'static str = "Group";
+}
+
+impl<'a> ResolveRef<'a> for Group {
+    fn resolve_ref(
+        &'a self,
+        _ctx: &Context,
+    ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+        Ok(Some(dynamic::FieldValue::borrowed_any(self)))
+    }
+}
+
+impl Register for Group {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define owner field
+        let owner_field = dynamic::Field::new(
+            "owner",
+            dynamic::TypeRef::named_nn(NodeInterface::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_owner().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(owner_field);
+
+        // define members field
+        let members_field = dynamic::Field::new(
+            "members",
+            dynamic::TypeRef::named_nn_list_nn(NodeInterface::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_members().await;
+                    ResolveRef::resolve_ref(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(members_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Object for FooNode {
+    const NAME: &'static str = "FooNode";
+}
+
+impl Register for FooNode {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define foo field
+        let foo_field = dynamic::Field::new(
+            "foo",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_foo().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(foo_field);
+
+        // implement Node
+        let object_type = NodeInterface::implement::<Self>(object_type);
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Object for BarNode {
+    const NAME: &'static str = "BarNode";
+}
+
+impl Register for BarNode {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define bar field
+        let bar_field = dynamic::Field::new(
+            "bar",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::INT),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_bar().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(bar_field);
+
+        // implement Node
+        let object_type = NodeInterface::implement::<Self>(object_type);
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define node field
+        let node_field = dynamic::Field::new(
+            "node",
+            dynamic::TypeRef::named(NodeInterface::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("id")?.deserialize()?;
+                    let value = parent.resolve_node(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let node_field = node_field.argument(dynamic::InputValue::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        ));
+        let object_type = object_type.field(node_field);
+
+        // define nodes field
+        let nodes_field = dynamic::Field::new(
+            "nodes",
+            dynamic::TypeRef::named_nn_list_nn(NodeInterface::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_nodes().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(nodes_field);
+
+        // define pinned field
+        let pinned_field = dynamic::Field::new(
+            "pinned",
+            dynamic::TypeRef::named(NodeInterface::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_pinned().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(pinned_field);
+
+        // define search field
+        let search_field = dynamic::Field::new(
+            "search",
+            dynamic::TypeRef::named_nn_list_nn(NodeInterface::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("prefix")?.deserialize()?;
+                    let value = parent.resolve_search(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let search_field = search_field.argument(dynamic::InputValue::new(
+            "prefix",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        ));
+        let object_type = object_type.field(search_field);
+
+        // define group field
+        let group_field = dynamic::Field::new(
+            "group",
+            dynamic::TypeRef::named_nn(<Group as Object>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_group().await;
+                    ResolveRef::resolve_ref(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(group_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new()
+        .register::<Query>()
+        .register::<NodeInterface>()
+        .register::<Group>()
+        .register::<FooNode>()
+        .register::<BarNode>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                type BarNode implements Node {
+                  bar: Int!
+                  id: String!
+                  name: String!
+                }
+                type FooNode implements Node {
+                  foo: String!
+                  id: String!
+                  name: String!
+                }
+                type Group {
+                  owner: Node!
+                  members: [Node!]!
+                }
+                interface Node {
+                  id: String!
+                  name: String!
+                }
+                type Query {
+                  node(id: String!): Node
+                  nodes: [Node!]!
+                  pinned: Node
+                  search(prefix: String!): [Node!]!
+                  group: Group!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    fn create_root() -> Query {
+        Query {
+            pinned: Some(FooNode {
+                id: "foo-pinned".to_string(),
+            }),
+            group: Group {
+                owner: Box::new(FooNode {
+                    id: "foo-owner".to_string(),
+                }),
+                members: vec![
+                    Box::new(BarNode {
+                        id: "bar-member".to_string(),
+                        bar: 3,
+                    }),
+                    Box::new(FooNode {
+                        id: "foo-member".to_string(),
+                    }),
+                ],
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_node() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                foo: node(id: "foo-1") {
+                    __typename
+                    id
+                    name
+                    ... on FooNode { foo }
+                }
+                bar: node(id: "bar-1") {
+                    __typename
+                    id
+                    ... on BarNode { bar }
+                }
+                missing: node(id: "baz-1") {
+                    id
+                }
+            }
+        "#;
+        let req = async_graphql::Request::new(query)
+            .root_value(dynamic::FieldValue::owned_any(create_root()));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "foo": { "__typename": "FooNode", "id": "foo-1", "name": "foo foo-1", "foo": "foo" },
+                "bar": { "__typename": "BarNode", "id": "bar-1", "bar": 1 },
+                "missing": null,
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_nodes() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                nodes {
+                    __typename
+                    name
+                    ... on BarNode { bar }
+                }
+            }
+        "#;
+        let req = async_graphql::Request::new(query)
+            .root_value(dynamic::FieldValue::owned_any(create_root()));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "nodes": [
+                    { "__typename": "FooNode", "name": "foo foo-1" },
+                    { "__typename": "BarNode", "name": "bar bar-2", "bar": 2 },
+                ]
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_borrowed() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                pinned { __typename id }
+                search(prefix: "foo") { __typename id }
+                group {
+                    owner { __typename id }
+                    members {
+                        __typename
+                        id
+                        ... on BarNode { bar }
+                    }
+                }
+            }
+        "#;
+        let req = async_graphql::Request::new(query)
+            .root_value(dynamic::FieldValue::owned_any(create_root()));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "pinned": { "__typename": "FooNode", "id": "foo-pinned" },
+                "search": [
+                    { "__typename": "FooNode", "id": "foo-owner" },
+                    { "__typename": "FooNode", "id": "foo-member" },
+                ],
+                "group": {
+                    "owner": { "__typename": "FooNode", "id": "foo-owner" },
+                    "members": [
+                        { "__typename": "BarNode", "id": "bar-member", "bar": 3 },
+                        { "__typename": "FooNode", "id": "foo-member" },
+                    ],
+                },
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_pinned() {
+        let schema = create_schema();
+        let query = "query { pinned { id } }";
+        let root = Query {
+            pinned: None,
+            ..create_root()
+        };
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(root));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(data, serde_json::json!({ "pinned": null }));
+    }
+}
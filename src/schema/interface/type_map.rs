@@ -0,0 +1,384 @@
+use crate::schema::interface::utils::{register_interface_fields, AutoBox};
+use crate::schema::output_types::utils::ResolveOwned;
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Interface, InterfaceFields, Object, Register, Registry};
+use async_graphql::{dynamic, Context};
+use std::sync::Arc;
+
+// user
+
+// mark as interface, possible types FooNode, BarNode
+trait Node {
+    fn resolve_id(&self) -> &str;
+}
+
+// mark as object, implements Node
+struct FooNode {
+    // mark as skip
+    id: String,
+}
+
+impl FooNode {
+    async fn resolve_foo(&self) -> String {
+        "foo".to_string()
+    }
+}
+
+impl Node for FooNode {
+    fn resolve_id(&self) -> &str {
+        &self.id
+    }
+}
+
+// mark as object, implements Node
+struct BarNode {
+    // mark as skip
+    id: String,
+}
+
+impl BarNode {
+    async fn resolve_bar(&self) -> String {
+        "bar".to_string()
+    }
+}
+
+impl Node for BarNode {
+    fn resolve_id(&self) -> &str {
+        &self.id
+    }
+}
+
+// mark as object
+struct Query {
+    // mark as skip
+    foo: Arc<FooNode>,
+    // mark as skip
+    bar: BarNode,
+}
+
+impl Query {
+    async fn resolve_nodes(&self) -> Vec<NodeInterface<'_>> {
+        // no graphql type name is given, it is found from the rust type
+        vec![
+            NodeInterface::new_shared(self.foo.clone()),
+            NodeInterface::new_borrowed(&self.bar),
+        ]
+    }
+    async fn resolve_new_node(&self) -> NodeInterface<'static> {
+        NodeInterface::new_owned(BarNode {
+            id: "bar-2".to_string(),
+        })
+    }
+}
+
+// generated
+
+struct NodeInterface<'a>(AutoBox<'a>);
+
+impl<'a> NodeInterface<'a> {
+    fn new_owned<T: Node + Send + Sync + 'static>(value: T) -> Self {
+        NodeInterface(AutoBox::new_owned(value))
+    }
+    fn new_borrowed<T: Node + Send + Sync + 'static>(value: &'a T) -> Self {
+        NodeInterface(AutoBox::new_borrowed(value))
+    }
+    fn new_shared<T: Node + Send + Sync + 'static>(value: Arc<T>) -> Self {
+        NodeInterface(AutoBox::new_owned(value))
+    }
+}
+
+impl<'a> ResolveOwned<'a> for NodeInterface<'a> {
+    fn resolve_owned(
+        self,
+        ctx: &Context,
+    ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+        self.0.resolve_owned(ctx)
+    }
+}
+
+impl Interface for NodeInterface<'_> {
+    const NAME: &'static str = "Node";
+
+    fn register_fields(interface: dynamic::Interface) -> dynamic::Interface {
+        register_interface_fields::<Self>(interface)
+    }
+}
+
+impl InterfaceFields for NodeInterface<'_> {
+    fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)> {
+        let id_field = dynamic::InterfaceField::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        vec![("id", id_field)]
+    }
+}
+
+impl Register for NodeInterface<'_> {
+    fn register(registry: Registry) -> Registry {
+        registry
+            .register_interface_type::<Self>()
+            .expect_object_type::<FooNode>(Self::NAME)
+            .expect_object_type::<BarNode>(Self::NAME)
+    }
+}
+
+impl Object for FooNode {
+    const NAME: &'static str = "FooNode";
+}
+
+impl Register for FooNode {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define id field
+        let id_field = dynamic::Field::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = Node::resolve_id(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(id_field);
+
+        // define foo field
+        let foo_field = dynamic::Field::new(
+            "foo",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_foo().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(foo_field);
+
+        registry
+            .register_object_type::<Self>(object_type)
+            .implement_interface(<Self as Object>::NAME, NodeInterface::NAME)
+    }
+}
+
+impl Object for BarNode {
+    const NAME: &'static str = "BarNode";
+}
+
+impl Register for BarNode {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define id field
+        let id_field = dynamic::Field::new(
+            "id",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = Node::resolve_id(parent);
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(id_field);
+
+        // define bar field
+        let bar_field = dynamic::Field::new(
+            "bar",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_bar().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(bar_field);
+
+        registry
+            .register_object_type::<Self>(object_type)
+            .implement_interface(<Self as Object>::NAME, NodeInterface::NAME)
+    }
+}
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define nodes field
+        let nodes_field = dynamic::Field::new(
+            "nodes",
+            dynamic::TypeRef::named_nn_list_nn(NodeInterface::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_nodes().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(nodes_field);
+
+        // define new_node field
+        let new_node_field = dynamic::Field::new(
+            "new_node",
+            dynamic::TypeRef::named_nn(NodeInterface::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_new_node().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(new_node_field);
+
+        registry.register_object_type::<Self>(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new()
+        .register::<Query>()
+        .register::<NodeInterface>()
+        .register::<FooNode>()
+        .register::<BarNode>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                type BarNode implements Node {
+                  id: String!
+                  bar: String!
+                }
+                type FooNode implements Node {
+                  id: String!
+                  foo: String!
+                }
+                interface Node {
+                  id: String!
+                }
+                type Query {
+                  nodes: [Node!]!
+                  new_node: Node!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                nodes {
+                    __typename
+                    id
+                    ... on FooNode { foo }
+                    ... on BarNode { bar }
+                }
+                new_node { __typename id }
+            }
+        "#;
+        let root = Query {
+            foo: Arc::new(FooNode {
+                id: "foo-1".to_string(),
+            }),
+            bar: BarNode {
+                id: "bar-1".to_string(),
+            },
+        };
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(root));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "nodes": [
+                    { "__typename": "FooNode", "id": "foo-1", "foo": "foo" },
+                    { "__typename": "BarNode", "id": "bar-1", "bar": "bar" },
+                ],
+                "new_node": { "__typename": "BarNode", "id": "bar-2" },
+            }),
+        );
+    }
+
+    #[test]
+    fn test_unregistered_type() {
+        let registry = Registry::new()
+            .register::<Query>()
+            .register::<NodeInterface>()
+            .register::<FooNode>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        assert_eq!(
+            registry.try_build_schema(schema).err().unwrap(),
+            format!(
+                "Invalid abstract type: {:?}",
+                format!(
+                    "rust type {} returned by Node is not a registered object",
+                    std::any::type_name::<BarNode>()
+                )
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_type() {
+        // schema built without the expectations of the interface
+        let registry = Registry::new()
+            .register::<Query>()
+            .register_interface_type::<NodeInterface>()
+            .register::<FooNode>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        let schema = registry.build_schema(schema).finish().unwrap();
+        let query = r#"
+            query {
+                new_node { id }
+            }
+        "#;
+        let root = Query {
+            foo: Arc::new(FooNode {
+                id: "foo-1".to_string(),
+            }),
+            bar: BarNode {
+                id: "bar-1".to_string(),
+            },
+        };
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(root));
+        let res = schema.execute(req).await;
+        assert_eq!(res.errors.len(), 1);
+        assert!(res.errors[0]
+            .message
+            .ends_with("BarNode is not a registered object"));
+    }
+}
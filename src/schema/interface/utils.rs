@@ -1,7 +1,10 @@
 use crate::schema::output_types::utils::ResolveOwned;
-use async_graphql::dynamic::FieldValue;
+use crate::schema::registry::{InterfaceFields, Object};
+use async_graphql::dynamic::{self, FieldValue};
 use async_graphql::Context;
-use std::any::Any;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
 
 pub enum AnyBox<'a> {
     Owned(Box<dyn Any + Send + Sync>, String),
@@ -25,3 +28,146 @@ impl<'a> ResolveOwned<'a> for AnyBox<'a> {
         }
     }
 }
+
+/// `Interface::register_fields` of an interface whose fields are defined by `InterfaceFields`
+pub fn register_interface_fields<T: InterfaceFields>(
+    interface: dynamic::Interface,
+) -> dynamic::Interface {
+    T::interface_fields()
+        .into_iter()
+        .fold(interface, |interface, (_, field)| interface.field(field))
+}
+
+/// the value holds the rust type of an object, or shares it with `Arc`
+pub fn downcast_object<'a, T: Any>(value: &'a FieldValue<'a>) -> Option<&'a T> {
+    value
+        .downcast_ref::<T>()
+        .or_else(|| value.downcast_ref::<Arc<T>>().map(|value| value.as_ref()))
+}
+
+/// graphql object registered with its rust type
+#[derive(Clone, Copy)]
+pub struct ObjectTypeName {
+    pub name: &'static str,
+    pub rust_name: &'static str,
+    probe: fn(&FieldValue) -> bool,
+}
+
+impl ObjectTypeName {
+    pub fn of<T: Object + Any>() -> Self {
+        Self {
+            name: <T as Object>::NAME,
+            rust_name: std::any::type_name::<T>(),
+            probe: |value| downcast_object::<T>(value).is_some(),
+        }
+    }
+    /// the value holds the rust type of the object, or shares it with `Arc`
+    pub fn matches(&self, value: &FieldValue) -> bool {
+        (self.probe)(value)
+    }
+}
+
+/// graphql object names by rust type, added to the schema data by the registry
+pub struct ObjectTypeNames(HashMap<TypeId, ObjectTypeName>);
+
+impl ObjectTypeNames {
+    pub fn new(names: HashMap<TypeId, ObjectTypeName>) -> Self {
+        Self(names)
+    }
+    pub fn get(&self, type_id: TypeId) -> Option<&'static str> {
+        self.0.get(&type_id).map(|object| object.name)
+    }
+    /// registered object held by the value
+    pub fn find(&self, value: &FieldValue) -> Option<&ObjectTypeName> {
+        self.0.values().find(|object| object.matches(value))
+    }
+}
+
+/// object value of an abstract type, the graphql type is looked up from the rust type of the
+/// value in `ObjectTypeNames`
+pub enum AutoBox<'a> {
+    Owned(Box<dyn Any + Send + Sync>, &'static str),
+    Borrowed(&'a (dyn Any + Send + Sync), &'static str),
+}
+
+impl<'a> AutoBox<'a> {
+    pub fn new_owned<T: Any + Send + Sync>(value: T) -> Self {
+        Self::Owned(Box::new(value), std::any::type_name::<T>())
+    }
+    pub fn new_borrowed<T: Any + Send + Sync>(value: &'a T) -> Self {
+        Self::Borrowed(value, std::any::type_name::<T>())
+    }
+    fn type_id(&self) -> TypeId {
+        match self {
+            AutoBox::Owned(obj, _) => Any::type_id(obj.as_ref()),
+            AutoBox::Borrowed(obj, _) => Any::type_id(*obj),
+        }
+    }
+    fn rust_type_name(&self) -> &'static str {
+        match self {
+            AutoBox::Owned(_, name) | AutoBox::Borrowed(_, name) => name,
+        }
+    }
+}
+
+impl<'a> ResolveOwned<'a> for AutoBox<'a> {
+    fn resolve_owned(self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        let name = ctx
+            .data::<ObjectTypeNames>()?
+            .get(self.type_id())
+            .ok_or_else(|| {
+                async_graphql::Error::new(format!(
+                    "internal: rust type {} is not a registered object",
+                    self.rust_type_name()
+                ))
+            })?;
+        match self {
+            AutoBox::Owned(obj, _) => Ok(Some(FieldValue::boxed_any(obj).with_type(name))),
+            AutoBox::Borrowed(obj, _) => Ok(Some(FieldValue::borrowed_any(obj).with_type(name))),
+        }
+    }
+}
+
+/// view the parent value of an implementer as the interface trait object, e.g. `dyn Node`
+pub type InterfaceCast<I> = Arc<dyn for<'a> Fn(&'a FieldValue<'a>) -> Option<&'a I> + Send + Sync>;
+
+/// `Interface::parent_cast` of an interface trait object `I` inheriting from `P`, the
+/// `InterfaceCast<I>` of an implementer is viewed as an `InterfaceCast<P>`
+pub fn upcast_interface<I: ?Sized + 'static, P: ?Sized + 'static>(
+    cast: &dyn Any,
+    upcast: fn(&I) -> &P,
+) -> Option<Arc<dyn Any>> {
+    let cast = cast.downcast_ref::<InterfaceCast<I>>()?.clone();
+    let cast: InterfaceCast<P> = Arc::new(move |value| cast(value).map(upcast));
+    Some(Arc::new(cast))
+}
+
+/// object value behind an interface trait object, the graphql type is taken from the value
+pub trait InterfaceObject: Send + Sync + 'static {
+    fn type_name(&self) -> &'static str;
+    fn as_any(&self) -> &(dyn Any + Send + Sync);
+    fn into_any(self: Box<Self>) -> Box<dyn Any + Send + Sync>;
+}
+
+impl<T: Object + Send + Sync + 'static> InterfaceObject for T {
+    fn type_name(&self) -> &'static str {
+        <T as Object>::NAME
+    }
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any + Send + Sync> {
+        self
+    }
+}
+
+/// resolve a boxed trait object as its concrete object type
+pub fn resolve_dyn_owned<'a, T: InterfaceObject + ?Sized>(value: Box<T>) -> FieldValue<'a> {
+    let type_name = value.type_name();
+    FieldValue::boxed_any(value.into_any()).with_type(type_name)
+}
+
+/// resolve a borrowed trait object as its concrete object type
+pub fn resolve_dyn_ref<'a, T: InterfaceObject + ?Sized>(value: &'a T) -> FieldValue<'a> {
+    FieldValue::borrowed_any(value.as_any()).with_type(value.type_name())
+}
@@ -1,16 +1,25 @@
 pub mod enums;
+pub mod errors;
 pub mod expand_object;
 pub mod expand_object_with_self;
 pub mod extend_hello;
 pub mod extend_object;
+pub mod features;
+pub mod handle;
 pub mod hello;
 pub mod implements_hello;
+pub mod incremental;
 pub mod input;
 pub mod interface;
 pub mod mutation;
 pub mod nullable_array;
 pub mod output_types;
+pub mod parent;
 pub mod registry;
+pub mod scalars;
 pub mod simple_object;
 pub mod union;
 pub mod union_with_interface;
+pub mod validator;
+pub mod versions;
+pub mod visibility;
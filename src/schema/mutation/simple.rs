@@ -1,4 +1,5 @@
 use crate::schema::output_types::utils::ResolveOwned;
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{
     ExpandObject, ExpandObjectContext, Mutation, Object, Register, Registry,
 };
@@ -78,18 +79,20 @@ impl ExpandObject for CountQuery {
 
 impl Register for CountQuery {
     fn register(registry: Registry) -> Registry {
-        // define count field
-        let count_field =
-            dynamic::Field::new("count", dynamic::TypeRef::named_nn(TypeRef::INT), |ctx| {
-                dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Query>()?;
-                    let value = Self::resolve_count(parent, &ctx).await;
-                    ResolveOwned::resolve_owned(value, &ctx)
-                })
-            });
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
-            |query_object| query_object.field(count_field),
+            |query_object| {
+                // define count field
+                let count_field =
+                    dynamic::Field::new("count", dynamic::TypeRef::named_nn(TypeRef::INT), |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<Query>(&ctx)?;
+                            let value = Self::resolve_count(parent, &ctx).await;
+                            ResolveOwned::resolve_owned(value, &ctx)
+                        })
+                    });
+                query_object.field(count_field)
+            },
             ExpandObjectContext::new("CountQuery", "count"),
         )
     }
@@ -137,36 +140,35 @@ impl CountMutations {
 
 impl Register for CountMutations {
     fn register(registry: Registry) -> Registry {
-        // define increment field
-        let increment_field = dynamic::Field::new(
-            "increment",
-            dynamic::TypeRef::named_nn(TypeRef::INT),
-            |ctx| {
-                dynamic::FieldFuture::new(async move {
-                    let parent = ctx
-                        .parent_value
-                        .try_downcast_ref::<<<Self as ExpandObject>::Target as Mutation>::Root>(
-                    )?;
-                    let value = Self::resolve_increment(parent, &ctx).await;
-                    ResolveOwned::resolve_owned(value, &ctx)
-                })
-            },
-        );
-        // define decrement field
-        let decrement_field = dynamic::Field::new(
-            "decrement",
-            dynamic::TypeRef::named_nn(TypeRef::INT),
-            |ctx| {
-                dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Root>()?;
-                    let value = Self::resolve_decrement(parent, &ctx).await;
-                    ResolveOwned::resolve_owned(value, &ctx)
-                })
-            },
-        );
         registry.update_object(
             <<Self as ExpandObject>::Target as Object>::NAME,
             |mutation_object| {
+                // define increment field
+                let increment_field = dynamic::Field::new(
+                    "increment",
+                    dynamic::TypeRef::named_nn(TypeRef::INT),
+                    |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<
+                                <<Self as ExpandObject>::Target as Mutation>::Root,
+                            >(&ctx)?;
+                            let value = Self::resolve_increment(parent, &ctx).await;
+                            ResolveOwned::resolve_owned(value, &ctx)
+                        })
+                    },
+                );
+                // define decrement field
+                let decrement_field = dynamic::Field::new(
+                    "decrement",
+                    dynamic::TypeRef::named_nn(TypeRef::INT),
+                    |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<Root>(&ctx)?;
+                            let value = Self::resolve_decrement(parent, &ctx).await;
+                            ResolveOwned::resolve_owned(value, &ctx)
+                        })
+                    },
+                );
                 mutation_object
                     .field(increment_field)
                     .field(decrement_field)
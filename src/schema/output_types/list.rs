@@ -1,4 +1,5 @@
 use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{Object, Register, Registry};
 use async_graphql::dynamic::DynamicRequestExt;
 use async_graphql::dynamic::FieldValue;
@@ -45,7 +46,7 @@ impl Register for Query {
         // define foo field
         let foo_field = dynamic::Field::new("foo", dynamic::TypeRef::named_nn(Foo::NAME), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_foo().await;
                 ResolveRef::resolve_ref(value, &ctx)
             })
@@ -86,7 +87,7 @@ impl Register for Bar {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_value().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -128,7 +129,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn_list_nn(Bar::NAME),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_bar().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -142,7 +143,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn_list_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_the_string().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -156,7 +157,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn_list_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_the_str().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -170,7 +171,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn_list_nn(dynamic::TypeRef::INT),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_the_i32().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -185,7 +186,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn_list_nn(dynamic::TypeRef::FLOAT),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_the_f32().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -199,7 +200,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn_list_nn(dynamic::TypeRef::BOOLEAN),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_the_bool().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -213,7 +214,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn_list_nn(dynamic::TypeRef::ID),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_the_id().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -0,0 +1,213 @@
+use crate::schema::output_types::utils::ResolveOwned;
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Object, Register, Registry};
+use async_graphql::dynamic;
+
+// user
+
+#[derive(thiserror::Error, Debug)]
+enum MyError {
+    #[error("item {0} failed")]
+    Item(i32),
+}
+
+// mark as object
+struct Query;
+
+fn item(value: i32) -> Result<i32, MyError> {
+    if value % 2 == 0 {
+        Ok(value)
+    } else {
+        Err(MyError::Item(value))
+    }
+}
+
+impl Query {
+    async fn resolve_nullable_items(&self) -> Vec<Result<Option<i32>, MyError>> {
+        (0..4).map(|value| item(value).map(Some)).collect()
+    }
+    async fn resolve_items(&self) -> Vec<Result<i32, MyError>> {
+        (0..4).map(item).collect()
+    }
+    async fn resolve_required_items(&self) -> Vec<Result<i32, MyError>> {
+        (0..4).map(item).collect()
+    }
+}
+
+// generated
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define nullable_items field
+        let nullable_items_field = dynamic::Field::new(
+            "nullable_items",
+            dynamic::TypeRef::named_list_nn(dynamic::TypeRef::INT),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_nullable_items().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(nullable_items_field);
+
+        // define items field
+        let items_field = dynamic::Field::new(
+            "items",
+            dynamic::TypeRef::named_nn_list(dynamic::TypeRef::INT),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_items().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(items_field);
+
+        // define required_items field
+        let required_items_field = dynamic::Field::new(
+            "required_items",
+            dynamic::TypeRef::named_nn_list_nn(dynamic::TypeRef::INT),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_required_items().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(required_items_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+fn create_schema_with(registry: Registry) -> dynamic::Schema {
+    let registry = registry.register::<Query>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    create_schema_with(Registry::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                type Query {
+                  nullable_items: [Int]!
+                  items: [Int!]
+                  required_items: [Int!]!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    async fn execute(schema: &dynamic::Schema, query: &str) -> serde_json::Value {
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        serde_json::to_value(res).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_nullable_items() {
+        let schema = create_schema();
+        let res = execute(&schema, "{ nullable_items }").await;
+        assert_eq!(
+            res,
+            serde_json::json!({
+                "data": { "nullable_items": [0, null, 2, null] },
+                "errors": [
+                    {
+                        "message": "item 1 failed",
+                        "locations": [{ "line": 1, "column": 3 }],
+                        "path": ["nullable_items", 1],
+                    },
+                    {
+                        "message": "item 3 failed",
+                        "locations": [{ "line": 1, "column": 3 }],
+                        "path": ["nullable_items", 3],
+                    },
+                ],
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_items() {
+        let schema = create_schema();
+        let res = execute(&schema, "{ items }").await;
+        // the first failed item nulls the list
+        assert_eq!(
+            res,
+            serde_json::json!({
+                "data": { "items": null },
+                "errors": [
+                    {
+                        "message": "item 1 failed",
+                        "locations": [{ "line": 1, "column": 3 }],
+                        "path": ["items", 1],
+                    },
+                ],
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_required_items() {
+        let schema = create_schema();
+        let res = execute(&schema, "{ required_items }").await;
+        // the item error fails the non-null field
+        assert_eq!(
+            res,
+            serde_json::json!({
+                "data": null,
+                "errors": [{
+                    "message": "item 1 failed",
+                    "locations": [{ "line": 1, "column": 3 }],
+                    "path": ["required_items", 1],
+                }],
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lenient() {
+        let schema = create_schema_with(Registry::new().lenient_list_items());
+        let res = execute(&schema, "{ nullable_items items }").await;
+        assert_eq!(
+            res,
+            serde_json::json!({
+                "data": {
+                    "nullable_items": [0, null, 2, null],
+                    "items": [0, null, 2, null],
+                },
+            }),
+        );
+    }
+}
@@ -1,9 +1,12 @@
 pub mod list;
+pub mod list_errors;
 pub mod optionals;
 pub mod resolved;
 pub mod resolved_list;
 pub mod resolved_list_and_result;
 pub mod resolved_optional;
 pub mod resolved_result;
+pub mod shared;
 pub mod simple;
+pub mod stream;
 pub mod utils;
@@ -1,4 +1,5 @@
 use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{Object, Register, Registry};
 use async_graphql::dynamic::DynamicRequestExt;
 use async_graphql::dynamic::FieldValue;
@@ -45,7 +46,7 @@ impl Register for Query {
         // define foo field
         let foo_field = dynamic::Field::new("foo", dynamic::TypeRef::named_nn(Foo::NAME), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_foo().await;
                 ResolveRef::resolve_ref(value, &ctx)
             })
@@ -86,7 +87,7 @@ impl Register for Bar {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_value().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -125,7 +126,7 @@ impl Register for Foo {
         // define bar field
         let bar_field = dynamic::Field::new("bar", dynamic::TypeRef::named(Bar::NAME), |ctx| {
             dynamic::FieldFuture::new(async move {
-                let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                let parent = parent_value::<Self>(&ctx)?;
                 let value = parent.resolve_bar().await;
                 ResolveRef::resolve_ref(value, &ctx)
             })
@@ -138,7 +139,7 @@ impl Register for Foo {
             dynamic::TypeRef::named(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_the_string().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -152,7 +153,7 @@ impl Register for Foo {
             dynamic::TypeRef::named(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_the_str().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -166,7 +167,7 @@ impl Register for Foo {
             dynamic::TypeRef::named(dynamic::TypeRef::INT),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_the_i32().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -181,7 +182,7 @@ impl Register for Foo {
             dynamic::TypeRef::named(dynamic::TypeRef::FLOAT),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_the_f32().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -195,7 +196,7 @@ impl Register for Foo {
             dynamic::TypeRef::named(dynamic::TypeRef::BOOLEAN),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_the_bool().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -209,7 +210,7 @@ impl Register for Foo {
             dynamic::TypeRef::named(dynamic::TypeRef::ID),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_the_id().await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -0,0 +1,326 @@
+use crate::schema::output_types::utils::{OutputTypeRef, ResolveOwned};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Object, Register, Registry};
+use async_graphql::dynamic::FieldValue;
+use async_graphql::{dynamic, Context};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, VecDeque};
+use std::sync::Arc;
+
+// user
+
+// mark as object
+struct Query {
+    // mark as skip
+    library: Arc<Library>,
+}
+
+impl Query {
+    async fn resolve_library(&self) -> Arc<Library> {
+        self.library.clone()
+    }
+}
+
+// mark as object
+struct Library {
+    // mark as skip
+    name: String,
+    // mark as skip
+    tags: BTreeSet<String>,
+    // mark as skip
+    ratings: [i32; 3],
+    // mark as skip
+    books: Vec<Arc<Book>>,
+}
+
+impl Library {
+    async fn resolve_name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.name)
+    }
+    async fn resolve_tags(&self) -> &BTreeSet<String> {
+        &self.tags
+    }
+    async fn resolve_ratings(&self) -> &[i32] {
+        &self.ratings
+    }
+    async fn resolve_books(&self) -> VecDeque<Arc<Book>> {
+        self.books.iter().cloned().collect()
+    }
+    async fn resolve_draft(&self) -> Option<Box<Book>> {
+        Some(Box::new(Book {
+            title: "untitled".to_string(),
+        }))
+    }
+}
+
+// mark as object
+struct Book {
+    // mark as skip
+    title: String,
+}
+
+impl Book {
+    async fn resolve_title(&self) -> &String {
+        &self.title
+    }
+}
+
+// generated
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl OutputTypeRef for Query {
+    fn type_name() -> &'static str {
+        <Self as Object>::NAME
+    }
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define library field
+        let library_field = dynamic::Field::new(
+            "library",
+            <Arc<Library> as OutputTypeRef>::type_ref(),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_library().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(library_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Object for Library {
+    const NAME: &'static str = "Library";
+}
+
+impl OutputTypeRef for Library {
+    fn type_name() -> &'static str {
+        <Self as Object>::NAME
+    }
+}
+
+impl Register for Library {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define name field
+        let name_field =
+            dynamic::Field::new("name", <Cow<'_, str> as OutputTypeRef>::type_ref(), |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_name().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            });
+        let object_type = object_type.field(name_field);
+
+        // define tags field
+        let tags_field = dynamic::Field::new(
+            "tags",
+            <&BTreeSet<String> as OutputTypeRef>::type_ref(),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_tags().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(tags_field);
+
+        // define ratings field
+        let ratings_field =
+            dynamic::Field::new("ratings", <&[i32] as OutputTypeRef>::type_ref(), |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_ratings().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            });
+        let object_type = object_type.field(ratings_field);
+
+        // define books field
+        let books_field = dynamic::Field::new(
+            "books",
+            <VecDeque<Arc<Book>> as OutputTypeRef>::type_ref(),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_books().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(books_field);
+
+        // define draft field
+        let draft_field = dynamic::Field::new(
+            "draft",
+            <Option<Box<Book>> as OutputTypeRef>::type_ref(),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_draft().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(draft_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Object for Book {
+    const NAME: &'static str = "Book";
+}
+
+impl OutputTypeRef for Book {
+    fn type_name() -> &'static str {
+        <Self as Object>::NAME
+    }
+}
+
+impl<'a> ResolveOwned<'a> for Book {
+    fn resolve_owned(self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::owned_any(self)))
+    }
+}
+
+impl Register for Book {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define title field
+        let title_field =
+            dynamic::Field::new("title", <&String as OutputTypeRef>::type_ref(), |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_title().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            });
+        let object_type = object_type.field(title_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new()
+        .register::<Query>()
+        .register::<Library>()
+        .register::<Book>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                type Book {
+                  title: String!
+                }
+                type Library {
+                  name: String!
+                  tags: [String!]!
+                  ratings: [Int!]!
+                  books: [Book!]!
+                  draft: Book
+                }
+                type Query {
+                  library: Library!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn test_type_ref() {
+        fn type_ref<T: OutputTypeRef + ?Sized>() -> String {
+            T::type_ref().to_string()
+        }
+        assert_eq!(type_ref::<Vec<Option<i32>>>(), "[Int]!");
+        assert_eq!(type_ref::<Option<&[String]>>(), "[String!]");
+        assert_eq!(type_ref::<HashSet<async_graphql::ID>>(), "[ID!]!");
+        assert_eq!(type_ref::<[f64; 2]>(), "[Float!]!");
+        assert_eq!(type_ref::<Box<Option<Arc<Book>>>>(), "Book");
+        assert_eq!(type_ref::<Result<Cow<'_, str>, String>>(), "String!");
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                library {
+                    name
+                    tags
+                    ratings
+                    books { title }
+                    draft { title }
+                }
+            }
+        "#;
+        let library = Arc::new(Library {
+            name: "city".to_string(),
+            tags: ["public", "central"].map(String::from).into(),
+            ratings: [5, 4, 5],
+            books: vec![
+                Arc::new(Book {
+                    title: "dune".to_string(),
+                }),
+                Arc::new(Book {
+                    title: "emma".to_string(),
+                }),
+            ],
+        });
+        let root = Query {
+            library: library.clone(),
+        };
+        let req = async_graphql::Request::new(query).root_value(FieldValue::owned_any(root));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "library": {
+                    "name": "city",
+                    "tags": ["central", "public"],
+                    "ratings": [5, 4, 5],
+                    "books": [{ "title": "dune" }, { "title": "emma" }],
+                    "draft": { "title": "untitled" },
+                }
+            }),
+        );
+        // the request only held shared references and released them all
+        assert_eq!(Arc::strong_count(&library), 1);
+        assert_eq!(Arc::strong_count(&library.books[0]), 1);
+    }
+}
@@ -0,0 +1,370 @@
+use crate::schema::output_types::utils::{
+    resolve_stream, subscription_stream, Concurrent, ResolveOwned,
+};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Object, Register, Registry};
+use async_graphql::dynamic::FieldValue;
+use async_graphql::{dynamic, Context};
+use futures_util::stream::{self, Stream, StreamExt};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// user
+
+#[derive(thiserror::Error, Debug)]
+enum MyError {
+    #[error("book {0} not found")]
+    NotFound(usize),
+}
+
+struct Repository {
+    titles: Vec<&'static str>,
+    // mark as skip
+    loading: AtomicUsize,
+    // mark as skip
+    max_loading: AtomicUsize,
+}
+
+impl Repository {
+    fn new(titles: Vec<&'static str>) -> Self {
+        Self {
+            titles,
+            loading: AtomicUsize::new(0),
+            max_loading: AtomicUsize::new(0),
+        }
+    }
+
+    fn books(&self) -> impl Stream<Item = Book> + Send + '_ {
+        stream::iter(self.titles.iter().map(|title| Book {
+            title: title.to_string(),
+        }))
+    }
+
+    async fn load_book(&self, id: usize) -> Result<Book, MyError> {
+        let loading = self.loading.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_loading.fetch_max(loading, Ordering::SeqCst);
+        // later books load faster, the list keeps the order of the ids
+        let delay = 10u64.saturating_sub(id as u64);
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+        self.loading.fetch_sub(1, Ordering::SeqCst);
+        match self.titles.get(id) {
+            Some(title) => Ok(Book {
+                title: title.to_string(),
+            }),
+            None => Err(MyError::NotFound(id)),
+        }
+    }
+}
+
+// mark as object
+struct Query {
+    // mark as skip
+    repository: Arc<Repository>,
+}
+
+impl Query {
+    async fn resolve_books(&self) -> impl Stream<Item = Book> + Send + '_ {
+        self.repository.books()
+    }
+    async fn resolve_books_by_id(
+        &self,
+        ids: Vec<usize>,
+    ) -> Concurrent<impl Stream<Item = impl Future<Output = Result<Book, MyError>> + Send> + Send>
+    {
+        let repository = self.repository.clone();
+        Concurrent(stream::iter(ids).map(move |id| {
+            let repository = repository.clone();
+            async move { repository.load_book(id).await }
+        }))
+    }
+}
+
+// mark as subscription
+struct Subscription {
+    // mark as skip
+    repository: Arc<Repository>,
+}
+
+impl Subscription {
+    async fn resolve_books(&self) -> impl Stream<Item = Book> + Send + '_ {
+        self.repository.books()
+    }
+}
+
+// mark as object
+struct Book {
+    // mark as skip
+    title: String,
+}
+
+impl Book {
+    async fn resolve_title(&self) -> &String {
+        &self.title
+    }
+}
+
+// generated
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define books field
+        let books_field = dynamic::Field::new(
+            "books",
+            dynamic::TypeRef::named_nn_list_nn(Book::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_books().await;
+                    resolve_stream(value, &ctx).await
+                })
+            },
+        );
+        let object_type = object_type.field(books_field);
+
+        // define books_by_id field
+        let books_by_id_field = dynamic::Field::new(
+            "books_by_id",
+            dynamic::TypeRef::named_nn_list(Book::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("ids")?.deserialize()?;
+                    let value = parent.resolve_books_by_id(arg_0).await;
+                    resolve_stream(value, &ctx).await
+                })
+            },
+        );
+        let books_by_id_field = books_by_id_field.argument(dynamic::InputValue::new(
+            "ids",
+            dynamic::TypeRef::named_nn_list_nn(dynamic::TypeRef::INT),
+        ));
+        let object_type = object_type.field(books_by_id_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Object for Subscription {
+    const NAME: &'static str = "Subscription";
+}
+
+impl Register for Subscription {
+    fn register(registry: Registry) -> Registry {
+        let subscription = dynamic::Subscription::new(<Self as Object>::NAME);
+
+        // define books field
+        let books_field = dynamic::SubscriptionField::new(
+            "books",
+            dynamic::TypeRef::named_nn(Book::NAME),
+            |ctx| {
+                dynamic::SubscriptionFieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_books().await;
+                    Ok(subscription_stream(value, ctx.ctx))
+                })
+            },
+        );
+        let subscription = subscription.field(books_field);
+
+        registry.register_subscription(subscription)
+    }
+}
+
+impl Object for Book {
+    const NAME: &'static str = "Book";
+}
+
+impl<'a> ResolveOwned<'a> for Book {
+    fn resolve_owned(self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::owned_any(self)))
+    }
+}
+
+impl Register for Book {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define title field
+        let title_field = dynamic::Field::new(
+            "title",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_title().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(title_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+fn create_schema_with(registry: Registry) -> dynamic::Schema {
+    let registry = registry
+        .register::<Query>()
+        .register::<Subscription>()
+        .register::<Book>();
+    let schema = dynamic::Schema::build(Query::NAME, None, Some(Subscription::NAME));
+    registry.build_schema(schema).finish().unwrap()
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    create_schema_with(Registry::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                type Book {
+                  title: String!
+                }
+                type Query {
+                  books: [Book!]!
+                  books_by_id(ids: [Int!]!): [Book!]
+                }
+                type Subscription {
+                  books: Book!
+                }
+                schema {
+                  query: Query
+                  subscription: Subscription
+                }
+                "#
+            ),
+        );
+    }
+
+    async fn execute(
+        schema: &dynamic::Schema,
+        repository: &Arc<Repository>,
+        query: &str,
+    ) -> serde_json::Value {
+        let root = Query {
+            repository: repository.clone(),
+        };
+        let req = async_graphql::Request::new(query).root_value(FieldValue::owned_any(root));
+        let res = schema.execute(req).await;
+        serde_json::to_value(res).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_list() {
+        let schema = create_schema();
+        let repository = Arc::new(Repository::new(vec!["dune", "emma"]));
+        let res = execute(&schema, &repository, "{ books { title } }").await;
+        assert_eq!(
+            res,
+            serde_json::json!({
+                "data": { "books": [{ "title": "dune" }, { "title": "emma" }] },
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent() {
+        let schema = create_schema_with(Registry::new().stream_concurrency(3));
+        let repository = Arc::new(Repository::new(vec!["a", "b", "c", "d", "e", "f"]));
+        let res = execute(
+            &schema,
+            &repository,
+            "{ books_by_id(ids: [0, 1, 2, 3, 4, 5]) { title } }",
+        )
+        .await;
+        assert_eq!(
+            res,
+            serde_json::json!({
+                "data": {
+                    "books_by_id": [
+                        { "title": "a" },
+                        { "title": "b" },
+                        { "title": "c" },
+                        { "title": "d" },
+                        { "title": "e" },
+                        { "title": "f" },
+                    ],
+                },
+            }),
+        );
+        assert_eq!(repository.max_loading.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_zero_concurrency() {
+        let registry = Registry::new()
+            .stream_concurrency(0)
+            .register::<Query>()
+            .register::<Subscription>()
+            .register::<Book>();
+        let schema = dynamic::Schema::build(Query::NAME, None, Some(Subscription::NAME));
+        assert_eq!(
+            registry.try_build_schema(schema).err().unwrap(),
+            "Invalid stream concurrency: at least one item must run at once",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_item() {
+        let schema = create_schema();
+        let repository = Arc::new(Repository::new(vec!["a", "b"]));
+        let res = execute(
+            &schema,
+            &repository,
+            "{ books_by_id(ids: [0, 9, 1]) { title } }",
+        )
+        .await;
+        assert_eq!(
+            res,
+            serde_json::json!({
+                "data": { "books_by_id": null },
+                "errors": [{
+                    "message": "book 9 not found",
+                    "locations": [{ "line": 1, "column": 3 }],
+                    "path": ["books_by_id", 1],
+                }],
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscription() {
+        let schema = create_schema();
+        let repository = Arc::new(Repository::new(vec!["dune", "emma"]));
+        let root = Subscription { repository };
+        let req = async_graphql::Request::new("subscription { books { title } }")
+            .root_value(FieldValue::owned_any(root));
+        let responses = schema
+            .execute_stream(req)
+            .map(|res| res.data.into_json().unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            responses,
+            vec![
+                serde_json::json!({ "books": { "title": "dune" } }),
+                serde_json::json!({ "books": { "title": "emma" } }),
+            ],
+        );
+    }
+}
@@ -1,15 +1,36 @@
-use async_graphql::dynamic::FieldValue;
-use async_graphql::Context;
+use crate::schema::registry::Object;
+use async_graphql::dynamic::{FieldValue, TypeRef};
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextResolve, ResolveInfo,
+};
+use async_graphql::{Context, ServerError, ServerResult};
+use async_graphql_value::ConstValue;
+use futures_util::future::{BoxFuture, FutureExt};
+use futures_util::stream::{BoxStream, Stream, StreamExt};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::Arc;
 
 pub trait ResolveOwned<'a> {
+    /// the value can resolve to null, list items of nullable types are nulled on error
+    const NULLABLE: bool = false;
+
     fn resolve_owned(self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>>;
 }
 
 pub trait ResolveRef<'a> {
+    /// the value can resolve to null, list items of nullable types are nulled on error
+    const NULLABLE: bool = false;
+
     fn resolve_ref(&'a self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>>;
 }
 
 impl<'a, T: ResolveOwned<'a>> ResolveOwned<'a> for Option<T> {
+    const NULLABLE: bool = true;
+
     #[inline]
     fn resolve_owned(self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
         match self {
@@ -20,6 +41,8 @@ impl<'a, T: ResolveOwned<'a>> ResolveOwned<'a> for Option<T> {
 }
 
 impl<'a, T: ResolveRef<'a>> ResolveRef<'a> for Option<T> {
+    const NULLABLE: bool = true;
+
     fn resolve_ref(&'a self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
         match self {
             None => Ok(None),
@@ -33,6 +56,8 @@ where
     T: ResolveOwned<'a>,
     E: Into<async_graphql::Error>,
 {
+    const NULLABLE: bool = T::NULLABLE;
+
     #[inline]
     fn resolve_owned(self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
         match self {
@@ -47,6 +72,8 @@ where
     T: ResolveRef<'a>,
     E: Into<async_graphql::Error> + Clone,
 {
+    const NULLABLE: bool = T::NULLABLE;
+
     #[inline]
     fn resolve_ref(&'a self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
         match self {
@@ -56,31 +83,301 @@ where
     }
 }
 
-impl<'a, T: ResolveOwned<'a>> ResolveOwned<'a> for Vec<T> {
-    fn resolve_owned(self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
-        let iter = self.into_iter();
-        let items = iter.enumerate().map(|(index, item)| {
-            let ctx_idx = ctx.with_index(index);
-            match item.resolve_owned(&ctx_idx) {
-                Ok(Some(value)) => value,
-                _ => FieldValue::NULL,
+/// lenient mode of list items, see `Registry::lenient_list_items`
+pub struct LenientListItems;
+
+tokio::task_local! {
+    /// the type of the resolved field is non-null, set by `ListFieldExtension`
+    static NON_NULL_FIELD: bool;
+    /// the failed item of a non-null list, reported with its index by `ListFieldExtension`
+    static FAILED_ITEM: RefCell<Option<ServerError>>;
+}
+
+/// tells the list resolvers whether the resolved field is non-null, installed on the registry
+/// built schemas
+pub(crate) struct ListFieldExtension;
+
+impl ExtensionFactory for ListFieldExtension {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(ListFieldExtension)
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for ListFieldExtension {
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<ConstValue>> {
+        let non_null = info.return_type.ends_with('!');
+        let resolve = async move {
+            let value = next.run(ctx, info).await;
+            match FAILED_ITEM.with(|item| item.take()) {
+                Some(err) if value.is_err() => Err(err),
+                _ => value,
             }
-        });
-        Ok(Some(FieldValue::list(items)))
+        };
+        NON_NULL_FIELD
+            .scope(non_null, FAILED_ITEM.scope(RefCell::new(None), resolve))
+            .await
     }
 }
 
-impl<'a, T: ResolveRef<'a>> ResolveRef<'a> for Vec<T> {
-    fn resolve_ref(&'a self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
-        let iter = self.iter();
-        let items = iter.enumerate().map(|(index, item)| {
+/// value of a list item, a failed item of a nullable type is reported with its index in the
+/// path. A failed item of a non-null type nulls a nullable list (`None`), and its error fails
+/// a non-null list with the index in the path as well
+fn list_item<'a>(
+    ctx: &Context,
+    nullable: bool,
+    item: async_graphql::Result<Option<FieldValue<'a>>>,
+) -> async_graphql::Result<Option<FieldValue<'a>>> {
+    match item {
+        Ok(Some(value)) => Ok(Some(value)),
+        Ok(None) => Ok(Some(FieldValue::NULL)),
+        Err(_) if ctx.data_opt::<LenientListItems>().is_some() => Ok(Some(FieldValue::NULL)),
+        Err(err) if !nullable && NON_NULL_FIELD.try_with(|non_null| *non_null) == Ok(true) => {
+            let item = ctx.set_error_path(err.clone().into_server_error(ctx.item.pos));
+            let _ = FAILED_ITEM.try_with(|failed| failed.replace(Some(item)));
+            Err(err)
+        }
+        Err(err) => {
+            ctx.add_error(ctx.set_error_path(err.into_server_error(ctx.item.pos)));
+            Ok(nullable.then_some(FieldValue::NULL))
+        }
+    }
+}
+
+fn resolve_items_owned<'a, T: ResolveOwned<'a>>(
+    ctx: &Context,
+    items: impl IntoIterator<Item = T>,
+) -> async_graphql::Result<Option<FieldValue<'a>>> {
+    let mut values = Vec::new();
+    for (index, item) in items.into_iter().enumerate() {
+        let ctx_idx = ctx.with_index(index);
+        match list_item(&ctx_idx, T::NULLABLE, item.resolve_owned(&ctx_idx))? {
+            Some(value) => values.push(value),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(FieldValue::list(values)))
+}
+
+fn resolve_items_ref<'a, T: ResolveRef<'a> + 'a>(
+    ctx: &Context,
+    items: impl IntoIterator<Item = &'a T>,
+) -> async_graphql::Result<Option<FieldValue<'a>>> {
+    let mut values = Vec::new();
+    for (index, item) in items.into_iter().enumerate() {
+        let ctx_idx = ctx.with_index(index);
+        match list_item(&ctx_idx, T::NULLABLE, item.resolve_ref(&ctx_idx))? {
+            Some(value) => values.push(value),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(FieldValue::list(values)))
+}
+
+/// concurrency of `Concurrent` streams, see `Registry::stream_concurrency`
+pub struct StreamConcurrency(pub usize);
+
+const DEFAULT_STREAM_CONCURRENCY: usize = 16;
+
+/// asynchronous list output, a `futures::Stream` of items or a `Concurrent` stream of item
+/// futures. Resolved as a list with `resolve_stream`, or as the events of a subscription
+/// with `subscription_stream`
+pub trait ResolveStream<'a>: Send + 'a {
+    type Item: ResolveOwned<'a> + Send + 'a;
+
+    fn into_items(self, ctx: &Context) -> BoxStream<'a, Self::Item>;
+}
+
+impl<'a, S> ResolveStream<'a> for S
+where
+    S: Stream + Send + 'a,
+    S::Item: ResolveOwned<'a> + Send + 'a,
+{
+    type Item = S::Item;
+
+    fn into_items(self, _ctx: &Context) -> BoxStream<'a, Self::Item> {
+        self.boxed()
+    }
+}
+
+/// stream of item futures, at most `Registry::stream_concurrency` of them run at once and the
+/// items keep the order of the stream
+pub struct Concurrent<S>(pub S);
+
+impl<'a, S> ResolveStream<'a> for Concurrent<S>
+where
+    S: Stream + Send + 'a,
+    S::Item: Future + Send + 'a,
+    <S::Item as Future>::Output: ResolveOwned<'a> + Send + 'a,
+{
+    type Item = <S::Item as Future>::Output;
+
+    fn into_items(self, ctx: &Context) -> BoxStream<'a, Self::Item> {
+        let concurrency = ctx
+            .data_opt::<StreamConcurrency>()
+            .map_or(DEFAULT_STREAM_CONCURRENCY, |concurrency| concurrency.0);
+        self.0.buffered(concurrency).boxed()
+    }
+}
+
+/// resolve a stream as a list, a failed item that nulls the list stops the stream
+pub fn resolve_stream<'a, 'b, S>(
+    value: S,
+    ctx: &'b Context<'b>,
+) -> BoxFuture<'b, async_graphql::Result<Option<FieldValue<'a>>>>
+where
+    'a: 'b,
+    S: ResolveStream<'a>,
+{
+    async move {
+        let mut items = value.into_items(ctx).enumerate();
+        let mut values = Vec::new();
+        while let Some((index, item)) = items.next().await {
             let ctx_idx = ctx.with_index(index);
-            match item.resolve_ref(&ctx_idx) {
-                Ok(Some(value)) => value,
-                _ => FieldValue::NULL,
+            match list_item(&ctx_idx, S::Item::NULLABLE, item.resolve_owned(&ctx_idx))? {
+                Some(value) => values.push(value),
+                None => return Ok(None),
             }
-        });
-        Ok(Some(FieldValue::list(items)))
+        }
+        Ok(Some(FieldValue::list(values)))
+    }
+    .boxed()
+}
+
+/// resolve every item of a stream as an event of a subscription field
+pub fn subscription_stream<'a, S: ResolveStream<'a>>(
+    value: S,
+    ctx: &'a Context<'a>,
+) -> impl Stream<Item = async_graphql::Result<FieldValue<'a>>> + Send + 'a {
+    value
+        .into_items(ctx)
+        .map(move |item| Ok(item.resolve_owned(ctx)?.unwrap_or(FieldValue::NULL)))
+}
+
+macro_rules! output_list {
+    ($($ty:ident $(: $bound:path)?),*) => {
+        $(
+            impl<'a, T: ResolveOwned<'a> $(+ $bound)?> ResolveOwned<'a> for $ty<T> {
+                fn resolve_owned(self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+                    resolve_items_owned(ctx, self)
+                }
+            }
+            impl<'a, T: ResolveRef<'a> + 'a> ResolveRef<'a> for $ty<T> {
+                fn resolve_ref(&'a self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+                    resolve_items_ref(ctx, self)
+                }
+            }
+        )*
+    };
+}
+
+output_list!(Vec, VecDeque, BTreeSet, HashSet);
+
+impl<'a, T: ResolveOwned<'a>, const N: usize> ResolveOwned<'a> for [T; N] {
+    fn resolve_owned(self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        resolve_items_owned(ctx, self)
+    }
+}
+
+impl<'a, T: ResolveRef<'a> + 'a, const N: usize> ResolveRef<'a> for [T; N] {
+    fn resolve_ref(&'a self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        resolve_items_ref(ctx, self)
+    }
+}
+
+impl<'a, T: ResolveRef<'a> + 'a> ResolveRef<'a> for [T] {
+    fn resolve_ref(&'a self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        resolve_items_ref(ctx, self)
+    }
+}
+
+/// borrowed values, e.g. `&Foo` or `&[String]` returned by a resolver
+impl<'a, T: ResolveRef<'a> + ?Sized> ResolveOwned<'a> for &'a T {
+    const NULLABLE: bool = T::NULLABLE;
+
+    #[inline]
+    fn resolve_owned(self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        self.resolve_ref(ctx)
+    }
+}
+
+impl<'a, T: ResolveOwned<'a>> ResolveOwned<'a> for Box<T> {
+    const NULLABLE: bool = T::NULLABLE;
+
+    #[inline]
+    fn resolve_owned(self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        (*self).resolve_owned(ctx)
+    }
+}
+
+impl<'a, T: ResolveRef<'a>> ResolveRef<'a> for Box<T> {
+    const NULLABLE: bool = T::NULLABLE;
+
+    #[inline]
+    fn resolve_ref(&'a self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        (**self).resolve_ref(ctx)
+    }
+}
+
+/// resolve a value shared with `Arc` without cloning it, objects keep the `Arc` as parent value
+pub trait ResolveShared: Send + Sync + 'static {
+    fn resolve_shared<'a>(
+        value: Arc<Self>,
+        ctx: &Context,
+    ) -> async_graphql::Result<Option<FieldValue<'a>>>;
+}
+
+impl<T: Object + Send + Sync + 'static> ResolveShared for T {
+    fn resolve_shared<'a>(
+        value: Arc<Self>,
+        _ctx: &Context,
+    ) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::owned_any(value)))
+    }
+}
+
+impl<'a, T: ResolveShared> ResolveOwned<'a> for Arc<T> {
+    #[inline]
+    fn resolve_owned(self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        T::resolve_shared(self, ctx)
+    }
+}
+
+impl<'a, T: ResolveRef<'a>> ResolveRef<'a> for Arc<T> {
+    const NULLABLE: bool = T::NULLABLE;
+
+    #[inline]
+    fn resolve_ref(&'a self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        (**self).resolve_ref(ctx)
+    }
+}
+
+impl<'a, T> ResolveOwned<'a> for Cow<'a, T>
+where
+    T: ToOwned + ?Sized,
+    &'a T: ResolveOwned<'a>,
+    T::Owned: ResolveOwned<'a>,
+{
+    fn resolve_owned(self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        match self {
+            Cow::Borrowed(value) => value.resolve_owned(ctx),
+            Cow::Owned(value) => value.resolve_owned(ctx),
+        }
+    }
+}
+
+impl<'a, T> ResolveRef<'a> for Cow<'_, T>
+where
+    T: ToOwned + ?Sized + 'a,
+    &'a T: ResolveOwned<'a>,
+{
+    fn resolve_ref(&'a self, ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        self.as_ref().resolve_owned(ctx)
     }
 }
 
@@ -126,8 +423,168 @@ macro_rules! output_value {
                     Ok(Some(FieldValue::value(self.to_owned())))
                 }
             }
+            impl ResolveShared for $ty {
+                fn resolve_shared<'a>(value: Arc<Self>, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+                    Ok(Some(FieldValue::value((*value).to_owned())))
+                }
+            }
+        )*
+    };
+}
+
+output_value!(String, i8, i16, i32, u8, u16, bool, f32, f64);
+
+/// graphql `Int` is 32-bit, wider integers that don't fit are an error instead of a number
+/// clients can't parse. Use `BigInt` for values beyond the range
+fn checked_int<'a, T: Copy + Display>(value: T) -> async_graphql::Result<Option<FieldValue<'a>>>
+where
+    i32: TryFrom<T>,
+{
+    match i32::try_from(value) {
+        Ok(value) => Ok(Some(FieldValue::value(value))),
+        Err(_) => Err(format!(
+            "internal: {} of rust type {} is out of range of Int, use BigInt",
+            value,
+            std::any::type_name::<T>()
+        )
+        .into()),
+    }
+}
+
+macro_rules! output_int {
+    ($($ty:ident),*) => {
+        $(
+            impl <'a> ResolveOwned<'a> for $ty {
+                #[inline]
+                fn resolve_owned(self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+                    checked_int(self)
+                }
+            }
+            impl <'a> ResolveRef<'a> for $ty {
+                fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+                    checked_int(*self)
+                }
+            }
+            impl ResolveShared for $ty {
+                fn resolve_shared<'a>(value: Arc<Self>, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+                    checked_int(*value)
+                }
+            }
         )*
     };
 }
 
-output_value!(String, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, bool, f32, f64);
+output_int!(i64, isize, u32, u64, usize);
+
+/// graphql type of an output value, e.g. `[String]!` for `Vec<Option<Arc<String>>>`. Objects
+/// implement it in the generated code, `&T` and `Box<T>` rule out a blanket impl
+pub trait OutputTypeRef {
+    /// named type of the value or of the list items
+    fn type_name() -> &'static str;
+
+    const NULLABLE: bool = false;
+
+    /// nullability of the list items, `None` when the value is not a list
+    const ITEM_NULLABLE: Option<bool> = None;
+
+    fn type_ref() -> TypeRef {
+        match (Self::ITEM_NULLABLE, Self::NULLABLE) {
+            (None, true) => TypeRef::named(Self::type_name()),
+            (None, false) => TypeRef::named_nn(Self::type_name()),
+            (Some(true), true) => TypeRef::named_list(Self::type_name()),
+            (Some(true), false) => TypeRef::named_list_nn(Self::type_name()),
+            (Some(false), true) => TypeRef::named_nn_list(Self::type_name()),
+            (Some(false), false) => TypeRef::named_nn_list_nn(Self::type_name()),
+        }
+    }
+}
+
+macro_rules! output_type_ref {
+    ($name:expr => $($ty:ty),*) => {
+        $(
+            impl OutputTypeRef for $ty {
+                fn type_name() -> &'static str {
+                    $name
+                }
+            }
+        )*
+    };
+}
+
+output_type_ref!(TypeRef::STRING => String, str);
+output_type_ref!(TypeRef::INT => i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+output_type_ref!(TypeRef::FLOAT => f32, f64);
+output_type_ref!(TypeRef::BOOLEAN => bool);
+output_type_ref!(TypeRef::ID => async_graphql::ID);
+
+impl<T: OutputTypeRef> OutputTypeRef for Option<T> {
+    const NULLABLE: bool = true;
+    const ITEM_NULLABLE: Option<bool> = T::ITEM_NULLABLE;
+
+    fn type_name() -> &'static str {
+        T::type_name()
+    }
+}
+
+impl<T: OutputTypeRef, E> OutputTypeRef for Result<T, E> {
+    const NULLABLE: bool = T::NULLABLE;
+    const ITEM_NULLABLE: Option<bool> = T::ITEM_NULLABLE;
+
+    fn type_name() -> &'static str {
+        T::type_name()
+    }
+}
+
+macro_rules! output_type_ref_wrapper {
+    ($($ty:ty),*) => {
+        $(
+            impl<T: OutputTypeRef + ?Sized> OutputTypeRef for $ty {
+                const NULLABLE: bool = T::NULLABLE;
+                const ITEM_NULLABLE: Option<bool> = T::ITEM_NULLABLE;
+
+                fn type_name() -> &'static str {
+                    T::type_name()
+                }
+            }
+        )*
+    };
+}
+
+output_type_ref_wrapper!(&T, Box<T>, Arc<T>);
+
+impl<T: OutputTypeRef + ToOwned + ?Sized> OutputTypeRef for Cow<'_, T> {
+    const NULLABLE: bool = T::NULLABLE;
+    const ITEM_NULLABLE: Option<bool> = T::ITEM_NULLABLE;
+
+    fn type_name() -> &'static str {
+        T::type_name()
+    }
+}
+
+macro_rules! output_type_ref_list {
+    ($($ty:ty),*) => {
+        $(
+            impl<T: OutputTypeRef> OutputTypeRef for $ty {
+                // `TypeRef` of async-graphql 5 can't express lists of lists
+                const ITEM_NULLABLE: Option<bool> = {
+                    assert!(T::ITEM_NULLABLE.is_none(), "lists of lists are not supported");
+                    Some(T::NULLABLE)
+                };
+
+                fn type_name() -> &'static str {
+                    T::type_name()
+                }
+            }
+        )*
+    };
+}
+
+output_type_ref_list!(Vec<T>, VecDeque<T>, BTreeSet<T>, HashSet<T>, [T]);
+
+impl<T: OutputTypeRef, const N: usize> OutputTypeRef for [T; N] {
+    const ITEM_NULLABLE: Option<bool> = <[T] as OutputTypeRef>::ITEM_NULLABLE;
+
+    fn type_name() -> &'static str {
+        T::type_name()
+    }
+}
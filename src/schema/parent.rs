@@ -0,0 +1,279 @@
+use crate::schema::interface::utils::{downcast_object, ObjectTypeName, ObjectTypeNames};
+use crate::schema::registry::Object;
+use async_graphql::dynamic::{FieldValue, ResolverContext};
+use async_graphql::extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute};
+use async_graphql::parser::types::OperationType;
+use async_graphql::{Response, Value};
+use std::any::Any;
+use std::sync::Arc;
+
+tokio::task_local! {
+    // query root of the operation being executed, see `RootValueCheckExtension`
+    static ROOT_VALUE_CHECK: ObjectTypeName;
+}
+
+/// debug mode checking the root value of every operation, see `Registry::check_root_value`
+pub(crate) struct RootValueCheckExtension(pub ObjectTypeName);
+
+impl ExtensionFactory for RootValueCheckExtension {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(RootValueCheckExtension(self.0))
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for RootValueCheckExtension {
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        ROOT_VALUE_CHECK
+            .scope(self.0, next.run(ctx, operation_name))
+            .await
+    }
+}
+
+/// downcast the parent value of a resolver, the error names the field, the expected rust type
+/// and the value actually supplied
+pub fn parent_value<'a, T: Object + Any>(
+    ctx: &ResolverContext<'a>,
+) -> async_graphql::Result<&'a T> {
+    check_root_value::<T>(ctx)?;
+    // objects shared with `Arc` are resolved without cloning them
+    downcast_object::<T>(ctx.parent_value).ok_or_else(|| {
+        async_graphql::Error::new(format!(
+            "internal: parent value of {}.{} should be rust type {}, got {}",
+            <T as Object>::NAME,
+            ctx.field().name(),
+            std::any::type_name::<T>(),
+            describe_value(ctx, ctx.parent_value),
+        ))
+    })
+}
+
+// extensions don't see the root value of a dynamic request, so the operations executed by
+// `RootValueCheckExtension` check it when their root fields are resolved. Queries expect the
+// registered query root, mutations the object of the resolved root field
+fn check_root_value<T: Object + Any>(ctx: &ResolverContext) -> async_graphql::Result<()> {
+    let is_root = ctx
+        .path_node
+        .as_ref()
+        .is_some_and(|node| node.parent.is_none());
+    let Ok(query_root) = ROOT_VALUE_CHECK.try_with(|root| *root) else {
+        return Ok(());
+    };
+    let root = match ctx.query_env.operation.node.ty {
+        OperationType::Query => query_root,
+        OperationType::Mutation => ObjectTypeName::of::<T>(),
+        OperationType::Subscription => return Ok(()),
+    };
+    if !is_root || root.matches(ctx.parent_value) {
+        return Ok(());
+    }
+    Err(async_graphql::Error::new(format!(
+        "internal: root value should be rust type {} of {}, got {}, is `root_value` missing on the request?",
+        root.rust_name,
+        root.name,
+        describe_value(ctx, ctx.parent_value),
+    )))
+}
+
+fn describe_value(ctx: &ResolverContext, value: &FieldValue) -> String {
+    if let Some(object) = ctx
+        .data_opt::<ObjectTypeNames>()
+        .and_then(|names| names.find(value))
+    {
+        return format!("rust type {} of {}", object.rust_name, object.name);
+    }
+    match (value.as_value(), value.as_list()) {
+        (Some(Value::Null), _) => "null".to_string(),
+        (Some(value), _) => format!("value {}", value),
+        (_, Some(list)) => format!("list of {} items", list.len()),
+        _ => "rust value of an unregistered type".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::output_types::utils::ResolveOwned;
+    use crate::schema::registry::{Register, Registry};
+    use async_graphql::dynamic;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    // mark as object
+    struct Query;
+
+    impl Query {
+        async fn resolve_hello(&self) -> String {
+            "world".to_string()
+        }
+    }
+
+    // mark as object
+    struct Other;
+
+    impl Object for Query {
+        const NAME: &'static str = "Query";
+    }
+
+    impl Register for Query {
+        fn register(registry: Registry) -> Registry {
+            let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+            // define hello field
+            let hello_field = dynamic::Field::new(
+                "hello",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                |ctx| {
+                    dynamic::FieldFuture::new(async move {
+                        let parent = parent_value::<Self>(&ctx)?;
+                        let value = parent.resolve_hello().await;
+                        ResolveOwned::resolve_owned(value, &ctx)
+                    })
+                },
+            );
+            let object_type = object_type.field(hello_field);
+
+            registry.register_object_type::<Self>(object_type)
+        }
+    }
+
+    // mark as object
+    struct Mutation;
+
+    impl Mutation {
+        async fn resolve_touch(&self) -> bool {
+            true
+        }
+    }
+
+    impl Object for Other {
+        const NAME: &'static str = "Other";
+    }
+
+    impl Register for Other {
+        fn register(registry: Registry) -> Registry {
+            let object_type = dynamic::Object::new(<Self as Object>::NAME).field(
+                dynamic::Field::new("id", dynamic::TypeRef::named(dynamic::TypeRef::ID), |_| {
+                    dynamic::FieldFuture::new(async move { Ok(None::<dynamic::FieldValue>) })
+                }),
+            );
+            registry.register_object_type::<Self>(object_type)
+        }
+    }
+
+    impl Object for Mutation {
+        const NAME: &'static str = "Mutation";
+    }
+
+    impl Register for Mutation {
+        fn register(registry: Registry) -> Registry {
+            let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+            // define touch field
+            let touch_field = dynamic::Field::new(
+                "touch",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::BOOLEAN),
+                |ctx| {
+                    dynamic::FieldFuture::new(async move {
+                        let parent = parent_value::<Self>(&ctx)?;
+                        let value = parent.resolve_touch().await;
+                        ResolveOwned::resolve_owned(value, &ctx)
+                    })
+                },
+            );
+            let object_type = object_type.field(touch_field);
+
+            registry.register_object_type::<Self>(object_type)
+        }
+    }
+
+    fn create_schema(registry: Registry) -> dynamic::Schema {
+        let registry = registry.register::<Query>().register::<Other>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        registry.build_schema(schema).finish().unwrap()
+    }
+
+    async fn execute(schema: &dynamic::Schema, root: Option<FieldValue<'static>>) -> String {
+        let req = async_graphql::Request::new("{ hello }");
+        let res = match root {
+            Some(root) => schema.execute(req.root_value(root)).await,
+            None => schema.execute(req).await,
+        };
+        assert_eq!(res.errors.len(), 1);
+        res.errors[0].message.clone()
+    }
+
+    #[tokio::test]
+    async fn test_missing_root() {
+        let schema = create_schema(Registry::new());
+        let message = execute(&schema, None).await;
+        assert!(message.starts_with("internal: parent value of Query.hello should be rust type "));
+        assert!(message.ends_with("::Query, got null"));
+    }
+
+    #[tokio::test]
+    async fn test_wrong_root() {
+        let schema = create_schema(Registry::new());
+        let message = execute(&schema, Some(FieldValue::owned_any(Other))).await;
+        assert!(message.ends_with("::Other of Other"));
+        let message = execute(&schema, Some(FieldValue::owned_any(1_u8))).await;
+        assert!(message.ends_with("got rust value of an unregistered type"));
+    }
+
+    #[tokio::test]
+    async fn test_root_value_check() {
+        let schema = create_schema(Registry::new().check_root_value::<Query>());
+        let message = execute(&schema, None).await;
+        assert!(message.starts_with("internal: root value should be rust type "));
+        assert!(message.ends_with("got null, is `root_value` missing on the request?"));
+
+        let req = async_graphql::Request::new("{ hello }").root_value(FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        assert_eq!(
+            res.data.into_json().unwrap(),
+            serde_json::json!({ "hello": "world" })
+        );
+
+        // a shared root value is accepted as well
+        let req = async_graphql::Request::new("{ hello }")
+            .root_value(FieldValue::owned_any(Arc::new(Query)));
+        let res = schema.execute(req).await;
+        assert_eq!(
+            res.data.into_json().unwrap(),
+            serde_json::json!({ "hello": "world" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_root_value_check_mutation() {
+        // the mutation root has its own root value
+        let registry = Registry::new()
+            .check_root_value::<Query>()
+            .register::<Query>()
+            .register::<Mutation>();
+        let schema = dynamic::Schema::build(Query::NAME, Some(Mutation::NAME), None);
+        let schema = registry.build_schema(schema).finish().unwrap();
+        let req = async_graphql::Request::new("mutation { touch }")
+            .root_value(FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        assert_eq!(res.errors.len(), 1);
+        assert!(res.errors[0]
+            .message
+            .starts_with("internal: root value should be rust type "));
+        assert!(res.errors[0]
+            .message
+            .ends_with("::Query of Query, is `root_value` missing on the request?"));
+
+        let req = async_graphql::Request::new("mutation { touch }")
+            .root_value(FieldValue::owned_any(Mutation));
+        let res = schema.execute(req).await;
+        assert_eq!(
+            res.data.into_json().unwrap(),
+            serde_json::json!({ "touch": true })
+        );
+    }
+}
@@ -1,6 +1,20 @@
+use crate::schema::errors::utils::{ErrorCode, ErrorHook, ErrorHookExtension};
+use crate::schema::features::utils::FeatureFlags;
+use crate::schema::handle::SchemaBuildError;
+use crate::schema::incremental::utils::IncrementalExtension;
+use crate::schema::input::utils::{ComposedInputObject, OneofInputObject};
+use crate::schema::interface::utils::{InterfaceCast, ObjectTypeName, ObjectTypeNames};
+use crate::schema::output_types::utils::{LenientListItems, ListFieldExtension, StreamConcurrency};
+use crate::schema::parent::RootValueCheckExtension;
+use crate::schema::union::utils::UnionDefinition;
+use crate::schema::validator::utils::InputValueValidator;
+use crate::schema::versions::utils::{ApiVersion, VersionRange, VersionedSchemas};
+use crate::schema::visibility::utils::{SchemaElement, VisibilityExtension, VisibleFn};
 use async_graphql::dynamic;
 use async_graphql::dynamic::SchemaBuilder;
-use std::collections::{HashMap, VecDeque};
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 
 pub trait Register {
     fn register(registry: Registry) -> Registry;
@@ -11,6 +25,17 @@ pub trait ExpandObject {
     type Target: Object;
 }
 
+/// add field to interface and to every object implementing it
+pub trait ExtendInterface {
+    /// interface trait object, e.g. `dyn Node`
+    type Interface: ?Sized + 'static;
+    const INTERFACE: &'static str;
+    const FIELD: &'static str;
+
+    fn interface_field() -> dynamic::InterfaceField;
+    fn object_field(cast: InterfaceCast<Self::Interface>) -> dynamic::Field;
+}
+
 /// define new object marked as extend (not supported yet)
 pub trait ExtendObject {
     type Target: Object;
@@ -30,8 +55,22 @@ pub trait Union {
 
 pub trait Interface {
     const NAME: &'static str;
+    /// parent interfaces, fields and implementations are inherited by the registry
+    const IMPLEMENTS: &'static [&'static str] = &[];
 
     fn register_fields(interface: dynamic::Interface) -> dynamic::Interface;
+
+    /// `InterfaceCast` to the parent interface from the `InterfaceCast` of an implementer,
+    /// fields added to the parent by `ExtendInterface` reach the implementers through it
+    fn parent_cast(_parent: &str, _cast: &dyn Any) -> Option<Arc<dyn Any>> {
+        None
+    }
+}
+
+/// fields of an interface by name, `register_interface_type` copies inherited fields one by
+/// one so an interface can redefine a field of its parents
+pub trait InterfaceFields: Interface {
+    fn interface_fields() -> Vec<(&'static str, dynamic::InterfaceField)>;
 }
 
 pub trait InputObject {
@@ -42,17 +81,42 @@ pub trait Mutation: Object {
     type Root;
 }
 
+#[derive(Clone)]
 pub struct ExpandObjectContext {
     definition: String,
     field: String,
 }
 
+/// adds the fields of an `ExpandObject` to its target, kept by the registry so every build
+/// creates them again
+type ExpandObjectFn = Arc<dyn Fn(dynamic::Object) -> dynamic::Object + Send + Sync>;
+
 struct PendingExpandObject {
     name: String,
-    map: Box<dyn FnOnce(dynamic::Object) -> dynamic::Object>,
+    map: ExpandObjectFn,
     ctx: ExpandObjectContext,
 }
 
+/// registration replayed by clones of the registry, e.g. `Register::register`
+type RegistryStep = Arc<dyn Fn(Registry) -> Registry + Send + Sync>;
+
+/// build the object field from the `InterfaceCast` of an implementer
+type InterfaceObjectField = Box<dyn Fn(&dyn Any) -> Option<dynamic::Field>>;
+
+struct PendingInterfaceField {
+    interface: &'static str,
+    field: &'static str,
+    default: bool,
+    interface_field: fn() -> dynamic::InterfaceField,
+    object_field: InterfaceObjectField,
+}
+
+struct InterfaceDefinition {
+    implements: &'static [&'static str],
+    fields: fn() -> Vec<(&'static str, dynamic::InterfaceField)>,
+    parent_cast: fn(&str, &dyn Any) -> Option<Arc<dyn Any>>,
+}
+
 impl ExpandObjectContext {
     pub fn new(definition: &str, field: &str) -> Self {
         Self {
@@ -65,11 +129,66 @@ impl ExpandObjectContext {
 pub struct Registry {
     types: HashMap<String, dynamic::Object>,
     extend_types: Vec<dynamic::Object>,
+    subscriptions: HashMap<String, dynamic::Subscription>,
     enums: HashMap<String, dynamic::Enum>,
+    scalars: HashMap<String, dynamic::Scalar>,
     unions: HashMap<String, dynamic::Union>,
     interfaces: HashMap<String, dynamic::Interface>,
     input_types: HashMap<String, dynamic::InputObject>,
+    oneof_input_types: HashMap<String, OneofInputObject>,
+    composed_input_types: HashMap<String, ComposedInputObject>,
     pending_expand_objects: VecDeque<PendingExpandObject>,
+    interface_definitions: HashMap<String, InterfaceDefinition>,
+    // fields of the interfaces built from their definitions
+    interface_field_names: HashMap<String, HashSet<String>>,
+    object_interfaces: HashMap<String, Vec<String>>,
+    // fields the objects define on their `dynamic::Object`
+    object_field_names: HashMap<String, HashSet<String>>,
+    // interfaces the objects implement on their `dynamic::Object`
+    object_own_interfaces: HashMap<String, Vec<String>>,
+    // (object, interface) -> InterfaceCast
+    interface_casts: HashMap<(String, String), Arc<dyn Any>>,
+    pending_interface_fields: Vec<PendingInterfaceField>,
+    // (object, interface, field)
+    overridden_interface_fields: HashSet<(String, String, String)>,
+    union_members: HashMap<String, Vec<String>>,
+    open_unions: HashSet<String>,
+    // (union, object)
+    union_joins: Vec<(String, String)>,
+    object_type_names: HashMap<TypeId, ObjectTypeName>,
+    root_value_check: Option<ObjectTypeName>,
+    lenient_list_items: bool,
+    stream_concurrency: Option<usize>,
+    error_hook: Option<ErrorHook>,
+    // Type.field -> codes
+    error_codes: BTreeMap<String, Vec<&'static str>>,
+    // (rust type, rust type name, abstract type)
+    expected_object_types: Vec<(TypeId, &'static str, String)>,
+    steps: Vec<RegistryStep>,
+    // > 0 while a step is applied
+    depth: usize,
+    // types registered outside of a step, they can't be replayed
+    detached: Vec<String>,
+    // types a replay left out, reported when the schema is built
+    replay_errors: Vec<String>,
+    feature_flags: Option<FeatureFlags>,
+    // type -> feature
+    disabled_types: BTreeMap<String, String>,
+    // (element, type), e.g. ("Query.recommended", "Recommendation")
+    type_references: Vec<(String, String)>,
+    // (Type.field, arguments, validators)
+    argument_validators: Vec<(String, Vec<&'static str>, Vec<InputValueValidator>)>,
+    visibility: Vec<(SchemaElement, VisibleFn)>,
+    // version of the schema built by `build_versions`
+    version: Option<ApiVersion>,
+}
+
+/// replays the registrations on a new registry, a type registered outside of `register` or
+/// `register_with` fails the build of the clone
+impl Clone for Registry {
+    fn clone(&self) -> Self {
+        self.replay(self.empty(), "clone registry")
+    }
 }
 
 impl Registry {
@@ -77,66 +196,554 @@ impl Registry {
         Self {
             types: Default::default(),
             extend_types: Default::default(),
+            subscriptions: Default::default(),
             enums: Default::default(),
+            scalars: Default::default(),
             unions: Default::default(),
             interfaces: Default::default(),
             input_types: Default::default(),
+            oneof_input_types: Default::default(),
+            composed_input_types: Default::default(),
             pending_expand_objects: Default::default(),
+            interface_definitions: Default::default(),
+            interface_field_names: Default::default(),
+            object_interfaces: Default::default(),
+            object_field_names: Default::default(),
+            object_own_interfaces: Default::default(),
+            interface_casts: Default::default(),
+            pending_interface_fields: Default::default(),
+            overridden_interface_fields: Default::default(),
+            union_members: Default::default(),
+            open_unions: Default::default(),
+            union_joins: Default::default(),
+            object_type_names: Default::default(),
+            root_value_check: Default::default(),
+            lenient_list_items: Default::default(),
+            stream_concurrency: Default::default(),
+            error_hook: Default::default(),
+            error_codes: Default::default(),
+            expected_object_types: Default::default(),
+            steps: Default::default(),
+            depth: Default::default(),
+            detached: Default::default(),
+            replay_errors: Default::default(),
+            feature_flags: Default::default(),
+            disabled_types: Default::default(),
+            type_references: Default::default(),
+            argument_validators: Default::default(),
+            visibility: Default::default(),
+            version: Default::default(),
         }
     }
     pub fn register<T: Register>(self) -> Self {
-        T::register(self)
+        let register: fn(Registry) -> Registry = T::register;
+        self.register_with(register)
     }
-    pub fn register_object(mut self, object: dynamic::Object) -> Self {
-        self.types.insert(object.type_name().to_string(), object);
+    /// registration kept by the registry, clones replay it to build their own types
+    pub fn register_with<F>(self, f: F) -> Self
+    where
+        F: Fn(Registry) -> Registry + Send + Sync + 'static,
+    {
+        if self.depth > 0 {
+            return f(self);
+        }
+        self.apply_step(Arc::new(f))
+    }
+    /// replays the registrations on the registry, the types registered outside of a step
+    /// can't be replayed and fail its build
+    fn replay(&self, mut registry: Registry, action: &str) -> Registry {
+        registry.replay_errors = self.replay_errors.clone();
+        if !self.detached.is_empty() {
+            registry.replay_errors.push(format!(
+                "Can't {}, registered outside of a step: {}",
+                action,
+                self.detached.join(", ")
+            ));
+        }
+        self.steps
+            .iter()
+            .fold(registry, |registry, step| registry.apply_step(step.clone()))
+    }
+    /// registry without registrations, with the feature flags and the version of this one
+    fn empty(&self) -> Registry {
+        let mut registry = Registry::new();
+        registry.feature_flags = self.feature_flags.clone();
+        registry.version = self.version;
+        registry
+    }
+    fn apply_step(mut self, step: RegistryStep) -> Self {
+        self.steps.push(step.clone());
+        self.depth += 1;
+        let mut registry = step(self);
+        registry.depth -= 1;
+        registry
+    }
+    /// types can't be cloned, a type registered outside of `register` or `register_with` fails
+    /// the build of the clones
+    fn detach(mut self, name: &str) -> Self {
+        if self.depth == 0 {
+            self.detached.push(name.to_string());
+        }
         self
     }
+    /// decides which features are enabled, the registrations are replayed so elements
+    /// registered before see the flags too. Types registered outside of a step fail the build
+    pub fn feature_flags<F>(self, flags: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        let mut registry = self.empty();
+        registry.feature_flags = Some(Arc::new(flags));
+        if self.depth > 0 {
+            // registrations of the current step are kept
+            return Registry {
+                feature_flags: registry.feature_flags,
+                ..self
+            };
+        }
+        self.replay(registry, "apply feature flags")
+    }
+    /// every feature is enabled without feature flags
+    pub fn feature_enabled(&self, feature: &str) -> bool {
+        self.feature_flags
+            .as_ref()
+            .is_none_or(|flags| flags(feature))
+    }
+    /// every element exists without a version, i.e. outside of `build_versions`
+    pub fn in_version(&self, range: VersionRange) -> bool {
+        self.version.is_none_or(|version| range.contains(version))
+    }
+    /// build one schema per version, the registrations are replayed for every version so
+    /// elements outside of its range are left out
+    pub fn build_versions<F>(
+        self,
+        versions: &[ApiVersion],
+        schema_builder: F,
+    ) -> Result<VersionedSchemas, SchemaBuildError>
+    where
+        F: Fn() -> SchemaBuilder,
+    {
+        let mut schemas = BTreeMap::new();
+        for &version in versions {
+            let mut registry = self.empty();
+            registry.version = Some(version);
+            let schema = self
+                .replay(registry, "build versions")
+                .finish_schema(schema_builder())
+                .map_err(|err| match err {
+                    SchemaBuildError::Registry(err) => {
+                        SchemaBuildError::Registry(format!("{}: {}", version, err))
+                    }
+                    SchemaBuildError::Schema(err) => {
+                        SchemaBuildError::Schema(format!("{}: {}", version, err))
+                    }
+                })?;
+            schemas.insert(version, schema);
+        }
+        Ok(VersionedSchemas::new(schemas))
+    }
+    /// the type is dropped because its feature is disabled, references to it are errors
+    pub fn disable_type(self, name: &str, feature: &str) -> Self {
+        let (name, feature) = (name.to_string(), feature.to_string());
+        self.register_with(move |mut registry| {
+            registry
+                .disabled_types
+                .insert(name.clone(), feature.clone());
+            registry
+        })
+    }
+    /// the element, e.g. `Query.recommended`, refers to the type, a reference to a type of a
+    /// disabled feature fails the build
+    pub fn reference_type(self, element: &str, type_name: &str) -> Self {
+        let (element, type_name) = (element.to_string(), type_name.to_string());
+        self.register_with(move |mut registry| {
+            registry
+                .type_references
+                .push((element.clone(), type_name.clone()));
+            registry
+        })
+    }
+    /// the validators of the field, checked against its arguments when the schema is built
+    pub fn check_validators(
+        self,
+        field: &str,
+        arguments: &[&'static str],
+        validators: &[InputValueValidator],
+    ) -> Self {
+        let entry = (field.to_string(), arguments.to_vec(), validators.to_vec());
+        self.register_with(move |mut registry| {
+            registry.argument_validators.push(entry.clone());
+            registry
+        })
+    }
+    /// the element is hidden from requests failing the predicate, in validation and in
+    /// introspection
+    pub fn visible_when<F>(self, element: SchemaElement, visible: F) -> Self
+    where
+        F: Fn(&async_graphql::extensions::ExtensionContext<'_>) -> bool + Send + Sync + 'static,
+    {
+        let visible: VisibleFn = Arc::new(visible);
+        self.register_with(move |mut registry| {
+            registry.visibility.push((element.clone(), visible.clone()));
+            registry
+        })
+    }
+    fn disabled_by(&self, name: &str) -> Option<&str> {
+        self.disabled_types.get(name).map(String::as_str)
+    }
+    pub fn register_object(mut self, object: dynamic::Object) -> Self {
+        let name = object.type_name().to_string();
+        self.types.insert(name.clone(), object);
+        self.detach(&name)
+    }
+    pub fn register_subscription(mut self, subscription: dynamic::Subscription) -> Self {
+        let name = subscription.type_name().to_string();
+        self.subscriptions.insert(name.clone(), subscription);
+        self.detach(&name)
+    }
+    /// register object and remember its rust type, abstract types returning an `AutoBox`
+    /// find the graphql type from the value
+    pub fn register_object_type<T: Object + 'static>(mut self, object: dynamic::Object) -> Self {
+        self.object_type_names
+            .insert(TypeId::of::<T>(), ObjectTypeName::of::<T>());
+        // values shared with `Arc`
+        self.object_type_names
+            .insert(TypeId::of::<Arc<T>>(), ObjectTypeName::of::<T>());
+        self.register_object(object)
+    }
+    /// the abstract type returns values of the rust type, checked when the schema is built
+    pub fn expect_object_type<T: 'static>(self, abstract_type: &str) -> Self {
+        let abstract_type = abstract_type.to_string();
+        self.register_with(move |mut registry| {
+            registry.expected_object_types.push((
+                TypeId::of::<T>(),
+                std::any::type_name::<T>(),
+                abstract_type.clone(),
+            ));
+            registry
+        })
+    }
+    /// debug mode, the root value of every query is checked against the query root, and of every
+    /// mutation against the mutation root, before their fields are resolved
+    pub fn check_root_value<T: Object + 'static>(self) -> Self {
+        self.register_with(|mut registry| {
+            registry.root_value_check = Some(ObjectTypeName::of::<T>());
+            registry
+        })
+    }
+    /// failed list items resolve to null without an error, instead of being reported with
+    /// their index
+    pub fn lenient_list_items(self) -> Self {
+        self.register_with(|mut registry| {
+            registry.lenient_list_items = true;
+            registry
+        })
+    }
+    /// number of item futures of a `Concurrent` stream resolved at once, 16 by default, 0 fails
+    /// the build
+    pub fn stream_concurrency(self, concurrency: usize) -> Self {
+        self.register_with(move |mut registry| {
+            registry.stream_concurrency = Some(concurrency);
+            registry
+        })
+    }
+    /// called with every error of the execution, e.g. to log the original error and mask the
+    /// message of internal errors. Parse and validation errors are not passed to the hook
+    pub fn error_hook<F>(self, hook: F) -> Self
+    where
+        F: Fn(async_graphql::ServerError) -> async_graphql::ServerError + Send + Sync + 'static,
+    {
+        let hook: ErrorHook = Arc::new(hook);
+        self.register_with(move |mut registry| {
+            registry.error_hook = Some(hook.clone());
+            registry
+        })
+    }
+    /// the field can fail with the codes of the error, listed by `error_catalog`
+    pub fn register_error_codes<E: ErrorCode>(self, object: &str, field: &str) -> Self {
+        let key = format!("{}.{}", object, field);
+        self.register_with(move |mut registry| {
+            let codes = registry.error_codes.entry(key.clone()).or_default();
+            for code in E::CODES {
+                if !codes.contains(code) {
+                    codes.push(code);
+                }
+            }
+            registry
+        })
+    }
+    /// (Type.field, codes) for every field with typed errors, sorted by field
+    pub fn error_catalog(&self) -> Vec<(String, Vec<&'static str>)> {
+        self.error_codes
+            .iter()
+            .map(|(field, codes)| (field.clone(), codes.clone()))
+            .collect()
+    }
     pub fn register_extend_object(mut self, object: dynamic::Object) -> Self {
+        let name = object.type_name().to_string();
         self.extend_types.push(object);
-        self
+        self.detach(&name)
     }
 
     pub fn register_enum(mut self, enum_: dynamic::Enum) -> Self {
-        self.enums.insert(enum_.type_name().to_string(), enum_);
-        self
+        let name = enum_.type_name().to_string();
+        self.enums.insert(name.clone(), enum_);
+        self.detach(&name)
+    }
+
+    pub fn register_scalar(mut self, scalar: dynamic::Scalar) -> Self {
+        let name = scalar.type_name().to_string();
+        self.scalars.insert(name.clone(), scalar);
+        self.detach(&name)
     }
 
     pub fn register_interface(mut self, interface: dynamic::Interface) -> Self {
-        self.interfaces
-            .insert(interface.type_name().to_string(), interface);
+        let name = interface.type_name().to_string();
+        self.interfaces.insert(name.clone(), interface);
+        self.detach(&name)
+    }
+
+    /// register interface with its parents, the interface is built with the fields of all
+    /// ancestors when the schema is built
+    pub fn register_interface_type<T: InterfaceFields>(self) -> Self {
+        self.register_with(|mut registry| {
+            registry.interface_definitions.insert(
+                T::NAME.to_string(),
+                InterfaceDefinition {
+                    implements: T::IMPLEMENTS,
+                    fields: T::interface_fields,
+                    parent_cast: T::parent_cast,
+                },
+            );
+            registry
+        })
+    }
+
+    /// declare that the object implements the interface, fields added by `ExtendInterface`
+    /// resolve the parent value through the cast
+    pub fn implement_interface_as<I: ?Sized + 'static>(
+        self,
+        object: &str,
+        interface: &str,
+        cast: impl for<'a> Fn(&'a dynamic::FieldValue<'a>) -> Option<&'a I> + Send + Sync + 'static,
+    ) -> Self {
+        let (object, interface) = (object.to_string(), interface.to_string());
+        let cast: InterfaceCast<I> = Arc::new(cast);
+        self.register_with(move |mut registry| {
+            registry
+                .interface_casts
+                .insert((object.clone(), interface.clone()), Arc::new(cast.clone()));
+            registry.implement_interface(&object, &interface)
+        })
+    }
+
+    /// add a field to an interface, the field is installed on every implementer when the
+    /// schema is built
+    pub fn register_interface_field<T: ExtendInterface>(self) -> Self {
+        self.register_with(|registry| registry.push_interface_field::<T>(false))
+    }
+
+    /// add a field with a default resolver to an interface, implementers get the default
+    /// unless they override the field with `override_interface_field`
+    pub fn register_interface_default_field<T: ExtendInterface>(self) -> Self {
+        self.register_with(|registry| registry.push_interface_field::<T>(true))
+    }
+
+    /// the object defines the default field of the interface itself
+    pub fn override_interface_field(self, object: &str, interface: &str, field: &str) -> Self {
+        let key = (object.to_string(), interface.to_string(), field.to_string());
+        self.register_with(move |mut registry| {
+            registry.overridden_interface_fields.insert(key.clone());
+            registry
+        })
+    }
+
+    /// (object, interface.field) for every object resolving a field with the interface default
+    pub fn default_interface_fields(&self) -> Vec<(String, String)> {
+        let mut usages = Vec::new();
+        for field in self.pending_interface_fields.iter().filter(|f| f.default) {
+            for object in self.object_interfaces.keys() {
+                let implemented = self.implemented_interfaces(object);
+                if implemented.iter().any(|i| i == field.interface)
+                    && !self.is_overridden(object, field.interface, field.field)
+                {
+                    usages.push((
+                        object.clone(),
+                        format!("{}.{}", field.interface, field.field),
+                    ));
+                }
+            }
+        }
+        usages.sort();
+        usages
+    }
+
+    fn is_overridden(&self, object: &str, interface: &str, field: &str) -> bool {
+        self.overridden_interface_fields.contains(&(
+            object.to_string(),
+            interface.to_string(),
+            field.to_string(),
+        ))
+    }
+
+    /// interfaces declared for the object and their ancestors
+    fn implemented_interfaces(&self, object: &str) -> Vec<String> {
+        let mut implemented = Vec::new();
+        for interface in self.object_interfaces.get(object).into_iter().flatten() {
+            let ancestors = self.interface_ancestors(interface).unwrap_or_default();
+            for interface in std::iter::once(interface.clone()).chain(ancestors) {
+                if !implemented.contains(&interface) {
+                    implemented.push(interface);
+                }
+            }
+        }
+        implemented
+    }
+
+    fn push_interface_field<T: ExtendInterface>(mut self, default: bool) -> Self {
+        self.pending_interface_fields.push(PendingInterfaceField {
+            interface: T::INTERFACE,
+            field: T::FIELD,
+            default,
+            interface_field: T::interface_field,
+            object_field: Box::new(|cast| {
+                cast.downcast_ref::<InterfaceCast<T::Interface>>()
+                    .map(|cast| T::object_field(cast.clone()))
+            }),
+        });
         self
     }
 
+    /// the object defines the fields itself, an interface field of the same name fails the
+    /// build unless the object overrides it with `override_interface_field`
+    pub fn object_fields(self, object: &str, fields: &[&str]) -> Self {
+        let object = object.to_string();
+        let fields = fields
+            .iter()
+            .map(|field| field.to_string())
+            .collect::<Vec<_>>();
+        self.register_with(move |mut registry| {
+            registry
+                .object_field_names
+                .entry(object.clone())
+                .or_default()
+                .extend(fields.iter().cloned());
+            registry
+        })
+    }
+
+    /// the object implements the interfaces itself with `dynamic::Object::implement`, the
+    /// registry does not add them again when they are inherited
+    pub fn object_implements(self, object: &str, interfaces: &[&str]) -> Self {
+        let object = object.to_string();
+        let interfaces = interfaces
+            .iter()
+            .map(|interface| interface.to_string())
+            .collect::<Vec<_>>();
+        self.register_with(move |mut registry| {
+            registry
+                .object_own_interfaces
+                .entry(object.clone())
+                .or_default()
+                .extend(interfaces.iter().cloned());
+            registry
+        })
+    }
+
+    /// declare that the object implements the interface and all of its ancestors
+    pub fn implement_interface(self, object: &str, interface: &str) -> Self {
+        let (object, interface) = (object.to_string(), interface.to_string());
+        self.register_with(move |mut registry| {
+            registry
+                .object_interfaces
+                .entry(object.clone())
+                .or_default()
+                .push(interface.clone());
+            registry
+        })
+    }
+
     pub fn register_union(mut self, union: dynamic::Union) -> Self {
-        self.unions.insert(union.type_name().to_string(), union);
-        self
+        let name = union.type_name().to_string();
+        self.unions.insert(name.clone(), union);
+        self.detach(&name)
+    }
+
+    /// register union derived from a rust enum, members are checked when the schema is built
+    pub fn register_union_type<T: UnionDefinition>(self) -> Self {
+        self.register_with(|mut registry| {
+            let members = T::MEMBERS.iter().map(|member| member.to_string()).collect();
+            registry
+                .union_members
+                .insert(<T as Union>::NAME.to_string(), members);
+            registry.register_union(T::create_union())
+        })
+    }
+
+    /// register union whose members join with `join_union`, the union is built with all
+    /// members when the schema is built
+    pub fn register_open_union(self, name: &str) -> Self {
+        let name = name.to_string();
+        self.register_with(move |mut registry| {
+            registry.open_unions.insert(name.clone());
+            registry.union_members.entry(name.clone()).or_default();
+            registry
+        })
+    }
+
+    /// add the object to an open union
+    pub fn join_union(self, union: &str, object: &str) -> Self {
+        let join = (union.to_string(), object.to_string());
+        self.register_with(move |mut registry| {
+            registry.union_joins.push(join.clone());
+            registry
+        })
     }
 
     pub fn register_input_object(mut self, object: dynamic::InputObject) -> Self {
-        self.input_types
-            .insert(object.type_name().to_string(), object);
-        self
+        let name = object.type_name().to_string();
+        self.input_types.insert(name.clone(), object);
+        self.detach(&name)
+    }
+
+    pub fn register_oneof_input_object(mut self, object: OneofInputObject) -> Self {
+        let name = object.type_name().to_string();
+        self.oneof_input_types.insert(name.clone(), object);
+        self.detach(&name)
+    }
+
+    pub fn register_composed_input_object(mut self, object: ComposedInputObject) -> Self {
+        let name = object.type_name().to_string();
+        self.composed_input_types.insert(name.clone(), object);
+        self.detach(&name)
     }
 
-    pub fn update_object<F>(mut self, name: &str, f: F, ctx: ExpandObjectContext) -> Self
+    /// add fields to an object, `f` creates them for every build of the registry
+    pub fn update_object<F>(self, name: &str, f: F, ctx: ExpandObjectContext) -> Self
     where
-        F: FnOnce(dynamic::Object) -> dynamic::Object + 'static,
+        F: Fn(dynamic::Object) -> dynamic::Object + Send + Sync + 'static,
     {
-        self.pending_expand_objects.push_back(PendingExpandObject {
-            name: name.to_string(),
-            map: Box::new(f),
-            ctx,
-        });
-        self
+        let (name, map): (String, ExpandObjectFn) = (name.to_string(), Arc::new(f));
+        self.register_with(move |mut registry| {
+            registry
+                .pending_expand_objects
+                .push_back(PendingExpandObject {
+                    name: name.clone(),
+                    map: map.clone(),
+                    ctx: ctx.clone(),
+                });
+            registry
+        })
     }
 
-    fn apply_pending(&mut self) {
+    fn apply_pending(&mut self) -> Result<(), String> {
         loop {
             if self.pending_expand_objects.is_empty() {
                 break;
             }
             let mut changed = false;
-            loop {
+            // one pass over the queue, entries pushed back wait for the next pass
+            for _ in 0..self.pending_expand_objects.len() {
                 let pending = match self.pending_expand_objects.pop_front() {
                     Some(v) => v,
                     None => break,
@@ -158,27 +765,520 @@ impl Registry {
                 let keys = self
                     .pending_expand_objects
                     .iter()
-                    .map(|p| {
-                        format!(
+                    .map(|p| match self.disabled_by(&p.name) {
+                        Some(feature) => format!(
+                            "Can't find {} when defining {} in {}, {} is disabled by feature {}",
+                            p.name, p.ctx.field, p.ctx.definition, p.name, feature
+                        ),
+                        None => format!(
                             "Can't find {} when defining {} in {}",
                             p.name, p.ctx.field, p.ctx.definition
-                        )
+                        ),
                     })
                     .collect::<Vec<_>>()
                     .join(", ");
-                panic!("Can't find object: {:?}", keys);
+                return Err(format!("Can't find object: {:?}", keys));
+            }
+        }
+        Ok(())
+    }
+
+    /// ancestors of the interface, nearest first
+    fn interface_ancestors(&self, name: &str) -> Result<Vec<String>, String> {
+        fn visit(
+            registry: &Registry,
+            name: &str,
+            path: &mut Vec<String>,
+            ancestors: &mut Vec<String>,
+        ) -> Result<(), String> {
+            let definition = match registry.interface_definitions.get(name) {
+                Some(definition) => definition,
+                None => return Ok(()),
+            };
+            path.push(name.to_string());
+            for parent in definition.implements {
+                if path.iter().any(|item| item == parent) {
+                    return Err(format!(
+                        "interface {} implements itself through {}",
+                        parent,
+                        path.join(" -> ")
+                    ));
+                }
+                if !registry.interface_definitions.contains_key(*parent) {
+                    // the fields of a plain `dynamic::Interface` can't be inherited
+                    if registry.interfaces.contains_key(*parent) {
+                        return Err(format!(
+                            "Can't inherit interface {} in {}, it is not registered with register_interface_type",
+                            parent, name
+                        ));
+                    }
+                    return Err(format!(
+                        "Can't find interface {} implemented by {}",
+                        parent, name
+                    ));
+                }
+                if !ancestors.iter().any(|item| item == parent) {
+                    ancestors.push(parent.to_string());
+                }
+                visit(registry, parent, path, ancestors)?;
+            }
+            path.pop();
+            Ok(())
+        }
+
+        let mut ancestors = Vec::new();
+        visit(self, name, &mut Vec::new(), &mut ancestors)?;
+        Ok(ancestors)
+    }
+
+    fn apply_interface_hierarchy(&mut self) -> Result<(), String> {
+        let mut errors = Vec::new();
+        let mut names = self
+            .interface_definitions
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            let ancestors = match self.interface_ancestors(&name) {
+                Ok(ancestors) => ancestors,
+                Err(err) => {
+                    errors.push(err);
+                    continue;
+                }
+            };
+            let interface = ancestors
+                .iter()
+                .fold(dynamic::Interface::new(&name), |interface, ancestor| {
+                    interface.implement(ancestor)
+                });
+            // a field is taken from the nearest interface defining it, so a redefined field
+            // of the interface wins over the inherited one
+            let mut defined = HashSet::new();
+            let mut layers = Vec::new();
+            for definer in std::iter::once(&name).chain(&ancestors) {
+                let (mut names, mut layer) = (HashSet::new(), Vec::new());
+                for (field_name, field) in (self.interface_definitions[definer].fields)() {
+                    if !names.insert(field_name) {
+                        errors.push(format!(
+                            "interface {} defines field {} twice",
+                            definer, field_name
+                        ));
+                    } else if defined.insert(field_name) {
+                        layer.push((field_name, field));
+                    }
+                }
+                layers.push(layer);
+            }
+            self.interface_field_names.insert(
+                name.clone(),
+                defined.iter().map(|field| field.to_string()).collect(),
+            );
+            // ancestor fields first
+            let interface = layers
+                .into_iter()
+                .rev()
+                .flatten()
+                .fold(interface, |interface, (_, field)| interface.field(field));
+            self.interfaces.insert(name, interface);
+        }
+
+        let object_interfaces = std::mem::take(&mut self.object_interfaces);
+        for (name, interfaces) in object_interfaces {
+            let mut object = match self.types.remove(&name) {
+                Some(object) => object,
+                None => {
+                    errors.push(format!(
+                        "Can't find object {} implementing {}",
+                        name,
+                        interfaces.join(", ")
+                    ));
+                    continue;
+                }
+            };
+            // implemented by the object itself, they are not added again
+            let own = self
+                .object_own_interfaces
+                .get(&name)
+                .cloned()
+                .unwrap_or_default();
+            let mut implemented = Vec::new();
+            for interface in interfaces {
+                if !self.interfaces.contains_key(&interface) {
+                    errors.push(format!(
+                        "Can't find interface {} implemented by {}",
+                        interface, name
+                    ));
+                    continue;
+                }
+                let ancestors = self.interface_ancestors(&interface).unwrap_or_default();
+                for interface in std::iter::once(interface).chain(ancestors) {
+                    if !implemented.contains(&interface) {
+                        if !own.contains(&interface) {
+                            object = object.implement(&interface);
+                        }
+                        implemented.push(interface);
+                    }
+                }
             }
+            self.types.insert(name.clone(), object);
+            // keep all implemented interfaces, used to install interface field extensions
+            self.object_interfaces.insert(name, implemented);
         }
+
+        if !errors.is_empty() {
+            return Err(format!(
+                "Invalid interface hierarchy: {:?}",
+                errors.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// `InterfaceCast` of the object to the interface, declared by `implement_interface_as` or
+    /// derived from the cast to an interface inheriting from it
+    fn interface_cast(&self, object: &str, interface: &str) -> Option<Arc<dyn Any>> {
+        let key = (object.to_string(), interface.to_string());
+        if let Some(cast) = self.interface_casts.get(&key) {
+            return Some(cast.clone());
+        }
+        self.interface_definitions
+            .iter()
+            .filter(|(_, definition)| definition.implements.contains(&interface))
+            .find_map(|(child, definition)| {
+                let cast = self.interface_cast(object, child)?;
+                (definition.parent_cast)(interface, cast.as_ref())
+            })
+    }
+
+    fn apply_interface_fields(&mut self) -> Result<(), String> {
+        let mut errors = Vec::new();
+        let extensions = std::mem::take(&mut self.pending_interface_fields);
+        for extension in extensions {
+            if !self.interfaces.contains_key(extension.interface) {
+                errors.push(format!(
+                    "Can't find interface {} when defining {}",
+                    extension.interface, extension.field
+                ));
+                continue;
+            }
+            // the fields of a plain `dynamic::Interface` are unknown
+            if !self.interface_field_names.contains_key(extension.interface) {
+                errors.push(format!(
+                    "Can't define {} on interface {}, it is not registered with register_interface_type",
+                    extension.field, extension.interface
+                ));
+                continue;
+            }
+
+            // the interface and the interfaces inheriting from it
+            let mut interfaces = vec![extension.interface.to_string()];
+            for name in self.interface_definitions.keys() {
+                let ancestors = self.interface_ancestors(name).unwrap_or_default();
+                if ancestors
+                    .iter()
+                    .any(|ancestor| ancestor == extension.interface)
+                {
+                    interfaces.push(name.clone());
+                }
+            }
+            interfaces[1..].sort();
+            let defined = interfaces
+                .iter()
+                .filter(|name| {
+                    self.interface_field_names
+                        .get(*name)
+                        .is_some_and(|fields| fields.contains(extension.field))
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            if !defined.is_empty() {
+                errors.push(format!(
+                    "Can't define {}.{}, the field already exists on {}",
+                    extension.interface,
+                    extension.field,
+                    defined.join(", ")
+                ));
+                continue;
+            }
+            for name in interfaces {
+                if let Some(fields) = self.interface_field_names.get_mut(&name) {
+                    fields.insert(extension.field.to_string());
+                }
+                if let Some(interface) = self.interfaces.remove(&name) {
+                    let interface = interface.field((extension.interface_field)());
+                    self.interfaces.insert(name, interface);
+                }
+            }
+
+            let mut implementers = self
+                .object_interfaces
+                .iter()
+                .filter(|(_, interfaces)| interfaces.iter().any(|i| i == extension.interface))
+                .map(|(object, _)| object.clone())
+                .collect::<Vec<_>>();
+            implementers.sort();
+            for object_name in implementers {
+                if self.is_overridden(&object_name, extension.interface, extension.field) {
+                    if !extension.default {
+                        errors.push(format!(
+                            "Can't override {}.{} on {}, the field has no default",
+                            extension.interface, extension.field, object_name
+                        ));
+                    }
+                    // the object keeps its own field
+                    continue;
+                }
+                let object_fields = self
+                    .object_field_names
+                    .entry(object_name.clone())
+                    .or_default();
+                if !object_fields.insert(extension.field.to_string()) {
+                    errors.push(format!(
+                        "Can't install {}.{} on {}, the object already defines {}, override it with override_interface_field",
+                        extension.interface, extension.field, object_name, extension.field
+                    ));
+                    continue;
+                }
+                let field = self
+                    .interface_cast(&object_name, extension.interface)
+                    .and_then(|cast| (extension.object_field)(cast.as_ref()));
+                match (field, self.types.remove(&object_name)) {
+                    (Some(field), Some(object)) => {
+                        self.types.insert(object_name, object.field(field));
+                    }
+                    (_, object) => {
+                        errors.push(format!(
+                            "Can't install {}.{} on {}, the object is not registered as {} trait object",
+                            extension.interface, extension.field, object_name, extension.interface
+                        ));
+                        if let Some(object) = object {
+                            self.types.insert(object_name, object);
+                        }
+                    }
+                }
+            }
+        }
+        if !errors.is_empty() {
+            return Err(format!("Invalid interface field: {:?}", errors.join(", ")));
+        }
+        Ok(())
     }
 
-    pub fn build_schema(mut self, schema_builder: SchemaBuilder) -> SchemaBuilder {
-        self.apply_pending();
+    fn apply_open_unions(&mut self) -> Result<(), String> {
+        let mut errors = Vec::new();
+        for (union, object) in std::mem::take(&mut self.union_joins) {
+            if !self.open_unions.contains(&union) {
+                errors.push(format!(
+                    "Can't find open union {} joined by {}",
+                    union, object
+                ));
+                continue;
+            }
+            let members = self.union_members.entry(union).or_default();
+            if !members.contains(&object) {
+                members.push(object);
+            }
+        }
+        if !errors.is_empty() {
+            return Err(format!("Invalid union: {:?}", errors.join(", ")));
+        }
+        for name in &self.open_unions {
+            let mut members = self.union_members[name].clone();
+            // members join in inventory order, keep the schema stable
+            members.sort();
+            let union = members
+                .iter()
+                .fold(dynamic::Union::new(name), |union, member| {
+                    union.possible_type(member)
+                });
+            self.unions.insert(name.clone(), union);
+        }
+        Ok(())
+    }
+
+    fn check_union_members(&self) -> Result<(), String> {
+        let mut errors = Vec::new();
+        let mut unions = self.union_members.iter().collect::<Vec<_>>();
+        unions.sort_by_key(|(name, _)| name.as_str());
+        for (name, members) in unions {
+            for member in members.iter() {
+                if let Some(feature) = self.disabled_by(member) {
+                    errors.push(format!(
+                        "member {} of union {} is disabled by feature {}",
+                        member, name, feature
+                    ));
+                } else if !self.types.contains_key(member) {
+                    errors.push(format!(
+                        "member {} of union {} is not a registered object",
+                        member, name
+                    ));
+                }
+            }
+        }
+        if !errors.is_empty() {
+            return Err(format!("Invalid union: {:?}", errors.join(", ")));
+        }
+        Ok(())
+    }
+
+    fn check_type_references(&self) -> Result<(), String> {
+        let errors = self
+            .type_references
+            .iter()
+            .filter_map(|(element, type_name)| {
+                let feature = self.disabled_by(type_name)?;
+                Some(format!(
+                    "Type \"{}\" is disabled by feature {} but still referenced by {}",
+                    type_name, feature, element
+                ))
+            })
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(errors.join(", "));
+        }
+        Ok(())
+    }
+
+    fn check_argument_validators(&self) -> Result<(), String> {
+        let errors = self
+            .argument_validators
+            .iter()
+            .flat_map(|(field, arguments, validators)| {
+                validators
+                    .iter()
+                    .flat_map(move |validator| validator.check(field, arguments))
+            })
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(format!("Invalid validator: {:?}", errors.join(", ")));
+        }
+        Ok(())
+    }
+
+    fn check_object_types(&self) -> Result<(), String> {
+        let errors = self
+            .expected_object_types
+            .iter()
+            .filter(|(type_id, _, _)| !self.object_type_names.contains_key(type_id))
+            .map(|(_, rust_type, abstract_type)| {
+                format!(
+                    "rust type {} returned by {} is not a registered object",
+                    rust_type, abstract_type
+                )
+            })
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(format!("Invalid abstract type: {:?}", errors.join(", ")));
+        }
+        Ok(())
+    }
+
+    fn check_oneof_input_objects(&self) -> Result<(), String> {
+        let errors = self
+            .oneof_input_types
+            .values()
+            .flat_map(|object| object.check())
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(format!(
+                "Invalid oneof input object: {:?}",
+                errors.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_composed_input_objects(&self) -> Result<(), String> {
+        let errors = self
+            .composed_input_types
+            .values()
+            .flat_map(|object| object.check())
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(format!(
+                "Conflicting input object fields: {:?}",
+                errors.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// panics when the registry is invalid, see `try_build_schema`
+    pub fn build_schema(self, schema_builder: SchemaBuilder) -> SchemaBuilder {
+        self.try_build_schema(schema_builder)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// build and finish the schema, errors of the registry are told apart from the errors
+    /// of async-graphql
+    pub fn finish_schema(
+        self,
+        schema_builder: SchemaBuilder,
+    ) -> Result<dynamic::Schema, SchemaBuildError> {
+        self.try_build_schema(schema_builder)
+            .map_err(SchemaBuildError::Registry)?
+            .finish()
+            .map_err(|err| SchemaBuildError::Schema(err.0))
+    }
+    pub fn try_build_schema(
+        mut self,
+        schema_builder: SchemaBuilder,
+    ) -> Result<SchemaBuilder, String> {
+        if !self.replay_errors.is_empty() {
+            return Err(self.replay_errors.join(", "));
+        }
+        self.apply_pending()?;
+        self.apply_interface_hierarchy()?;
+        self.apply_interface_fields()?;
+        self.apply_open_unions()?;
+        self.check_union_members()?;
+        self.check_object_types()?;
+        self.check_oneof_input_objects()?;
+        self.check_composed_input_objects()?;
+        self.check_type_references()?;
+        self.check_argument_validators()?;
+        if self.stream_concurrency == Some(0) {
+            return Err(
+                "Invalid stream concurrency: at least one item must run at once".to_string(),
+            );
+        }
+        let schema_builder = schema_builder.data(ObjectTypeNames::new(self.object_type_names));
+        let schema_builder = match self.root_value_check {
+            Some(root) => schema_builder.extension(RootValueCheckExtension(root)),
+            None => schema_builder,
+        };
+        let schema_builder = schema_builder.extension(ListFieldExtension);
+        let schema_builder =
+            schema_builder.extension(IncrementalExtension(self.error_hook.clone()));
+        let schema_builder = match self.error_hook {
+            Some(hook) => schema_builder.extension(ErrorHookExtension(hook)),
+            None => schema_builder,
+        };
+        let schema_builder = match self.visibility.is_empty() {
+            true => schema_builder,
+            false => schema_builder.extension(VisibilityExtension(Arc::new(self.visibility))),
+        };
+        let schema_builder = match self.lenient_list_items {
+            true => schema_builder.data(LenientListItems),
+            false => schema_builder,
+        };
+        let schema_builder = match self.stream_concurrency {
+            Some(concurrency) => schema_builder.data(StreamConcurrency(concurrency)),
+            None => schema_builder,
+        };
         let schema_builder = self
             .enums
             .into_iter()
             .fold(schema_builder, |schema_builder, (_, enum_)| {
                 schema_builder.register(enum_)
             });
+        let schema_builder = self
+            .scalars
+            .into_iter()
+            .fold(schema_builder, |schema_builder, (_, scalar)| {
+                schema_builder.register(scalar)
+            });
         let schema_builder = self
             .unions
             .into_iter()
@@ -197,16 +1297,35 @@ impl Registry {
             .fold(schema_builder, |schema_builder, (_, object)| {
                 schema_builder.register(object)
             });
+        let schema_builder = self
+            .oneof_input_types
+            .into_iter()
+            .fold(schema_builder, |schema_builder, (_, object)| {
+                schema_builder.register(object.into_input_object())
+            });
+        let schema_builder = self
+            .composed_input_types
+            .into_iter()
+            .fold(schema_builder, |schema_builder, (_, object)| {
+                schema_builder.register(object.into_input_object())
+            });
         let schema_builder = self
             .types
             .into_iter()
             .fold(schema_builder, |schema_builder, (_, object)| {
                 schema_builder.register(object)
             });
-        self.extend_types
+        let schema_builder = self
+            .subscriptions
+            .into_iter()
+            .fold(schema_builder, |schema_builder, (_, subscription)| {
+                schema_builder.register(subscription)
+            });
+        Ok(self
+            .extend_types
             .into_iter()
             .fold(schema_builder, |schema_builder, object| {
                 schema_builder.register(object)
-            })
+            }))
     }
 }
@@ -0,0 +1,144 @@
+use crate::schema::output_types::utils::{OutputTypeRef, ResolveOwned};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Object, Register, Registry};
+use crate::schema::scalars::utils::BigInt;
+use async_graphql::dynamic;
+
+// user
+
+// mark as object
+struct Query {
+    // mark as skip
+    bytes: u64,
+}
+
+impl Query {
+    async fn resolve_bytes(&self) -> BigInt<u64> {
+        BigInt(self.bytes)
+    }
+    async fn resolve_next(&self, value: BigInt) -> Option<BigInt> {
+        value.0.checked_add(1).map(BigInt)
+    }
+}
+
+// generated
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define bytes field
+        let bytes_field =
+            dynamic::Field::new("bytes", <BigInt<u64> as OutputTypeRef>::type_ref(), |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_bytes().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            });
+        let object_type = object_type.field(bytes_field);
+
+        // define next field
+        let next_field = dynamic::Field::new(
+            "next",
+            <Option<BigInt> as OutputTypeRef>::type_ref(),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("value")?.deserialize()?;
+                    let value = parent.resolve_next(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let next_field = next_field.argument(dynamic::InputValue::new(
+            "value",
+            <BigInt as OutputTypeRef>::type_ref(),
+        ));
+        let object_type = object_type.field(next_field);
+
+        registry
+            .register_object(object_type)
+            .register_scalar(BigInt::<i64>::scalar())
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new().register::<Query>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                """
+                integer serialized as a string, e.g. "9007199254740993"
+                """
+                scalar BigInt
+                type Query {
+                  bytes: BigInt!
+                  next(value: BigInt!): BigInt
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    async fn execute(query: &str) -> serde_json::Value {
+        let schema = create_schema();
+        let req = async_graphql::Request::new(query)
+            .root_value(dynamic::FieldValue::owned_any(Query { bytes: u64::MAX }));
+        let res = schema.execute(req).await;
+        serde_json::to_value(res).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_output() {
+        let res = execute("{ bytes }").await;
+        assert_eq!(
+            res,
+            serde_json::json!({ "data": { "bytes": "18446744073709551615" } }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_arguments() {
+        let res = execute(r#"{ a: next(value: "9007199254740992") b: next(value: -5) }"#).await;
+        assert_eq!(
+            res,
+            serde_json::json!({ "data": { "a": "9007199254740993", "b": "-4" } }),
+        );
+        let res = execute(r#"{ next(value: "9223372036854775807") }"#).await;
+        assert_eq!(res, serde_json::json!({ "data": { "next": null } }));
+
+        let res = execute(r#"{ next(value: "9223372036854775808") }"#).await;
+        assert_eq!(
+            res["errors"][0]["message"],
+            "internal: 9223372036854775808 is out of range of rust type i64",
+        );
+        let res = execute(r#"{ next(value: "12.5") }"#).await;
+        assert_eq!(
+            res["errors"][0]["message"],
+            r#"Invalid value for argument "value", expected type "BigInt""#,
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use crate::schema::output_types::utils::{OutputTypeRef, ResolveOwned};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Object, Register, Registry};
+use crate::schema::scalars::utils::{int_arg, opt_int_arg};
+use async_graphql::dynamic;
+
+// user
+
+// mark as object
+struct Query {
+    // mark as skip
+    bytes: u64,
+}
+
+impl Query {
+    async fn resolve_bytes(&self) -> u64 {
+        self.bytes
+    }
+    async fn resolve_range(&self, limit: usize, start: Option<i64>) -> Vec<i64> {
+        let start = start.unwrap_or_default();
+        (start..).take(limit).collect()
+    }
+}
+
+// generated
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define bytes field
+        let bytes_field = dynamic::Field::new("bytes", <u64 as OutputTypeRef>::type_ref(), |ctx| {
+            dynamic::FieldFuture::new(async move {
+                let parent = parent_value::<Self>(&ctx)?;
+                let value = parent.resolve_bytes().await;
+                ResolveOwned::resolve_owned(value, &ctx)
+            })
+        });
+        let object_type = object_type.field(bytes_field);
+
+        // define range field
+        let range_field =
+            dynamic::Field::new("range", <Vec<i64> as OutputTypeRef>::type_ref(), |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = int_arg(&ctx, "limit")?;
+                    let arg_1 = opt_int_arg(&ctx, "start")?;
+                    let value = parent.resolve_range(arg_0, arg_1).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            });
+        let range_field = range_field
+            .argument(dynamic::InputValue::new(
+                "limit",
+                <usize as OutputTypeRef>::type_ref(),
+            ))
+            .argument(dynamic::InputValue::new(
+                "start",
+                <Option<i64> as OutputTypeRef>::type_ref(),
+            ));
+        let object_type = object_type.field(range_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new().register::<Query>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                type Query {
+                  bytes: Int!
+                  range(limit: Int!, start: Int): [Int!]!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    async fn execute(bytes: u64, query: &str) -> serde_json::Value {
+        let schema = create_schema();
+        let req = async_graphql::Request::new(query)
+            .root_value(dynamic::FieldValue::owned_any(Query { bytes }));
+        let res = schema.execute(req).await;
+        serde_json::to_value(res).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_output() {
+        let res = execute(1024, "{ bytes }").await;
+        assert_eq!(res, serde_json::json!({ "data": { "bytes": 1024 } }));
+
+        let res = execute(5_000_000_000, "{ bytes }").await;
+        assert_eq!(res["data"], serde_json::json!(null));
+        assert_eq!(
+            res["errors"][0]["message"],
+            "internal: 5000000000 of rust type u64 is out of range of Int, use BigInt",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_arguments() {
+        let res = execute(0, "{ range(limit: 3, start: -1) }").await;
+        assert_eq!(res, serde_json::json!({ "data": { "range": [-1, 0, 1] } }));
+        let res = execute(0, "{ range(limit: 2, start: null) }").await;
+        assert_eq!(res, serde_json::json!({ "data": { "range": [0, 1] } }));
+
+        let res = execute(0, "{ range(limit: 1, start: 5000000000) }").await;
+        assert_eq!(
+            res["errors"][0]["message"],
+            "argument start is out of range of Int: 5000000000",
+        );
+        let res = execute(0, "{ range(limit: -1) }").await;
+        assert_eq!(
+            res["errors"][0]["message"],
+            "argument limit is out of range of rust type usize: -1",
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod big_int;
+pub mod int;
+pub mod utils;
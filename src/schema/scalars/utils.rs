@@ -0,0 +1,128 @@
+use crate::schema::output_types::utils::{OutputTypeRef, ResolveOwned, ResolveRef, ResolveShared};
+use async_graphql::dynamic::{FieldValue, ResolverContext};
+use async_graphql::{dynamic, Context, Value};
+use serde::de::{Error, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::fmt::{self, Display};
+use std::marker::PhantomData;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// `Int` argument of a wider rust integer, checked against the 32-bit range of `Int` because
+/// the engine accepts any integer for it
+pub fn int_arg<T: TryFrom<i64>>(ctx: &ResolverContext, name: &str) -> async_graphql::Result<T> {
+    let value = ctx.args.try_get(name)?.i64()?;
+    if i32::try_from(value).is_err() {
+        return Err(format!("argument {} is out of range of Int: {}", name, value).into());
+    }
+    T::try_from(value).map_err(|_| {
+        format!(
+            "argument {} is out of range of rust type {}: {}",
+            name,
+            std::any::type_name::<T>(),
+            value
+        )
+        .into()
+    })
+}
+
+/// nullable `Int` argument, see `int_arg`
+pub fn opt_int_arg<T: TryFrom<i64>>(
+    ctx: &ResolverContext,
+    name: &str,
+) -> async_graphql::Result<Option<T>> {
+    match ctx.args.get(name) {
+        Some(value) if !value.is_null() => int_arg(ctx, name).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// opt-in scalar for integers beyond the range of `Int`, serialized as a string. Arguments
+/// accept a string or an integer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct BigInt<T = i64>(pub T);
+
+impl<T> BigInt<T> {
+    pub const NAME: &'static str = "BigInt";
+
+    pub fn scalar() -> dynamic::Scalar {
+        dynamic::Scalar::new(Self::NAME)
+            .description("integer serialized as a string, e.g. \"9007199254740993\"")
+            .validator(|value| match value {
+                Value::String(value) => {
+                    let digits = value.strip_prefix('-').unwrap_or(value);
+                    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+                }
+                Value::Number(value) => value.is_i64() || value.is_u64(),
+                _ => false,
+            })
+    }
+}
+
+impl<'a, T: Display> ResolveOwned<'a> for BigInt<T> {
+    #[inline]
+    fn resolve_owned(self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::value(self.0.to_string())))
+    }
+}
+
+impl<'a, T: Display> ResolveRef<'a> for BigInt<T> {
+    fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::value(self.0.to_string())))
+    }
+}
+
+impl<T: Display + Send + Sync + 'static> ResolveShared for BigInt<T> {
+    fn resolve_shared<'a>(
+        value: Arc<Self>,
+        _ctx: &Context,
+    ) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::value(value.0.to_string())))
+    }
+}
+
+impl<T> OutputTypeRef for BigInt<T> {
+    fn type_name() -> &'static str {
+        Self::NAME
+    }
+}
+
+impl<'de, T: FromStr> Deserialize<'de> for BigInt<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BigIntVisitor<T>(PhantomData<T>);
+
+        impl<T: FromStr> BigIntVisitor<T> {
+            fn parse<E: Error>(value: &str) -> Result<BigInt<T>, E> {
+                value.parse().map(BigInt).map_err(|_| {
+                    E::custom(format!(
+                        "{} is out of range of rust type {}",
+                        value,
+                        std::any::type_name::<T>()
+                    ))
+                })
+            }
+        }
+
+        impl<'de, T: FromStr> Visitor<'de> for BigIntVisitor<T> {
+            type Value = BigInt<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an integer or a string of an integer")
+            }
+
+            fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+                Self::parse(value)
+            }
+
+            fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+                Self::parse(&value.to_string())
+            }
+
+            fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+                Self::parse(&value.to_string())
+            }
+        }
+
+        deserializer.deserialize_any(BigIntVisitor(PhantomData))
+    }
+}
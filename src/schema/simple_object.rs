@@ -1,5 +1,6 @@
 mod static_schema;
 
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{Object, Register, Registry};
 use async_graphql::dynamic;
 use async_graphql::dynamic::DynamicRequestExt;
@@ -49,7 +50,7 @@ impl Register for Query {
         let user_field =
             dynamic::Field::new("user", dynamic::TypeRef::named_nn(User::NAME), |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     // use borrowed_any because Image is not value
                     Ok(Some(FieldValue::borrowed_any(parent.resolve_user())))
                 })
@@ -82,7 +83,7 @@ impl Register for User {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     Ok(Some(FieldValue::value(parent.resolve_id().to_owned())))
                 })
             },
@@ -95,7 +96,7 @@ impl Register for User {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     // use value because it's a String
                     Ok(Some(FieldValue::value(parent.resolve_name().to_owned())))
                 })
@@ -107,7 +108,7 @@ impl Register for User {
         let avatar_field =
             dynamic::Field::new("avatar", dynamic::TypeRef::named(Image::NAME), |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     // use map because avatar is optional
                     // use borrowed_any because Image is not value
                     Ok(parent
@@ -150,7 +151,7 @@ impl Register for Image {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 dynamic::FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     Ok(Some(FieldValue::value(parent.resolve_url().to_owned())))
                 })
             },
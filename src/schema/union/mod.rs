@@ -1 +1,3 @@
+mod open;
 mod simple;
+pub mod utils;
@@ -0,0 +1,295 @@
+use crate::schema::output_types::utils::ResolveOwned;
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Object, Register, Registry, Union};
+use async_graphql::dynamic;
+use search::{SearchResult, SearchResultUnion};
+
+/// module defining the union, it does not know the members
+mod search {
+    use crate::schema::interface::utils::{resolve_dyn_owned, resolve_dyn_ref, InterfaceObject};
+    use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+    use crate::schema::registry::{Register, Registry, Union};
+    use crate::schema::union::utils::register_union_members;
+    use async_graphql::{dynamic, Context};
+
+    // user
+
+    // mark as open union
+    pub trait SearchResult: InterfaceObject {}
+
+    // generated
+
+    pub struct SearchResultUnion;
+
+    impl Union for SearchResultUnion {
+        const NAME: &'static str = "SearchResult";
+    }
+
+    impl Register for SearchResultUnion {
+        fn register(registry: Registry) -> Registry {
+            let registry = registry.register_open_union(Self::NAME);
+            register_union_members(registry, Self::NAME)
+        }
+    }
+
+    impl<'a> ResolveOwned<'a> for Box<dyn SearchResult> {
+        fn resolve_owned(
+            self,
+            _ctx: &Context,
+        ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+            Ok(Some(resolve_dyn_owned(self)))
+        }
+    }
+
+    impl<'a> ResolveRef<'a> for Box<dyn SearchResult> {
+        fn resolve_ref(
+            &'a self,
+            _ctx: &Context,
+        ) -> async_graphql::Result<Option<dynamic::FieldValue<'a>>> {
+            Ok(Some(resolve_dyn_ref(self.as_ref())))
+        }
+    }
+}
+
+/// member module, joins the union without changing the search module
+mod user {
+    use super::search::{SearchResult, SearchResultUnion};
+    use crate::schema::output_types::utils::ResolveOwned;
+    use crate::schema::parent::parent_value;
+    use crate::schema::registry::{Object, Register, Registry, Union};
+    use crate::schema::union::utils::UnionMember;
+    use async_graphql::dynamic;
+
+    // user
+
+    // mark as object, member of SearchResult
+    pub struct User {
+        pub name: String,
+    }
+
+    impl User {
+        async fn resolve_name(&self) -> &str {
+            &self.name
+        }
+    }
+
+    // generated
+
+    impl Object for User {
+        const NAME: &'static str = "User";
+    }
+
+    impl SearchResult for User {}
+
+    inventory::submit!(UnionMember::new::<User>(SearchResultUnion::NAME));
+
+    impl Register for User {
+        fn register(registry: Registry) -> Registry {
+            let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+            // define name field
+            let name_field = dynamic::Field::new(
+                "name",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                |ctx| {
+                    dynamic::FieldFuture::new(async move {
+                        let parent = parent_value::<Self>(&ctx)?;
+                        let value = parent.resolve_name().await;
+                        ResolveOwned::resolve_owned(value, &ctx)
+                    })
+                },
+            );
+            let object_type = object_type.field(name_field);
+
+            registry.register_object(object_type)
+        }
+    }
+}
+
+/// member module, joins the union without changing the search module
+mod post {
+    use super::search::{SearchResult, SearchResultUnion};
+    use crate::schema::output_types::utils::ResolveOwned;
+    use crate::schema::parent::parent_value;
+    use crate::schema::registry::{Object, Register, Registry, Union};
+    use crate::schema::union::utils::UnionMember;
+    use async_graphql::dynamic;
+
+    // user
+
+    // mark as object, member of SearchResult
+    pub struct Post {
+        pub title: String,
+    }
+
+    impl Post {
+        async fn resolve_title(&self) -> &str {
+            &self.title
+        }
+    }
+
+    // generated
+
+    impl Object for Post {
+        const NAME: &'static str = "Post";
+    }
+
+    impl SearchResult for Post {}
+
+    inventory::submit!(UnionMember::new::<Post>(SearchResultUnion::NAME));
+
+    impl Register for Post {
+        fn register(registry: Registry) -> Registry {
+            let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+            // define title field
+            let title_field = dynamic::Field::new(
+                "title",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                |ctx| {
+                    dynamic::FieldFuture::new(async move {
+                        let parent = parent_value::<Self>(&ctx)?;
+                        let value = parent.resolve_title().await;
+                        ResolveOwned::resolve_owned(value, &ctx)
+                    })
+                },
+            );
+            let object_type = object_type.field(title_field);
+
+            registry.register_object(object_type)
+        }
+    }
+}
+
+// user
+
+// mark as object
+struct Query;
+
+impl Query {
+    async fn resolve_search(&self, text: String) -> Vec<Box<dyn SearchResult>> {
+        vec![
+            Box::new(user::User {
+                name: format!("{} user", text),
+            }),
+            Box::new(post::Post {
+                title: format!("{} post", text),
+            }),
+        ]
+    }
+}
+
+// generated
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define search field
+        let search_field = dynamic::Field::new(
+            "search",
+            dynamic::TypeRef::named_nn_list_nn(<SearchResultUnion as Union>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("text")?.deserialize()?;
+                    let value = parent.resolve_search(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let search_field = search_field.argument(dynamic::InputValue::new(
+            "text",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        ));
+        let object_type = object_type.field(search_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    // members are collected by the union, they are not registered here
+    let registry = Registry::new()
+        .register::<Query>()
+        .register::<SearchResultUnion>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                type Post {
+                  title: String!
+                }
+                type Query {
+                  search(text: String!): [SearchResult!]!
+                }
+                union SearchResult = Post | User
+                type User {
+                  name: String!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                search(text: "rust") {
+                    __typename
+                    ... on User { name }
+                    ... on Post { title }
+                }
+            }
+        "#;
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "search": [
+                    { "__typename": "User", "name": "rust user" },
+                    { "__typename": "Post", "title": "rust post" },
+                ]
+            }),
+        );
+    }
+
+    #[test]
+    fn test_unknown_union() {
+        let registry = Registry::new()
+            .register::<Query>()
+            .register::<SearchResultUnion>()
+            .join_union("Missing", "User");
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        assert_eq!(
+            registry.try_build_schema(schema).err().unwrap(),
+            "Invalid union: \"Can't find open union Missing joined by User\"",
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
 use crate::schema::registry::{Object, Register, Registry, Union};
+use crate::schema::union::utils::UnionDefinition;
 use async_graphql::dynamic::{FieldFuture, FieldValue, Schema};
 use async_graphql::{dynamic, Context};
 
@@ -11,6 +13,7 @@ struct Bar {
     bar: String,
 }
 
+// mark as union
 enum FooBar {
     Foo(Foo),
     Bar(Bar),
@@ -18,6 +21,7 @@ enum FooBar {
 
 struct Query {
     foobar: FooBar,
+    foobars: Vec<FooBar>,
 }
 
 impl Query {
@@ -26,6 +30,13 @@ impl Query {
             bar: "bar".to_string(),
         })
     }
+    async fn resolve_maybe_foobar(&self, _ctx: &Context<'_>, bar: bool) -> Option<FooBar> {
+        bar.then(|| {
+            FooBar::Bar(Bar {
+                bar: "maybe bar".to_string(),
+            })
+        })
+    }
 }
 
 // generated
@@ -70,29 +81,33 @@ impl<'a> ResolveOwned<'a> for Bar {
     }
 }
 
-impl<'a> ResolveRef<'a> for FooBar {
-    fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+impl UnionDefinition for FooBar {
+    const MEMBERS: &'static [&'static str] = &[<Foo as Object>::NAME, <Bar as Object>::NAME];
+
+    fn member(&self) -> (&'static str, &(dyn std::any::Any + Send + Sync)) {
         match self {
-            FooBar::Foo(v) => Ok(Some(
-                FieldValue::borrowed_any(v).with_type(<Foo as Object>::NAME),
-            )),
-            FooBar::Bar(v) => Ok(Some(
-                FieldValue::borrowed_any(v).with_type(<Bar as Object>::NAME),
-            )),
+            FooBar::Foo(v) => (<Foo as Object>::NAME, v),
+            FooBar::Bar(v) => (<Bar as Object>::NAME, v),
+        }
+    }
+
+    fn into_member(self) -> (&'static str, Box<dyn std::any::Any + Send + Sync>) {
+        match self {
+            FooBar::Foo(v) => (<Foo as Object>::NAME, Box::new(v)),
+            FooBar::Bar(v) => (<Bar as Object>::NAME, Box::new(v)),
         }
     }
 }
 
+impl<'a> ResolveRef<'a> for FooBar {
+    fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(self.to_field_value()))
+    }
+}
+
 impl<'a> ResolveOwned<'a> for FooBar {
     fn resolve_owned(self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
-        match self {
-            FooBar::Foo(v) => Ok(Some(
-                FieldValue::owned_any(v).with_type(<Foo as Object>::NAME),
-            )),
-            FooBar::Bar(v) => Ok(Some(
-                FieldValue::owned_any(v).with_type(<Bar as Object>::NAME),
-            )),
-        }
+        Ok(Some(self.into_field_value()))
     }
 }
 
@@ -113,6 +128,9 @@ impl Query {
     async fn resolve_foobar(&self, _ctx: &Context<'_>) -> &FooBar {
         &self.foobar
     }
+    async fn resolve_foobars(&self, _ctx: &Context<'_>) -> &Vec<FooBar> {
+        &self.foobars
+    }
 }
 
 // register types
@@ -127,7 +145,7 @@ impl Register for Foo {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_foo(&ctx).await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -149,7 +167,7 @@ impl Register for Bar {
             dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
             |ctx| {
                 FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_bar(&ctx).await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -163,10 +181,7 @@ impl Register for Bar {
 
 impl Register for FooBar {
     fn register(registry: Registry) -> Registry {
-        let union_type = dynamic::Union::new(<FooBar as Union>::NAME);
-        let union_type = union_type.possible_type(<Foo as Object>::NAME);
-        let union_type = union_type.possible_type(<Bar as Object>::NAME);
-        registry.register_union(union_type)
+        registry.register_union_type::<Self>()
     }
 }
 
@@ -180,7 +195,7 @@ impl Register for Query {
             dynamic::TypeRef::named_nn(<FooBar as Union>::NAME),
             |ctx| {
                 FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_foobar(&ctx).await;
                     ResolveRef::resolve_ref(value, &ctx)
                 })
@@ -194,7 +209,7 @@ impl Register for Query {
             dynamic::TypeRef::named_nn(<FooBar as Union>::NAME),
             |ctx| {
                 FieldFuture::new(async move {
-                    let parent = ctx.parent_value.try_downcast_ref::<Self>()?;
+                    let parent = parent_value::<Self>(&ctx)?;
                     let value = parent.resolve_new_foobar(&ctx).await;
                     ResolveOwned::resolve_owned(value, &ctx)
                 })
@@ -203,6 +218,39 @@ impl Register for Query {
 
         let object_type = object_type.field(new_foobar_field);
 
+        // maybe_foobar field
+        let maybe_foobar_field = dynamic::Field::new(
+            "maybe_foobar",
+            dynamic::TypeRef::named(<FooBar as Union>::NAME),
+            |ctx| {
+                FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("bar")?.deserialize()?;
+                    let value = parent.resolve_maybe_foobar(&ctx, arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let maybe_foobar_field = maybe_foobar_field.argument(dynamic::InputValue::new(
+            "bar",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::BOOLEAN),
+        ));
+        let object_type = object_type.field(maybe_foobar_field);
+
+        // foobars field
+        let foobars_field = dynamic::Field::new(
+            "foobars",
+            dynamic::TypeRef::named_nn_list_nn(<FooBar as Union>::NAME),
+            |ctx| {
+                FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_foobars(&ctx).await;
+                    ResolveRef::resolve_ref(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(foobars_field);
+
         registry.register_object(object_type)
     }
 }
@@ -244,6 +292,8 @@ mod tests {
             type Query {
               foobar: FooBar!
               new_foobar: FooBar!
+              maybe_foobar(bar: Boolean!): FooBar
+              foobars: [FooBar!]!
             }
             schema {
               query: Query
@@ -259,6 +309,14 @@ mod tests {
             foobar: FooBar::Foo(Foo {
                 foo: "foo".to_string(),
             }),
+            foobars: vec![
+                FooBar::Foo(Foo {
+                    foo: "foo 1".to_string(),
+                }),
+                FooBar::Bar(Bar {
+                    bar: "bar 2".to_string(),
+                }),
+            ],
         }
     }
     #[tokio::test]
@@ -320,4 +378,53 @@ mod tests {
             }),
         );
     }
+
+    #[tokio::test]
+    async fn test_query_option_and_list() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                some: maybe_foobar(bar: true) {
+                    ... on Bar { bar }
+                }
+                none: maybe_foobar(bar: false) {
+                    ... on Bar { bar }
+                }
+                foobars {
+                    __typename
+                    ... on Foo { foo }
+                    ... on Bar { bar }
+                }
+            }
+        "#;
+
+        let req =
+            async_graphql::Request::new(query).root_value(FieldValue::owned_any(create_root()));
+        let res = schema.execute(req).await;
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "some": { "bar": "maybe bar" },
+                "none": null,
+                "foobars": [
+                    { "__typename": "Foo", "foo": "foo 1" },
+                    { "__typename": "Bar", "bar": "bar 2" },
+                ],
+            }),
+        );
+    }
+
+    #[test]
+    fn test_unregistered_member() {
+        let registry = Registry::new()
+            .register::<Query>()
+            .register::<Foo>()
+            .register::<FooBar>();
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        assert_eq!(
+            registry.try_build_schema(schema).err().unwrap(),
+            "Invalid union: \"member Bar of union FooBar is not a registered object\"",
+        );
+    }
 }
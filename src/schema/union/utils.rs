@@ -0,0 +1,63 @@
+use crate::schema::registry::{Object, Register, Registry, Union};
+use async_graphql::dynamic::{self, FieldValue};
+use std::any::Any;
+
+/// union derived from a rust enum, every variant holds one member object
+pub trait UnionDefinition: Union + Sized + 'static {
+    /// object names of the variants, in declaration order
+    const MEMBERS: &'static [&'static str];
+
+    fn create_union() -> dynamic::Union {
+        Self::MEMBERS.iter().fold(
+            dynamic::Union::new(<Self as Union>::NAME),
+            |union, member| union.possible_type(*member),
+        )
+    }
+
+    /// object name and payload of the variant
+    fn member(&self) -> (&'static str, &(dyn Any + Send + Sync));
+
+    fn into_member(self) -> (&'static str, Box<dyn Any + Send + Sync>);
+
+    fn to_field_value(&self) -> FieldValue<'_> {
+        let (name, value) = self.member();
+        FieldValue::borrowed_any(value).with_type(name)
+    }
+
+    fn into_field_value<'a>(self) -> FieldValue<'a> {
+        let (name, value) = self.into_member();
+        FieldValue::boxed_any(value).with_type(name)
+    }
+}
+
+/// object joining an open union, submitted with `inventory::submit!` by the member module
+pub struct UnionMember {
+    union: &'static str,
+    object: &'static str,
+    register: fn(Registry) -> Registry,
+}
+
+impl UnionMember {
+    pub const fn new<T: Object + Register>(union: &'static str) -> Self {
+        Self {
+            union,
+            object: T::NAME,
+            register: T::register,
+        }
+    }
+}
+
+inventory::collect!(UnionMember);
+
+/// register the open union members submitted through inventory, the member objects are
+/// registered as well
+pub fn register_union_members(registry: Registry, union: &str) -> Registry {
+    let mut members = inventory::iter::<UnionMember>
+        .into_iter()
+        .filter(|member| member.union == union)
+        .collect::<Vec<_>>();
+    members.sort_by_key(|member| member.object);
+    members.into_iter().fold(registry, |registry, member| {
+        (member.register)(registry).join_union(member.union, member.object)
+    })
+}
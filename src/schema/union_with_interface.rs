@@ -1,3 +1,4 @@
+use crate::schema::parent::parent_value;
 use async_graphql::{dynamic::*, Value};
 // struct
 struct Dog;
@@ -14,6 +15,10 @@ struct Query {
     pet: Animal,
 }
 
+impl crate::schema::registry::Object for Query {
+    const NAME: &'static str = "Query";
+}
+
 impl Animal {
     fn to_field_value(&self) -> FieldValue {
         match self {
@@ -74,13 +79,13 @@ pub fn create_schema() -> Schema {
     let query = Object::new("Query");
     let query = query.field(Field::new("pet", TypeRef::named_nn("Animal"), |ctx| {
         FieldFuture::new(async move {
-            let query = ctx.parent_value.try_downcast_ref::<Query>()?;
+            let query = parent_value::<Query>(&ctx)?;
             Ok(Some(query.pet.to_field_value()))
         })
     }));
     let query = query.field(Field::new("dog", TypeRef::named("Dog"), |ctx| {
         FieldFuture::new(async move {
-            let query = ctx.parent_value.try_downcast_ref::<Query>()?;
+            let query = parent_value::<Query>(&ctx)?;
             let dog = match &query.pet {
                 Animal::Dog(dog) => dog,
                 _ => return Ok(None),
@@ -0,0 +1,286 @@
+use crate::schema::output_types::utils::ResolveOwned;
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Object, Register, Registry};
+use crate::schema::validator::utils::{
+    with_validators, Custom, Email, InputValueValidator, MaxItems, MaxLength, Maximum, MinItems,
+    MinLength, Minimum, Pattern, Url, ValidationError,
+};
+use async_graphql::{dynamic, Value};
+
+// user
+// mark as object
+struct Query;
+
+fn not_reserved(value: &Value) -> Result<(), ValidationError> {
+    match value {
+        Value::String(name) if name == "admin" => {
+            Err(ValidationError::new("RESERVED", "is reserved"))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl Query {
+    #[allow(clippy::too_many_arguments)]
+    async fn resolve_sign_up(
+        &self,
+        // mark as validator(min_length = 2, max_length = 20)
+        name: String,
+        // mark as validator(minimum = 0, maximum = 150)
+        age: i32,
+        // mark as validator(email)
+        email: String,
+        // mark as validator(url)
+        website: Option<String>,
+        // mark as validator(min_items = 1, max_items = 3, items(pattern = "^[a-z]+$"))
+        tags: Vec<String>,
+        // mark as validator(custom = "not_reserved")
+        username: String,
+    ) -> String {
+        format!(
+            "{} ({}) {} {} [{}] @{}",
+            name,
+            age,
+            email,
+            website.unwrap_or_default(),
+            tags.join(", "),
+            username
+        )
+    }
+}
+
+// generated
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define sign_up field
+        let sign_up_validators = vec![
+            InputValueValidator::new("name")
+                .validator(MinLength(2))
+                .validator(MaxLength(20)),
+            InputValueValidator::new("age")
+                .validator(Minimum(0.0))
+                .validator(Maximum(150.0)),
+            InputValueValidator::new("email").validator(Email::default()),
+            InputValueValidator::new("website").validator(Url::default()),
+            InputValueValidator::new("tags")
+                .validator(MinItems(1))
+                .validator(MaxItems(3))
+                .item_validator(Pattern::new("^[a-z]+$")),
+            InputValueValidator::new("username").validator(Custom(not_reserved)),
+        ];
+        let registry = registry.check_validators(
+            "Query.sign_up",
+            &["name", "age", "email", "website", "tags", "username"],
+            &sign_up_validators,
+        );
+        let sign_up_field = dynamic::Field::new(
+            "sign_up",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            with_validators(sign_up_validators, |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("name")?.deserialize()?;
+                    let arg_1 = ctx.args.try_get("age")?.deserialize()?;
+                    let arg_2 = ctx.args.try_get("email")?.deserialize()?;
+                    let arg_3 = match ctx.args.get("website") {
+                        Some(value) => value.deserialize()?,
+                        None => None,
+                    };
+                    let arg_4 = ctx.args.try_get("tags")?.deserialize()?;
+                    let arg_5 = ctx.args.try_get("username")?.deserialize()?;
+                    let value = parent
+                        .resolve_sign_up(arg_0, arg_1, arg_2, arg_3, arg_4, arg_5)
+                        .await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            }),
+        );
+        let sign_up_field = sign_up_field
+            .argument(dynamic::InputValue::new(
+                "name",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            ))
+            .argument(dynamic::InputValue::new(
+                "age",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::INT),
+            ))
+            .argument(dynamic::InputValue::new(
+                "email",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            ))
+            .argument(dynamic::InputValue::new(
+                "website",
+                dynamic::TypeRef::named(dynamic::TypeRef::STRING),
+            ))
+            .argument(dynamic::InputValue::new(
+                "tags",
+                dynamic::TypeRef::named_nn_list_nn(dynamic::TypeRef::STRING),
+            ))
+            .argument(dynamic::InputValue::new(
+                "username",
+                dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            ));
+        let object_type = object_type.field(sign_up_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new().register::<Query>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                type Query {
+                  sign_up(name: String!, age: Int!, email: String!, website: String, tags: [String!]!, username: String!): String!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    async fn sign_up(variables: serde_json::Value) -> async_graphql::Response {
+        let schema = create_schema();
+        let query = r#"
+            query($name: String!, $age: Int!, $email: String!, $website: String, $tags: [String!]!, $username: String!) {
+                sign_up(name: $name, age: $age, email: $email, website: $website, tags: $tags, username: $username)
+            }
+        "#;
+        let mut input = serde_json::json!({
+            "name": "John",
+            "age": 42,
+            "email": "john@example.com",
+            "website": "https://example.com",
+            "tags": ["rust", "graphql"],
+            "username": "john",
+        });
+        for (key, value) in variables.as_object().unwrap() {
+            input[key] = value.clone();
+        }
+        let req = async_graphql::Request::new(query)
+            .variables(async_graphql::Variables::from_json(input))
+            .root_value(dynamic::FieldValue::owned_any(Query));
+        schema.execute(req).await
+    }
+
+    #[tokio::test]
+    async fn test_valid() {
+        let res = sign_up(serde_json::json!({})).await;
+        assert_eq!(res.errors.len(), 0);
+        assert_eq!(
+            res.data.into_json().unwrap(),
+            serde_json::json!({
+                "sign_up": "John (42) john@example.com https://example.com [rust, graphql] @john"
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid() {
+        let cases = [
+            (
+                serde_json::json!({ "name": "J" }),
+                "MIN_LENGTH",
+                serde_json::json!(["name"]),
+            ),
+            (
+                serde_json::json!({ "age": 200 }),
+                "MAXIMUM",
+                serde_json::json!(["age"]),
+            ),
+            (
+                serde_json::json!({ "age": -1 }),
+                "MINIMUM",
+                serde_json::json!(["age"]),
+            ),
+            (
+                serde_json::json!({ "email": "john" }),
+                "EMAIL",
+                serde_json::json!(["email"]),
+            ),
+            (
+                serde_json::json!({ "website": "example" }),
+                "URL",
+                serde_json::json!(["website"]),
+            ),
+            (
+                serde_json::json!({ "tags": [] }),
+                "MIN_ITEMS",
+                serde_json::json!(["tags"]),
+            ),
+            (
+                serde_json::json!({ "tags": ["a", "b", "c", "d"] }),
+                "MAX_ITEMS",
+                serde_json::json!(["tags"]),
+            ),
+            (
+                serde_json::json!({ "tags": ["rust", "Graph QL"] }),
+                "PATTERN",
+                serde_json::json!(["tags", 1]),
+            ),
+            (
+                serde_json::json!({ "username": "admin" }),
+                "RESERVED",
+                serde_json::json!(["username"]),
+            ),
+        ];
+        for (variables, code, path) in cases {
+            let res = sign_up(variables).await;
+            assert_eq!(res.errors.len(), 1);
+            let extensions = serde_json::to_value(&res.errors[0].extensions).unwrap();
+            assert_eq!(extensions["code"], serde_json::json!(code));
+            assert_eq!(extensions["argumentPath"], path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_message() {
+        let res = sign_up(serde_json::json!({ "website": null, "name": "J" })).await;
+        assert_eq!(
+            res.errors[0].message,
+            "Invalid value for argument \"name\": must be at least 2 characters"
+        );
+    }
+
+    #[test]
+    fn test_invalid_validators() {
+        let registry = Registry::new().register::<Query>().check_validators(
+            "Query.sign_up",
+            &["name", "tags"],
+            &[
+                InputValueValidator::new("nmae").validator(MinLength(2)),
+                InputValueValidator::new("tags").item_validator(Pattern::new("[a-")),
+            ],
+        );
+        let schema = dynamic::Schema::build(Query::NAME, None, None);
+        let err = registry.try_build_schema(schema).err().unwrap();
+        assert!(err.starts_with(
+            "Invalid validator: \"Can't validate Query.sign_up.nmae, there is no such argument or field, Query.sign_up.tags: invalid pattern: regex parse error"
+        ));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::schema::output_types::utils::ResolveOwned;
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{InputObject, Object, Register, Registry};
+use crate::schema::validator::utils::{
+    with_validators, InputValueValidator, MaxItems, MinLength, Pattern, ValidateInputObject,
+};
+use async_graphql::dynamic;
+use serde::Deserialize;
+
+// user
+// mark as object
+struct Query;
+
+// mark as input
+#[derive(Deserialize)]
+struct PostInput {
+    // mark as validator(min_length = 3)
+    title: String,
+    // mark as validator(max_items = 2)
+    tags: Vec<TagInput>,
+}
+
+// mark as input
+#[derive(Deserialize)]
+struct TagInput {
+    // mark as validator(pattern = "^[a-z]+$")
+    name: String,
+}
+
+impl Query {
+    async fn resolve_create_post(&self, input: PostInput) -> String {
+        let tags = input
+            .tags
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect::<Vec<_>>();
+        format!("{} [{}]", input.title, tags.join(", "))
+    }
+}
+
+// generated
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl InputObject for PostInput {
+    const NAME: &'static str = "PostInput";
+}
+
+impl ValidateInputObject for PostInput {
+    const FIELDS: &'static [&'static str] = &["title", "tags"];
+
+    fn validators() -> Vec<InputValueValidator> {
+        vec![
+            InputValueValidator::new("title").validator(MinLength(3)),
+            InputValueValidator::new("tags")
+                .validator(MaxItems(2))
+                .input_object::<TagInput>(),
+        ]
+    }
+}
+
+impl Register for PostInput {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::InputObject::new(<Self as InputObject>::NAME);
+
+        // define title field
+        let title_field = dynamic::InputValue::new(
+            "title",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+        );
+        let object_type = object_type.field(title_field);
+
+        // define tags field
+        let tags_field = dynamic::InputValue::new(
+            "tags",
+            dynamic::TypeRef::named_nn_list_nn(<TagInput as InputObject>::NAME),
+        );
+        let object_type = object_type.field(tags_field);
+
+        registry.register_input_object(object_type)
+    }
+}
+
+impl InputObject for TagInput {
+    const NAME: &'static str = "TagInput";
+}
+
+impl ValidateInputObject for TagInput {
+    const FIELDS: &'static [&'static str] = &["name"];
+
+    fn validators() -> Vec<InputValueValidator> {
+        vec![InputValueValidator::new("name").validator(Pattern::new("^[a-z]+$"))]
+    }
+}
+
+impl Register for TagInput {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::InputObject::new(<Self as InputObject>::NAME);
+
+        // define name field
+        let name_field =
+            dynamic::InputValue::new("name", dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING));
+        let object_type = object_type.field(name_field);
+
+        registry.register_input_object(object_type)
+    }
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define create_post field
+        let create_post_validators =
+            vec![InputValueValidator::new("input").input_object::<PostInput>()];
+        let registry =
+            registry.check_validators("Query.create_post", &["input"], &create_post_validators);
+        let create_post_field = dynamic::Field::new(
+            "create_post",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            with_validators(create_post_validators, |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = ctx.args.try_get("input")?.deserialize()?;
+                    let value = parent.resolve_create_post(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            }),
+        );
+        let create_post_field = create_post_field.argument(dynamic::InputValue::new(
+            "input",
+            dynamic::TypeRef::named_nn(<PostInput as InputObject>::NAME),
+        ));
+        let object_type = object_type.field(create_post_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new()
+        .register::<Query>()
+        .register::<PostInput>()
+        .register::<TagInput>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    #[test]
+    fn test_schema() {
+        let schema = create_schema();
+        let sdl = schema.sdl();
+        assert_eq!(
+            normalize_schema(&sdl),
+            normalize_schema(
+                r#"
+                input PostInput {
+                  title: String!
+                  tags: [TagInput!]!
+                }
+                type Query {
+                  create_post(input: PostInput!): String!
+                }
+                input TagInput {
+                  name: String!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_valid() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                create_post(input: { title: "hello", tags: [{ name: "rust" }] })
+            }
+        "#;
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        assert_eq!(
+            res.data.into_json().unwrap(),
+            serde_json::json!({ "create_post": "hello [rust]" }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_nested_path() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                create_post(input: { title: "hello", tags: [{ name: "rust" }, { name: "Rust" }] })
+            }
+        "#;
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        assert_eq!(res.errors.len(), 1);
+        assert_eq!(
+            res.errors[0].message,
+            "Invalid value for argument \"input.tags.1.name\": must match pattern ^[a-z]+$"
+        );
+        let extensions = serde_json::to_value(&res.errors[0].extensions).unwrap();
+        assert_eq!(
+            extensions,
+            serde_json::json!({
+                "code": "PATTERN",
+                "argumentPath": ["input", "tags", 1, "name"],
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_title_length() {
+        let schema = create_schema();
+        let query = r#"
+            query {
+                create_post(input: { title: "hi", tags: [] })
+            }
+        "#;
+        let req =
+            async_graphql::Request::new(query).root_value(dynamic::FieldValue::owned_any(Query));
+        let res = schema.execute(req).await;
+        assert_eq!(res.data.into_json().unwrap(), serde_json::json!(null));
+        assert_eq!(
+            res.errors[0].message,
+            "Invalid value for argument \"input.title\": must be at least 3 characters"
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod argument;
+pub mod input_object;
+pub mod utils;
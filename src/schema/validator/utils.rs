@@ -0,0 +1,377 @@
+use crate::schema::registry::InputObject;
+use async_graphql::dynamic::{FieldFuture, FieldValue, ResolverContext};
+use async_graphql::{ErrorExtensions, Value};
+use regex::Regex;
+use std::sync::Arc;
+
+pub struct ValidationError {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+pub trait Validator: Send + Sync + 'static {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError>;
+
+    /// invalid configuration of the validator, e.g. a bad pattern, fails the schema build
+    fn check(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        _ => None,
+    }
+}
+
+fn as_str(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(value) => Some(value),
+        _ => None,
+    }
+}
+
+fn as_list(value: &Value) -> Option<&Vec<Value>> {
+    match value {
+        Value::List(list) => Some(list),
+        _ => None,
+    }
+}
+
+pub struct Minimum(pub f64);
+
+impl Validator for Minimum {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match as_f64(value) {
+            Some(number) if number < self.0 => Err(ValidationError::new(
+                "MINIMUM",
+                format!("must be greater than or equal to {}", self.0),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub struct Maximum(pub f64);
+
+impl Validator for Maximum {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match as_f64(value) {
+            Some(number) if number > self.0 => Err(ValidationError::new(
+                "MAXIMUM",
+                format!("must be less than or equal to {}", self.0),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub struct MinLength(pub usize);
+
+impl Validator for MinLength {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match as_str(value) {
+            Some(value) if value.chars().count() < self.0 => Err(ValidationError::new(
+                "MIN_LENGTH",
+                format!("must be at least {} characters", self.0),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub struct MaxLength(pub usize);
+
+impl Validator for MaxLength {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match as_str(value) {
+            Some(value) if value.chars().count() > self.0 => Err(ValidationError::new(
+                "MAX_LENGTH",
+                format!("must be at most {} characters", self.0),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub struct MinItems(pub usize);
+
+impl Validator for MinItems {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match as_list(value) {
+            Some(list) if list.len() < self.0 => Err(ValidationError::new(
+                "MIN_ITEMS",
+                format!("must contain at least {} items", self.0),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub struct MaxItems(pub usize);
+
+impl Validator for MaxItems {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match as_list(value) {
+            Some(list) if list.len() > self.0 => Err(ValidationError::new(
+                "MAX_ITEMS",
+                format!("must contain at most {} items", self.0),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// an invalid pattern is reported when the schema is built
+pub struct Pattern(Result<Regex, String>);
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Self {
+        Self(Regex::new(pattern).map_err(|err| err.to_string()))
+    }
+}
+
+impl Validator for Pattern {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        let regex = match &self.0 {
+            Ok(regex) => regex,
+            Err(err) => return Err(ValidationError::new("PATTERN", err.clone())),
+        };
+        match as_str(value) {
+            Some(value) if !regex.is_match(value) => Err(ValidationError::new(
+                "PATTERN",
+                format!("must match pattern {}", regex.as_str()),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn check(&self) -> Result<(), String> {
+        self.0
+            .as_ref()
+            .map(|_| ())
+            .map_err(|err| format!("invalid pattern: {}", err))
+    }
+}
+
+pub struct Email(Regex);
+
+impl Default for Email {
+    fn default() -> Self {
+        Self(Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").unwrap())
+    }
+}
+
+impl Validator for Email {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match as_str(value) {
+            Some(value) if !self.0.is_match(value) => Err(ValidationError::new(
+                "EMAIL",
+                "must be a valid email address",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub struct Url(Regex);
+
+impl Default for Url {
+    fn default() -> Self {
+        Self(Regex::new(r"^[a-zA-Z][a-zA-Z0-9+.-]*://[^\s/?#]+[^\s]*$").unwrap())
+    }
+}
+
+impl Validator for Url {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match as_str(value) {
+            Some(value) if !self.0.is_match(value) => {
+                Err(ValidationError::new("URL", "must be a valid url"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+pub struct Custom<F>(pub F);
+
+impl<F> Validator for Custom<F>
+where
+    F: Fn(&Value) -> Result<(), ValidationError> + Send + Sync + 'static,
+{
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        (self.0)(value)
+    }
+}
+
+/// validators of an argument or input object field, nested input object fields are
+/// validated with the path of the parent
+#[derive(Clone)]
+pub struct InputValueValidator {
+    name: String,
+    validators: Vec<Arc<dyn Validator>>,
+    item_validators: Vec<Arc<dyn Validator>>,
+    fields: Vec<InputValueValidator>,
+    // (input object, its fields) of the validated fields
+    input_object: Option<(&'static str, &'static [&'static str])>,
+}
+
+impl InputValueValidator {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            validators: Vec::new(),
+            item_validators: Vec::new(),
+            fields: Vec::new(),
+            input_object: None,
+        }
+    }
+
+    pub fn validator(mut self, validator: impl Validator) -> Self {
+        self.validators.push(Arc::new(validator));
+        self
+    }
+
+    /// validator for every item of a list value
+    pub fn item_validator(mut self, validator: impl Validator) -> Self {
+        self.item_validators.push(Arc::new(validator));
+        self
+    }
+
+    /// validate fields of the input object, also for each item of a list of input objects
+    pub fn input_object<T: ValidateInputObject>(mut self) -> Self {
+        self.fields.extend(T::validators());
+        self.input_object = Some((<T as InputObject>::NAME, T::FIELDS));
+        self
+    }
+
+    /// errors of a validator naming an argument or field the owner does not have, and of
+    /// invalid validators, checked by the registry when the schema is built
+    pub fn check(&self, owner: &str, names: &[&str]) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !names.contains(&self.name.as_str()) {
+            errors.push(format!(
+                "Can't validate {}.{}, there is no such argument or field",
+                owner, self.name
+            ));
+        }
+        for validator in self.validators.iter().chain(&self.item_validators) {
+            if let Err(err) = validator.check() {
+                errors.push(format!("{}.{}: {}", owner, self.name, err));
+            }
+        }
+        if let Some((object, fields)) = self.input_object {
+            for field in &self.fields {
+                errors.extend(field.check(object, fields));
+            }
+        }
+        errors
+    }
+
+    fn validate(&self, value: &Value, path: &mut Vec<Value>) -> async_graphql::Result<()> {
+        if let Value::Null = value {
+            return Ok(());
+        }
+        path.push(Value::from(self.name.as_str()));
+        for validator in &self.validators {
+            validator
+                .validate(value)
+                .map_err(|err| validation_error(err, path))?;
+        }
+        match value {
+            Value::List(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    path.push(Value::from(index));
+                    for validator in &self.item_validators {
+                        validator
+                            .validate(item)
+                            .map_err(|err| validation_error(err, path))?;
+                    }
+                    self.validate_fields(item, path)?;
+                    path.pop();
+                }
+            }
+            value => self.validate_fields(value, path)?,
+        }
+        path.pop();
+        Ok(())
+    }
+
+    fn validate_fields(&self, value: &Value, path: &mut Vec<Value>) -> async_graphql::Result<()> {
+        if let Value::Object(object) = value {
+            for field in &self.fields {
+                if let Some(value) = object.get(field.name.as_str()) {
+                    field.validate(value, path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// field validators of an input object, applied wherever the input object is used
+pub trait ValidateInputObject: InputObject {
+    /// fields of the input object, the validators are checked against them
+    const FIELDS: &'static [&'static str];
+
+    fn validators() -> Vec<InputValueValidator>;
+}
+
+fn validation_error(err: ValidationError, path: &[Value]) -> async_graphql::Error {
+    let display_path = path
+        .iter()
+        .map(|segment| match segment {
+            Value::String(name) => name.clone(),
+            segment => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(".");
+    let code = err.code;
+    let argument_path = Value::List(path.to_vec());
+    async_graphql::Error::new(format!(
+        "Invalid value for argument \"{}\": {}",
+        display_path, err.message
+    ))
+    .extend_with(|_, extensions| {
+        extensions.set("code", code);
+        extensions.set("argumentPath", argument_path);
+    })
+}
+
+/// run the argument validators, called by the field resolver before the user resolver
+pub fn validate_arguments(
+    ctx: &ResolverContext,
+    validators: &[InputValueValidator],
+) -> async_graphql::Result<()> {
+    for validator in validators {
+        if let Some(value) = ctx.args.get(&validator.name) {
+            let value: Value = value.deserialize()?;
+            validator.validate(&value, &mut Vec::new())?;
+        }
+    }
+    Ok(())
+}
+
+/// wrap a field resolver, the arguments are validated before the resolver is called
+pub fn with_validators<F>(
+    validators: Vec<InputValueValidator>,
+    resolver_fn: F,
+) -> impl for<'a> Fn(ResolverContext<'a>) -> FieldFuture<'a> + Send + Sync + 'static
+where
+    F: for<'a> Fn(ResolverContext<'a>) -> FieldFuture<'a> + Send + Sync + 'static,
+{
+    move |ctx| match validate_arguments(&ctx, &validators) {
+        Ok(()) => resolver_fn(ctx),
+        Err(err) => FieldFuture::new(async move { Err::<Option<FieldValue>, _>(err) }),
+    }
+}
@@ -0,0 +1,430 @@
+use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{Object, Register, Registry};
+use crate::schema::versions::utils::{ApiVersion, VersionRange, VersionedSchemas};
+use async_graphql::dynamic::FieldValue;
+use async_graphql::{dynamic, Context};
+
+// user
+
+// mark as object
+struct Query {
+    // mark as skip
+    users: Vec<User>,
+}
+
+impl Query {
+    async fn resolve_users(
+        &self,
+        // mark as since = "v2"
+        limit: Option<usize>,
+    ) -> Vec<&User> {
+        self.users
+            .iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+// mark as object
+struct User {
+    first_name: String,
+    last_name: String,
+    // mark as since = "v2"
+    avatar: Option<Avatar>,
+}
+
+impl User {
+    // mark as until = "v2"
+    async fn resolve_name(&self) -> String {
+        format!("{} {}", self.first_name, self.last_name)
+    }
+    // mark as since = "v1", until = "v2"
+    async fn resolve_initials(&self) -> String {
+        format!("{}{}", &self.first_name[..1], &self.last_name[..1]).to_uppercase()
+    }
+    // mark as since = "v2"
+    async fn resolve_first_name(&self) -> &String {
+        &self.first_name
+    }
+    // mark as since = "v2"
+    async fn resolve_last_name(&self) -> &String {
+        &self.last_name
+    }
+    async fn resolve_avatar(&self) -> Option<&Avatar> {
+        self.avatar.as_ref()
+    }
+}
+
+// mark as object
+// mark as since = "v2"
+struct Avatar {
+    url: String,
+}
+
+impl Avatar {
+    async fn resolve_url(&self) -> &String {
+        &self.url
+    }
+}
+
+// generated
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define users field
+        let users_field = dynamic::Field::new(
+            "users",
+            dynamic::TypeRef::named_nn_list_nn(User::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = match ctx.args.get("limit") {
+                        Some(value) => value.deserialize()?,
+                        None => None,
+                    };
+                    let value = parent.resolve_users(arg_0).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let users_field = match registry.in_version(VersionRange::since(2)) {
+            true => users_field.argument(dynamic::InputValue::new(
+                "limit",
+                dynamic::TypeRef::named(dynamic::TypeRef::INT),
+            )),
+            false => users_field,
+        };
+        let object_type = object_type.field(users_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Object for User {
+    const NAME: &'static str = "User";
+}
+
+impl<'a> ResolveRef<'a> for User {
+    fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::borrowed_any(self)))
+    }
+}
+
+impl Register for User {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define name field
+        let name_field = dynamic::Field::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_name().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = match registry.in_version(VersionRange::until(2)) {
+            true => object_type.field(name_field),
+            false => object_type,
+        };
+
+        // define initials field
+        let initials_field = dynamic::Field::new(
+            "initials",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_initials().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = match registry.in_version(VersionRange::between(1, 2)) {
+            true => object_type.field(initials_field),
+            false => object_type,
+        };
+
+        // define first_name field
+        let first_name_field = dynamic::Field::new(
+            "first_name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_first_name().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = match registry.in_version(VersionRange::since(2)) {
+            true => object_type.field(first_name_field),
+            false => object_type,
+        };
+
+        // define last_name field
+        let last_name_field = dynamic::Field::new(
+            "last_name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_last_name().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = match registry.in_version(VersionRange::since(2)) {
+            true => object_type.field(last_name_field),
+            false => object_type,
+        };
+
+        // define avatar field
+        let avatar_field =
+            dynamic::Field::new("avatar", dynamic::TypeRef::named(Avatar::NAME), |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_avatar().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            });
+        let object_type = match registry.in_version(VersionRange::since(2)) {
+            true => object_type.field(avatar_field),
+            false => object_type,
+        };
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Object for Avatar {
+    const NAME: &'static str = "Avatar";
+}
+
+impl<'a> ResolveRef<'a> for Avatar {
+    fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::borrowed_any(self)))
+    }
+}
+
+impl Register for Avatar {
+    fn register(registry: Registry) -> Registry {
+        if !registry.in_version(VersionRange::since(2)) {
+            return registry;
+        }
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define url field
+        let url_field = dynamic::Field::new(
+            "url",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_url().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(url_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+fn create_registry() -> Registry {
+    Registry::new()
+        .register::<Query>()
+        .register::<User>()
+        .register::<Avatar>()
+}
+
+pub fn create_schemas() -> VersionedSchemas {
+    create_registry()
+        .build_versions(&[ApiVersion(1), ApiVersion(2)], || {
+            dynamic::Schema::build(Query::NAME, None, None)
+        })
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_utils::normalize_schema;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    fn users() -> Vec<User> {
+        ["ann", "bob"]
+            .into_iter()
+            .map(|name| User {
+                first_name: name.to_string(),
+                last_name: "smith".to_string(),
+                avatar: Some(Avatar {
+                    url: format!("https://example.com/{}.png", name),
+                }),
+            })
+            .collect()
+    }
+
+    async fn execute(
+        schemas: &VersionedSchemas,
+        version: ApiVersion,
+        query: &str,
+    ) -> serde_json::Value {
+        let req = async_graphql::Request::new(query)
+            .root_value(FieldValue::owned_any(Query { users: users() }));
+        serde_json::to_value(schemas.execute(version, req).await).unwrap()
+    }
+
+    #[test]
+    fn test_schema() {
+        let schemas = create_schemas();
+        assert_eq!(
+            schemas.versions().collect::<Vec<_>>(),
+            vec![ApiVersion(1), ApiVersion(2)],
+        );
+        assert_eq!(schemas.latest(), Some(ApiVersion(2)));
+        assert_eq!(
+            normalize_schema(&schemas.schema(ApiVersion(1)).unwrap().sdl()),
+            normalize_schema(
+                r#"
+                type Query {
+                  users: [User!]!
+                }
+                type User {
+                  name: String!
+                  initials: String!
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+        assert_eq!(
+            normalize_schema(&schemas.schema(ApiVersion(2)).unwrap().sdl()),
+            normalize_schema(
+                r#"
+                type Avatar {
+                  url: String!
+                }
+                type Query {
+                  users(limit: Int): [User!]!
+                }
+                type User {
+                  first_name: String!
+                  last_name: String!
+                  avatar: Avatar
+                }
+                schema {
+                  query: Query
+                }
+                "#
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dispatch() {
+        let schemas = create_schemas();
+        assert_eq!(
+            execute(&schemas, ApiVersion(1), "{ users { name initials } }").await,
+            serde_json::json!({
+                "data": {
+                    "users": [
+                        { "name": "ann smith", "initials": "AS" },
+                        { "name": "bob smith", "initials": "BS" },
+                    ],
+                },
+            }),
+        );
+        assert_eq!(
+            execute(
+                &schemas,
+                ApiVersion(2),
+                "{ users(limit: 1) { first_name avatar { url } } }"
+            )
+            .await,
+            serde_json::json!({
+                "data": {
+                    "users": [{
+                        "first_name": "ann",
+                        "avatar": { "url": "https://example.com/ann.png" },
+                    }],
+                },
+            }),
+        );
+
+        let res = execute(&schemas, ApiVersion(2), "{ users { name } }").await;
+        assert_eq!(
+            res["errors"][0]["message"],
+            r#"Unknown field "name" on type "User"."#,
+        );
+        let res = execute(&schemas, ApiVersion(2), "{ users { initials } }").await;
+        assert_eq!(
+            res["errors"][0]["message"],
+            r#"Unknown field "initials" on type "User"."#,
+        );
+        let res = execute(&schemas, ApiVersion(1), "{ users(limit: 1) { name } }").await;
+        assert_eq!(
+            res["errors"][0]["message"],
+            r#"Unknown argument "limit" on field "users" of type "Query"."#,
+        );
+        let res = execute(&schemas, ApiVersion(3), "{ users { name } }").await;
+        assert_eq!(res["errors"][0]["message"], "Unknown api version v3");
+    }
+
+    #[test]
+    fn test_write_sdl() {
+        let schemas = create_schemas();
+        let dir = std::env::temp_dir().join(format!("versions-{}", std::process::id()));
+        schemas.write_sdl(&dir).unwrap();
+        for version in schemas.versions() {
+            let sdl = std::fs::read_to_string(dir.join(format!("schema.{}.graphql", version)));
+            assert_eq!(sdl.unwrap(), schemas.schema(version).unwrap().sdl());
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!("v2".parse::<ApiVersion>(), Ok(ApiVersion(2)));
+        assert_eq!(
+            "2".parse::<ApiVersion>().unwrap_err(),
+            "invalid api version `2`, expected e.g. v1",
+        );
+    }
+
+    #[test]
+    fn test_invalid_version() {
+        // the avatar type is only registered since v2
+        let registry = Registry::new()
+            .register::<Query>()
+            .register_with(|registry| {
+                let user = dynamic::Object::new(User::NAME).field(dynamic::Field::new(
+                    "avatar",
+                    dynamic::TypeRef::named(Avatar::NAME),
+                    |_| dynamic::FieldFuture::new(async { Ok(None::<FieldValue>) }),
+                ));
+                registry.register_object(user)
+            });
+        let registry = registry.register::<Avatar>();
+        let err = registry
+            .build_versions(&[ApiVersion(1), ApiVersion(2)], || {
+                dynamic::Schema::build(Query::NAME, None, None)
+            })
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), r#"v1: Type "Avatar" not found"#);
+    }
+}
@@ -0,0 +1,113 @@
+use async_graphql::dynamic::{self, DynamicRequest};
+use async_graphql::{Response, ServerError};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// version of the api, `v1` in requests and file names
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiVersion(pub u32);
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+impl FromStr for ApiVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix('v')
+            .and_then(|number| number.parse().ok())
+            .map(ApiVersion)
+            .ok_or_else(|| format!("invalid api version `{}`, expected e.g. v1", s))
+    }
+}
+
+/// versions an element exists in, `until` is the first version without it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VersionRange {
+    pub since: Option<ApiVersion>,
+    pub until: Option<ApiVersion>,
+}
+
+impl VersionRange {
+    pub const fn since(version: u32) -> Self {
+        Self {
+            since: Some(ApiVersion(version)),
+            until: None,
+        }
+    }
+
+    pub const fn until(version: u32) -> Self {
+        Self {
+            since: None,
+            until: Some(ApiVersion(version)),
+        }
+    }
+
+    pub const fn between(since: u32, until: u32) -> Self {
+        Self {
+            since: Some(ApiVersion(since)),
+            until: Some(ApiVersion(until)),
+        }
+    }
+
+    pub fn contains(&self, version: ApiVersion) -> bool {
+        self.since.is_none_or(|since| since <= version)
+            && self.until.is_none_or(|until| version < until)
+    }
+}
+
+/// one schema per api version, built by `Registry::build_versions`
+pub struct VersionedSchemas {
+    schemas: BTreeMap<ApiVersion, dynamic::Schema>,
+}
+
+impl VersionedSchemas {
+    pub(crate) fn new(schemas: BTreeMap<ApiVersion, dynamic::Schema>) -> Self {
+        Self { schemas }
+    }
+
+    pub fn versions(&self) -> impl Iterator<Item = ApiVersion> + '_ {
+        self.schemas.keys().copied()
+    }
+
+    pub fn latest(&self) -> Option<ApiVersion> {
+        self.schemas.keys().next_back().copied()
+    }
+
+    pub fn schema(&self, version: ApiVersion) -> Option<&dynamic::Schema> {
+        self.schemas.get(&version)
+    }
+
+    /// run the request on the schema of the version, an unknown version is an error response
+    pub async fn execute(
+        &self,
+        version: ApiVersion,
+        request: impl Into<DynamicRequest>,
+    ) -> Response {
+        match self.schema(version) {
+            Some(schema) => schema.execute(request).await,
+            None => Response::from_errors(vec![ServerError::new(
+                format!("Unknown api version {}", version),
+                None,
+            )]),
+        }
+    }
+
+    /// write `schema.<version>.graphql` for every version into the directory
+    pub fn write_sdl(&self, dir: impl AsRef<Path>) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        for (version, schema) in &self.schemas {
+            std::fs::write(
+                dir.join(format!("schema.{}.graphql", version)),
+                schema.sdl(),
+            )?;
+        }
+        Ok(())
+    }
+}
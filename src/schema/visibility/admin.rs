@@ -0,0 +1,455 @@
+use crate::schema::enums::utils::{EnumDefinition, EnumItemDefinition};
+use crate::schema::output_types::utils::{ResolveOwned, ResolveRef};
+use crate::schema::parent::parent_value;
+use crate::schema::registry::{
+    Enum, ExpandObject, ExpandObjectContext, Object, Register, Registry,
+};
+use crate::schema::visibility::utils::SchemaElement;
+use async_graphql::dynamic::FieldValue;
+use async_graphql::extensions::ExtensionContext;
+use async_graphql::{dynamic, Context};
+
+// user
+
+/// caller of the request, added to the request data
+struct Viewer {
+    admin: bool,
+}
+
+fn is_admin(ctx: &ExtensionContext<'_>) -> bool {
+    ctx.data_opt::<Viewer>().is_some_and(|viewer| viewer.admin)
+}
+
+// mark as enum
+#[derive(Clone, PartialEq)]
+enum Status {
+    Active,
+    // mark as visible = "is_admin"
+    Banned,
+}
+
+// mark as object
+struct Query {
+    // mark as skip
+    users: Vec<User>,
+}
+
+impl Query {
+    async fn resolve_users(
+        &self,
+        status: Option<Status>,
+        // mark as visible = "is_admin"
+        include_deleted: Option<bool>,
+    ) -> Vec<&User> {
+        let include_deleted = include_deleted.unwrap_or(false);
+        self.users
+            .iter()
+            .filter(|user| include_deleted || !user.deleted)
+            .filter(|user| status.as_ref().is_none_or(|status| &user.status == status))
+            .collect()
+    }
+    async fn resolve_audit_log(&self) -> Vec<AuditEntry> {
+        vec![AuditEntry {
+            action: "ban".to_string(),
+        }]
+    }
+}
+
+// mark as object
+struct User {
+    name: String,
+    status: Status,
+    // mark as skip
+    deleted: bool,
+    // mark as skip
+    notes: String,
+}
+
+impl User {
+    async fn resolve_name(&self) -> &String {
+        &self.name
+    }
+    async fn resolve_status(&self) -> &Status {
+        &self.status
+    }
+}
+
+// mark as object
+// mark as visible = "is_admin"
+struct AuditEntry {
+    action: String,
+}
+
+impl AuditEntry {
+    async fn resolve_action(&self) -> &String {
+        &self.action
+    }
+}
+
+struct UserAdmin;
+
+impl ExpandObject for UserAdmin {
+    type Target = User;
+}
+
+impl UserAdmin {
+    // mark User as Target
+    // mark as visible = "is_admin"
+    async fn resolve_notes(parent: &User) -> &String {
+        &parent.notes
+    }
+}
+
+// generated
+
+impl Enum for Status {
+    const NAME: &'static str = "Status";
+}
+
+impl EnumDefinition for Status {
+    const ITEMS: &'static [EnumItemDefinition<Self>] = &[
+        EnumItemDefinition::new("ACTIVE", Status::Active),
+        EnumItemDefinition::new("BANNED", Status::Banned),
+    ];
+}
+
+impl Register for Status {
+    fn register(registry: Registry) -> Registry {
+        let registry = registry.register_enum(<Self as EnumDefinition>::create_enum());
+        registry.visible_when(
+            SchemaElement::enum_item(<Self as Enum>::NAME, "BANNED"),
+            is_admin,
+        )
+    }
+}
+
+impl<'a> ResolveRef<'a> for Status {
+    fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(self.to_field_value()))
+    }
+}
+
+impl Object for Query {
+    const NAME: &'static str = "Query";
+}
+
+impl Register for Query {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define users field
+        let users_field = dynamic::Field::new(
+            "users",
+            dynamic::TypeRef::named_nn_list_nn(User::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let arg_0 = match ctx.args.get("status") {
+                        Some(value) if !value.is_null() => Some(Status::parse_value(value)?),
+                        _ => None,
+                    };
+                    let arg_1 = match ctx.args.get("include_deleted") {
+                        Some(value) => value.deserialize()?,
+                        None => None,
+                    };
+                    let value = parent.resolve_users(arg_0, arg_1).await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let users_field = users_field.argument(dynamic::InputValue::new(
+            "status",
+            dynamic::TypeRef::named(<Status as Enum>::NAME),
+        ));
+        let users_field = users_field.argument(dynamic::InputValue::new(
+            "include_deleted",
+            dynamic::TypeRef::named(dynamic::TypeRef::BOOLEAN),
+        ));
+        let registry = registry.visible_when(
+            SchemaElement::argument(<Self as Object>::NAME, "users", "include_deleted"),
+            is_admin,
+        );
+        let object_type = object_type.field(users_field);
+
+        // define audit_log field
+        let audit_log_field = dynamic::Field::new(
+            "audit_log",
+            dynamic::TypeRef::named_nn_list_nn(AuditEntry::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_audit_log().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(audit_log_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Object for User {
+    const NAME: &'static str = "User";
+}
+
+impl<'a> ResolveRef<'a> for User {
+    fn resolve_ref(&'a self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::borrowed_any(self)))
+    }
+}
+
+impl Register for User {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define name field
+        let name_field = dynamic::Field::new(
+            "name",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_name().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(name_field);
+
+        // define status field
+        let status_field = dynamic::Field::new(
+            "status",
+            dynamic::TypeRef::named_nn(<Status as Enum>::NAME),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_status().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(status_field);
+
+        registry.register_object(object_type)
+    }
+}
+
+impl Object for AuditEntry {
+    const NAME: &'static str = "AuditEntry";
+}
+
+impl<'a> ResolveOwned<'a> for AuditEntry {
+    fn resolve_owned(self, _ctx: &Context) -> async_graphql::Result<Option<FieldValue<'a>>> {
+        Ok(Some(FieldValue::owned_any(self)))
+    }
+}
+
+impl Register for AuditEntry {
+    fn register(registry: Registry) -> Registry {
+        let object_type = dynamic::Object::new(<Self as Object>::NAME);
+
+        // define action field
+        let action_field = dynamic::Field::new(
+            "action",
+            dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+            |ctx| {
+                dynamic::FieldFuture::new(async move {
+                    let parent = parent_value::<Self>(&ctx)?;
+                    let value = parent.resolve_action().await;
+                    ResolveOwned::resolve_owned(value, &ctx)
+                })
+            },
+        );
+        let object_type = object_type.field(action_field);
+
+        registry
+            .register_object(object_type)
+            .visible_when(SchemaElement::type_(<Self as Object>::NAME), is_admin)
+    }
+}
+
+impl Register for UserAdmin {
+    fn register(registry: Registry) -> Registry {
+        let registry = registry.visible_when(
+            SchemaElement::field(<<Self as ExpandObject>::Target as Object>::NAME, "notes"),
+            is_admin,
+        );
+        registry.update_object(
+            <<Self as ExpandObject>::Target as Object>::NAME,
+            |object_type| {
+                // define notes field
+                let notes_field = dynamic::Field::new(
+                    "notes",
+                    dynamic::TypeRef::named_nn(dynamic::TypeRef::STRING),
+                    |ctx| {
+                        dynamic::FieldFuture::new(async move {
+                            let parent = parent_value::<<Self as ExpandObject>::Target>(&ctx)?;
+                            let value = Self::resolve_notes(parent).await;
+                            ResolveOwned::resolve_owned(value, &ctx)
+                        })
+                    },
+                );
+                object_type.field(notes_field)
+            },
+            ExpandObjectContext::new("UserAdmin", "notes"),
+        )
+    }
+}
+
+pub fn create_schema() -> dynamic::Schema {
+    let registry = Registry::new()
+        .register::<Status>()
+        .register::<Query>()
+        .register::<User>()
+        .register::<AuditEntry>()
+        .register::<UserAdmin>();
+    let schema = dynamic::Schema::build(Query::NAME, None, None);
+    registry.build_schema(schema).finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::dynamic::DynamicRequestExt;
+
+    fn user(name: &str, status: Status, deleted: bool) -> User {
+        User {
+            name: name.to_string(),
+            status,
+            deleted,
+            notes: format!("{} notes", name),
+        }
+    }
+
+    async fn execute(query: &str, admin: bool) -> serde_json::Value {
+        let schema = create_schema();
+        let users = vec![
+            user("ann", Status::Active, false),
+            user("bob", Status::Banned, false),
+            user("cid", Status::Active, true),
+        ];
+        let req = async_graphql::Request::new(query)
+            .data(Viewer { admin })
+            .root_value(FieldValue::owned_any(Query { users }));
+        serde_json::to_value(schema.execute(req).await).unwrap()
+    }
+
+    async fn error(query: &str) -> serde_json::Value {
+        execute(query, false).await["errors"][0]["message"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_admin() {
+        let query = r#"
+            {
+                users(status: BANNED, include_deleted: true) { name notes }
+                audit_log { action }
+            }
+        "#;
+        assert_eq!(
+            execute(query, true).await,
+            serde_json::json!({
+                "data": {
+                    "users": [{ "name": "bob", "notes": "bob notes" }],
+                    "audit_log": [{ "action": "ban" }],
+                },
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hidden() {
+        assert_eq!(
+            execute("{ users { name status } }", false).await,
+            serde_json::json!({
+                "data": {
+                    "users": [
+                        { "name": "ann", "status": "ACTIVE" },
+                        { "name": "bob", "status": "BANNED" },
+                    ],
+                },
+            }),
+        );
+        assert_eq!(
+            error("{ users { notes } }").await,
+            r#"Unknown field "notes" on type "User"."#,
+        );
+        assert_eq!(
+            error("{ audit_log { action } }").await,
+            r#"Unknown field "audit_log" on type "Query"."#,
+        );
+        assert_eq!(
+            error("{ users(include_deleted: true) { name } }").await,
+            r#"Unknown argument "include_deleted" on field "users" of type "Query"."#,
+        );
+        assert_eq!(
+            error("{ users(status: BANNED) { name } }").await,
+            r#"Invalid value for argument "status", enumeration type "Status" does not contain the value "BANNED""#,
+        );
+        // the default value of the variable is checked too
+        assert_eq!(
+            error("query($status: Status = BANNED) { users(status: $status) { name } }").await,
+            r#"Invalid value for argument "status", enumeration type "Status" does not contain the value "BANNED""#,
+        );
+        assert_eq!(
+            error(
+                "query($status: Status = BANNED) { ...Users } fragment Users on Query { users(status: $status) { name } }"
+            )
+            .await,
+            r#"Invalid value for argument "status", enumeration type "Status" does not contain the value "BANNED""#,
+        );
+        assert_eq!(
+            error("query { ...Admin } fragment Admin on Query { users { ... on User { notes } } }")
+                .await,
+            r#"Unknown field "notes" on type "User"."#,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_introspection() {
+        let query = r#"
+            {
+                user: __type(name: "User") { ...Fields }
+                query: __type(name: "Query") { ...Fields }
+                status: __type(name: "Status") { enumValues { name } }
+                audit: __type(name: "AuditEntry") { name }
+                __schema { types { name } }
+            }
+            fragment Fields on __Type {
+                fields { name args { name } }
+            }
+        "#;
+        let res = execute(query, false).await;
+        assert_eq!(
+            res["data"]["user"],
+            serde_json::json!({
+                "fields": [{ "name": "name", "args": [] }, { "name": "status", "args": [] }],
+            }),
+        );
+        assert_eq!(
+            res["data"]["query"],
+            serde_json::json!({
+                "fields": [{ "name": "users", "args": [{ "name": "status" }] }],
+            }),
+        );
+        assert_eq!(
+            res["data"]["status"],
+            serde_json::json!({ "enumValues": [{ "name": "ACTIVE" }] }),
+        );
+        assert_eq!(res["data"]["audit"], serde_json::Value::Null);
+        let types = res["data"]["__schema"]["types"].as_array().unwrap();
+        assert!(types.contains(&serde_json::json!({ "name": "User" })));
+        assert!(!types.contains(&serde_json::json!({ "name": "AuditEntry" })));
+
+        let res = execute(query, true).await;
+        assert_eq!(
+            res["data"]["user"]["fields"][2],
+            serde_json::json!({ "name": "notes", "args": [] }),
+        );
+        assert_eq!(
+            res["data"]["audit"],
+            serde_json::json!({ "name": "AuditEntry" }),
+        );
+    }
+}
@@ -0,0 +1,592 @@
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextExecute, NextParseQuery,
+};
+use async_graphql::parser::types::{
+    DocumentOperations, ExecutableDocument, Field, OperationDefinition, OperationType, Selection,
+    SelectionSet,
+};
+use async_graphql::parser::{Pos, Positioned};
+use async_graphql::registry::{MetaType, MetaTypeName};
+use async_graphql::{Name, Response, ServerError, ServerResult, Variables};
+use async_graphql_value::ConstValue;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+/// decides per request whether an element is visible, see `Registry::visible_when`
+pub type VisibleFn = Arc<dyn Fn(&ExtensionContext<'_>) -> bool + Send + Sync>;
+
+/// element of the schema, names are graphql names
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SchemaElement {
+    Type(String),
+    /// (type, field)
+    Field(String, String),
+    /// (enum, item)
+    EnumItem(String, String),
+    /// (type, field, argument)
+    Argument(String, String, String),
+}
+
+impl SchemaElement {
+    pub fn type_(name: &str) -> Self {
+        Self::Type(name.to_string())
+    }
+
+    pub fn field(type_name: &str, field: &str) -> Self {
+        Self::Field(type_name.to_string(), field.to_string())
+    }
+
+    pub fn enum_item(enum_name: &str, item: &str) -> Self {
+        Self::EnumItem(enum_name.to_string(), item.to_string())
+    }
+
+    pub fn argument(type_name: &str, field: &str, argument: &str) -> Self {
+        Self::Argument(
+            type_name.to_string(),
+            field.to_string(),
+            argument.to_string(),
+        )
+    }
+}
+
+// alias of the name selected in every introspection object, removed from the response
+const NAME_KEY: &str = "_visibility_name";
+
+/// root type of the operation, `None` when the schema has no such root
+fn root_type<'a>(
+    registry: &'a async_graphql::registry::Registry,
+    operation: &OperationDefinition,
+) -> Option<&'a str> {
+    match operation.ty {
+        OperationType::Query => Some(registry.query_type.as_str()),
+        OperationType::Mutation => registry.mutation_type.as_deref(),
+        OperationType::Subscription => registry.subscription_type.as_deref(),
+    }
+}
+
+/// elements hidden from the current request, fields and arguments of hidden types are hidden
+/// too
+struct Hidden<'a> {
+    types: &'a std::collections::BTreeMap<String, MetaType>,
+    elements: HashSet<SchemaElement>,
+}
+
+impl Hidden<'_> {
+    fn type_(&self, name: &str) -> bool {
+        self.elements.contains(&SchemaElement::type_(name))
+    }
+
+    fn field(&self, type_name: &str, field: &str) -> bool {
+        let ty = self
+            .types
+            .get(type_name)
+            .and_then(MetaType::fields)
+            .and_then(|fields| fields.get(field))
+            .map(|field| MetaTypeName::concrete_typename(&field.ty));
+        self.elements
+            .contains(&SchemaElement::field(type_name, field))
+            || ty.is_some_and(|ty| self.type_(ty))
+    }
+
+    fn argument(&self, type_name: &str, field: &str, argument: &str) -> bool {
+        let ty = self
+            .types
+            .get(type_name)
+            .and_then(MetaType::fields)
+            .and_then(|fields| fields.get(field))
+            .and_then(|field| field.args.get(argument))
+            .map(|argument| MetaTypeName::concrete_typename(&argument.ty));
+        self.elements
+            .contains(&SchemaElement::argument(type_name, field, argument))
+            || ty.is_some_and(|ty| self.type_(ty))
+    }
+
+    fn enum_item(&self, enum_name: &str, item: &str) -> bool {
+        self.elements
+            .contains(&SchemaElement::enum_item(enum_name, item))
+    }
+
+    /// named type of the field, introspection fields of the query root included
+    fn field_type<'b>(&'b self, type_name: &str, field: &str) -> Option<&'b str> {
+        match field {
+            "__schema" => Some("__Schema"),
+            "__type" => Some("__Type"),
+            _ => self
+                .types
+                .get(type_name)
+                .and_then(MetaType::fields)
+                .and_then(|fields| fields.get(field))
+                .map(|field| MetaTypeName::concrete_typename(&field.ty)),
+        }
+    }
+}
+
+pub(crate) struct VisibilityExtension(pub Arc<Vec<(SchemaElement, VisibleFn)>>);
+
+impl ExtensionFactory for VisibilityExtension {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(VisibilityFilter {
+            rules: self.0.clone(),
+            state: Default::default(),
+        })
+    }
+}
+
+struct VisibilityFilter {
+    rules: Arc<Vec<(SchemaElement, VisibleFn)>>,
+    // hidden elements and the document with the injected names, set when the query is parsed
+    state: Mutex<Option<(HashSet<SchemaElement>, ExecutableDocument)>>,
+}
+
+#[async_trait::async_trait]
+impl Extension for VisibilityFilter {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let mut document = next.run(ctx, query, variables).await?;
+        let elements = self
+            .rules
+            .iter()
+            .filter(|(_, visible)| !visible(ctx))
+            .map(|(element, _)| element.clone())
+            .collect::<HashSet<_>>();
+        if elements.is_empty() {
+            return Ok(document);
+        }
+        let registry = &ctx.schema_env.registry;
+        let hidden = Hidden {
+            types: &registry.types,
+            elements,
+        };
+        let mut checker = Checker {
+            hidden: &hidden,
+            variables,
+            defaults: HashMap::new(),
+            errors: Vec::new(),
+        };
+        checker.check_document(&document, registry);
+        if let Some(err) = checker.errors.into_iter().next() {
+            return Err(err);
+        }
+        inject_names(&hidden, &mut document, registry);
+        *self.state.lock().unwrap() = Some((hidden.elements, document.clone()));
+        Ok(document)
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let mut response = next.run(ctx, operation_name).await;
+        let state = self.state.lock().unwrap().take();
+        if let Some((elements, document)) = state {
+            let registry = &ctx.schema_env.registry;
+            let hidden = Hidden {
+                types: &registry.types,
+                elements,
+            };
+            let operation = match &document.operations {
+                DocumentOperations::Single(operation) => Some(operation),
+                DocumentOperations::Multiple(operations) => operation_name
+                    .and_then(|name| operations.get(name))
+                    .or_else(|| operations.values().next()),
+            };
+            if let Some((operation, root)) = operation
+                .and_then(|operation| Some((operation, root_type(registry, &operation.node)?)))
+            {
+                let filter = Filter {
+                    hidden: &hidden,
+                    document: &document,
+                };
+                filter.filter_object(
+                    root,
+                    &operation.node.selection_set.node,
+                    &mut response.data,
+                    &Owner::default(),
+                );
+            }
+        }
+        response
+    }
+}
+
+/// rejects selections of hidden elements with the errors of unknown elements
+struct Checker<'a> {
+    hidden: &'a Hidden<'a>,
+    variables: &'a Variables,
+    // default values of the variables of the checked operation, of all operations for
+    // fragments
+    defaults: HashMap<Name, ConstValue>,
+    errors: Vec<ServerError>,
+}
+
+impl Checker<'_> {
+    fn check_document(
+        &mut self,
+        document: &ExecutableDocument,
+        registry: &async_graphql::registry::Registry,
+    ) {
+        let mut all_defaults = HashMap::new();
+        for (_, operation) in document.operations.iter() {
+            let root = root_type(registry, &operation.node);
+            self.defaults = operation
+                .node
+                .variable_definitions
+                .iter()
+                .filter_map(|variable| {
+                    let default = variable.node.default_value.as_ref()?;
+                    Some((variable.node.name.node.clone(), default.node.clone()))
+                })
+                .collect();
+            for (name, value) in &self.defaults {
+                all_defaults
+                    .entry(name.clone())
+                    .or_insert_with(|| value.clone());
+            }
+            for variable in &operation.node.variable_definitions {
+                let ty = &variable.node.var_type;
+                self.check_type(
+                    ty.node.base.to_string().trim_matches(['[', ']', '!']),
+                    ty.pos,
+                );
+            }
+            if let Some(root) = root {
+                self.check_selection_set(root, &operation.node.selection_set.node);
+            }
+        }
+        self.defaults = all_defaults;
+        for (_, fragment) in document.fragments.iter() {
+            let on = &fragment.node.type_condition.node.on;
+            if self.check_type(&on.node, on.pos) {
+                self.check_selection_set(&on.node, &fragment.node.selection_set.node);
+            }
+        }
+    }
+
+    fn check_type(&mut self, name: &str, pos: Pos) -> bool {
+        if self.hidden.type_(name) {
+            self.errors.push(ServerError::new(
+                format!(r#"Unknown type "{}""#, name),
+                Some(pos),
+            ));
+            return false;
+        }
+        true
+    }
+
+    fn check_selection_set(&mut self, type_name: &str, selection_set: &SelectionSet) {
+        for selection in &selection_set.items {
+            match &selection.node {
+                Selection::Field(field) => self.check_field(type_name, field),
+                Selection::InlineFragment(fragment) => {
+                    let type_name = match &fragment.node.type_condition {
+                        Some(condition) => {
+                            let on = &condition.node.on;
+                            if !self.check_type(&on.node, on.pos) {
+                                continue;
+                            }
+                            on.node.as_str()
+                        }
+                        None => type_name,
+                    };
+                    self.check_selection_set(type_name, &fragment.node.selection_set.node);
+                }
+                // fragments are checked with their type condition
+                Selection::FragmentSpread(_) => {}
+            }
+        }
+    }
+
+    fn check_field(&mut self, type_name: &str, field: &Positioned<Field>) {
+        let name = field.node.name.node.as_str();
+        if self.hidden.field(type_name, name) {
+            self.errors.push(ServerError::new(
+                format!(r#"Unknown field "{}" on type "{}"."#, name, type_name),
+                Some(field.pos),
+            ));
+            return;
+        }
+        let meta_field = self
+            .hidden
+            .types
+            .get(type_name)
+            .and_then(MetaType::fields)
+            .and_then(|fields| fields.get(name));
+        for (argument, value) in &field.node.arguments {
+            if self.hidden.argument(type_name, name, &argument.node) {
+                self.errors.push(ServerError::new(
+                    format!(
+                        r#"Unknown argument "{}" on field "{}" of type "{}"."#,
+                        argument.node, name, type_name
+                    ),
+                    Some(argument.pos),
+                ));
+                continue;
+            }
+            let meta_argument = meta_field.and_then(|field| field.args.get(argument.node.as_str()));
+            let value = value.node.clone().into_const_with(|variable| {
+                self.variables
+                    .get(&variable)
+                    .or_else(|| self.defaults.get(&variable))
+                    .cloned()
+                    .ok_or(())
+            });
+            if let (Some(meta_argument), Ok(value)) = (meta_argument, value) {
+                if let Some(reason) =
+                    self.check_value(&meta_argument.ty, &value, argument.node.to_string())
+                {
+                    self.errors.push(ServerError::new(
+                        format!("Invalid value for argument {}", reason),
+                        Some(argument.pos),
+                    ));
+                }
+            }
+        }
+        if let Some(field_type) = self.hidden.field_type(type_name, name) {
+            self.check_selection_set(field_type, &field.node.selection_set.node);
+        }
+    }
+
+    /// hidden enum items in the input value
+    fn check_value(&self, ty: &str, value: &ConstValue, path: String) -> Option<String> {
+        match MetaTypeName::create(ty) {
+            MetaTypeName::NonNull(ty) => self.check_value(ty, value, path),
+            MetaTypeName::List(ty) => match value {
+                ConstValue::List(items) => items.iter().enumerate().find_map(|(idx, item)| {
+                    self.check_value(ty, item, format!("{}.{}", path, idx))
+                }),
+                _ => self.check_value(ty, value, path),
+            },
+            MetaTypeName::Named(ty) => match (self.hidden.types.get(ty), value) {
+                (Some(MetaType::Enum { .. }), ConstValue::Enum(item)) => {
+                    self.check_enum_item(ty, item, &path)
+                }
+                (Some(MetaType::Enum { .. }), ConstValue::String(item)) => {
+                    self.check_enum_item(ty, item, &path)
+                }
+                (Some(MetaType::InputObject { input_fields, .. }), ConstValue::Object(fields)) => {
+                    fields.iter().find_map(|(name, value)| {
+                        let field = input_fields.get(name.as_str())?;
+                        self.check_value(&field.ty, value, format!("{}.{}", path, name))
+                    })
+                }
+                _ => None,
+            },
+        }
+    }
+
+    fn check_enum_item(&self, enum_name: &str, item: &str, path: &str) -> Option<String> {
+        self.hidden.enum_item(enum_name, item).then(|| {
+            format!(
+                r#""{}", enumeration type "{}" does not contain the value "{}""#,
+                path, enum_name, item
+            )
+        })
+    }
+}
+
+fn is_introspection_object(type_name: &str) -> bool {
+    matches!(
+        type_name,
+        "__Type" | "__Field" | "__EnumValue" | "__InputValue"
+    )
+}
+
+/// select the name of every introspection object, the filter needs it to find hidden elements
+fn inject_names(
+    hidden: &Hidden,
+    document: &mut ExecutableDocument,
+    registry: &async_graphql::registry::Registry,
+) {
+    let operations = match &mut document.operations {
+        DocumentOperations::Single(operation) => vec![operation],
+        DocumentOperations::Multiple(operations) => operations.values_mut().collect(),
+    };
+    for operation in operations {
+        if let Some(root) = root_type(registry, &operation.node) {
+            inject_selection_set(hidden, root, &mut operation.node.selection_set.node);
+        }
+    }
+    for (_, fragment) in document.fragments.iter_mut() {
+        let on = fragment.node.type_condition.node.on.node.to_string();
+        inject_selection_set(hidden, &on, &mut fragment.node.selection_set.node);
+    }
+}
+
+fn inject_selection_set(hidden: &Hidden, type_name: &str, selection_set: &mut SelectionSet) {
+    for selection in &mut selection_set.items {
+        match &mut selection.node {
+            Selection::Field(field) => {
+                let name = field.node.name.node.as_str();
+                if let Some(field_type) = hidden.field_type(type_name, name) {
+                    let field_type = field_type.to_string();
+                    inject_selection_set(hidden, &field_type, &mut field.node.selection_set.node);
+                }
+            }
+            Selection::InlineFragment(fragment) => {
+                let type_name = match &fragment.node.type_condition {
+                    Some(condition) => condition.node.on.node.to_string(),
+                    None => type_name.to_string(),
+                };
+                inject_selection_set(hidden, &type_name, &mut fragment.node.selection_set.node);
+            }
+            Selection::FragmentSpread(_) => {}
+        }
+    }
+    if is_introspection_object(type_name) {
+        let name = Field {
+            alias: Some(Positioned::new(Name::new(NAME_KEY), Pos::default())),
+            name: Positioned::new(Name::new("name"), Pos::default()),
+            arguments: Vec::new(),
+            directives: Vec::new(),
+            selection_set: Positioned::new(SelectionSet::default(), Pos::default()),
+        };
+        selection_set.items.push(Positioned::new(
+            Selection::Field(Positioned::new(name, Pos::default())),
+            Pos::default(),
+        ));
+    }
+}
+
+/// the enclosing type and field of an introspection object
+#[derive(Default, Clone)]
+struct Owner {
+    type_name: Option<String>,
+    field: Option<String>,
+}
+
+/// removes hidden elements from the introspection results of the response
+struct Filter<'a> {
+    hidden: &'a Hidden<'a>,
+    document: &'a ExecutableDocument,
+}
+
+impl Filter<'_> {
+    /// (response key, field, parent type) of the selection set with its fragments
+    fn collect_fields<'b>(
+        &'b self,
+        type_name: &'b str,
+        selection_set: &'b SelectionSet,
+        fields: &mut Vec<(&'b str, &'b Field, &'b str)>,
+    ) {
+        for selection in &selection_set.items {
+            match &selection.node {
+                Selection::Field(field) => {
+                    let key = field.node.alias.as_ref().unwrap_or(&field.node.name);
+                    fields.push((key.node.as_str(), &field.node, type_name));
+                }
+                Selection::InlineFragment(fragment) => {
+                    let type_name = match &fragment.node.type_condition {
+                        Some(condition) => condition.node.on.node.as_str(),
+                        None => type_name,
+                    };
+                    self.collect_fields(type_name, &fragment.node.selection_set.node, fields);
+                }
+                Selection::FragmentSpread(spread) => {
+                    if let Some(fragment) =
+                        self.document.fragments.get(&spread.node.fragment_name.node)
+                    {
+                        let type_name = fragment.node.type_condition.node.on.node.as_str();
+                        self.collect_fields(type_name, &fragment.node.selection_set.node, fields);
+                    }
+                }
+            }
+        }
+    }
+
+    fn filter_object(
+        &self,
+        type_name: &str,
+        selection_set: &SelectionSet,
+        value: &mut ConstValue,
+        owner: &Owner,
+    ) {
+        let ConstValue::Object(object) = value else {
+            return;
+        };
+        let name = match object.shift_remove(NAME_KEY) {
+            Some(ConstValue::String(name)) => Some(name),
+            _ => None,
+        };
+        let owner = match type_name {
+            "__Type" => Owner {
+                type_name: name,
+                field: None,
+            },
+            "__Field" => Owner {
+                field: name,
+                ..owner.clone()
+            },
+            _ => owner.clone(),
+        };
+        let mut fields = Vec::new();
+        self.collect_fields(type_name, selection_set, &mut fields);
+        for (key, field, parent) in fields {
+            let name = field.name.node.as_str();
+            let (Some(field_type), Some(value)) =
+                (self.hidden.field_type(parent, name), object.get_mut(key))
+            else {
+                continue;
+            };
+            if name == "__type"
+                && self
+                    .type_name(value)
+                    .is_some_and(|ty| self.hidden.type_(&ty))
+            {
+                *value = ConstValue::Null;
+                continue;
+            }
+            self.filter_value(field_type, &field.selection_set.node, value, &owner);
+        }
+    }
+
+    fn filter_value(
+        &self,
+        type_name: &str,
+        selection_set: &SelectionSet,
+        value: &mut ConstValue,
+        owner: &Owner,
+    ) {
+        match value {
+            ConstValue::List(items) => {
+                items.retain(|item| !self.is_hidden(type_name, item, owner));
+                for item in items {
+                    self.filter_value(type_name, selection_set, item, owner);
+                }
+            }
+            ConstValue::Object(_) => self.filter_object(type_name, selection_set, value, owner),
+            _ => {}
+        }
+    }
+
+    fn type_name(&self, value: &ConstValue) -> Option<String> {
+        match value {
+            ConstValue::Object(object) => match object.get(NAME_KEY) {
+                Some(ConstValue::String(name)) => Some(name.clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn is_hidden(&self, type_name: &str, item: &ConstValue, owner: &Owner) -> bool {
+        let Some(name) = self.type_name(item) else {
+            return false;
+        };
+        match (type_name, &owner.type_name, &owner.field) {
+            ("__Type", _, _) => self.hidden.type_(&name),
+            ("__Field", Some(parent), _) => self.hidden.field(parent, &name),
+            ("__EnumValue", Some(parent), _) => self.hidden.enum_item(parent, &name),
+            ("__InputValue", Some(parent), Some(field)) => {
+                self.hidden.argument(parent, field, &name)
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+usage: async-graphql-dynamic-extend [options]
+  --bind <addr>              address to listen on, default 127.0.0.1:8000
+  --schema <name>            schema module to serve, default hello
+  --limit-depth <n>          reject queries nested deeper than n
+  --limit-complexity <n>     reject queries more complex than n
+  --max-body-bytes <n>       reject request bodies larger than n bytes, default 1048576
+  --write-sdl <path>         write the sdl of the schema to the file and exit
+  -h, --help                 print this help and exit";
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ConfigError {
+    /// `--help` is given, the usage is printed instead of serving
+    #[error("help requested")]
+    Help,
+    #[error("unknown option {0}")]
+    UnknownOption(String),
+    #[error("missing value for {0}")]
+    MissingValue(String),
+    #[error("invalid value `{value}` for {option}")]
+    InvalidValue { option: String, value: String },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerConfig {
+    pub bind: SocketAddr,
+    pub schema: String,
+    pub limit_depth: Option<usize>,
+    pub limit_complexity: Option<usize>,
+    pub max_body_bytes: usize,
+    pub write_sdl: Option<PathBuf>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([127, 0, 0, 1], 8000)),
+            schema: "hello".to_string(),
+            limit_depth: None,
+            limit_complexity: None,
+            max_body_bytes: 1024 * 1024,
+            write_sdl: None,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// parse the command line arguments, without the program name
+    pub fn from_args<I>(args: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut config = Self::default();
+        let mut args = args.into_iter();
+        while let Some(option) = args.next() {
+            if option == "--help" || option == "-h" {
+                return Err(ConfigError::Help);
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| ConfigError::MissingValue(option.clone()))?;
+            match option.as_str() {
+                "--bind" => config.bind = parse(&option, value)?,
+                "--schema" => config.schema = value,
+                "--limit-depth" => config.limit_depth = Some(parse(&option, value)?),
+                "--limit-complexity" => config.limit_complexity = Some(parse(&option, value)?),
+                "--max-body-bytes" => config.max_body_bytes = parse(&option, value)?,
+                "--write-sdl" => config.write_sdl = Some(PathBuf::from(value)),
+                _ => return Err(ConfigError::UnknownOption(option)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+fn parse<T: std::str::FromStr>(option: &str, value: String) -> Result<T, ConfigError> {
+    value.parse().map_err(|_| ConfigError::InvalidValue {
+        option: option.to_string(),
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_args(args: &[&str]) -> Result<ServerConfig, ConfigError> {
+        ServerConfig::from_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_from_args() {
+        assert_eq!(from_args(&[]).unwrap(), ServerConfig::default());
+        assert_eq!(
+            from_args(&[
+                "--bind",
+                "0.0.0.0:9000",
+                "--schema",
+                "expand_object",
+                "--limit-depth",
+                "5",
+                "--max-body-bytes",
+                "100",
+            ])
+            .unwrap(),
+            ServerConfig {
+                bind: "0.0.0.0:9000".parse().unwrap(),
+                schema: "expand_object".to_string(),
+                limit_depth: Some(5),
+                max_body_bytes: 100,
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        assert_eq!(
+            from_args(&["--port", "80"]).unwrap_err(),
+            ConfigError::UnknownOption("--port".to_string()),
+        );
+        assert_eq!(
+            from_args(&["--bind"]).unwrap_err().to_string(),
+            "missing value for --bind",
+        );
+        assert_eq!(
+            from_args(&["--limit-depth", "deep"])
+                .unwrap_err()
+                .to_string(),
+            "invalid value `deep` for --limit-depth",
+        );
+    }
+
+    #[test]
+    fn test_help() {
+        assert_eq!(from_args(&["--help"]).unwrap_err(), ConfigError::Help);
+        assert_eq!(
+            from_args(&["--bind", "0.0.0.0:9000", "-h"]).unwrap_err(),
+            ConfigError::Help,
+        );
+        // the value of an option is never taken as help
+        assert_eq!(
+            from_args(&["--schema", "-h"]).unwrap(),
+            ServerConfig {
+                schema: "-h".to_string(),
+                ..Default::default()
+            },
+        );
+    }
+}
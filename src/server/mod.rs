@@ -0,0 +1,207 @@
+pub mod config;
+pub mod routes;
+pub mod schemas;
+
+use crate::server::config::ServerConfig;
+use crate::server::schemas::ServedSchema;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// serve the schema until `shutdown` completes, requests in flight are finished first
+pub async fn serve<F>(
+    listener: TcpListener,
+    schema: ServedSchema,
+    config: &ServerConfig,
+    shutdown: F,
+) -> std::io::Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let app = routes::router(Arc::new(schema), config);
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown)
+        .await
+}
+
+/// completes on ctrl-c or SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.ok();
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio::sync::oneshot;
+    use tokio::task::JoinHandle;
+
+    struct TestServer {
+        addr: SocketAddr,
+        shutdown: oneshot::Sender<()>,
+        task: JoinHandle<std::io::Result<()>>,
+    }
+
+    async fn start(config: ServerConfig) -> TestServer {
+        let schema = ServedSchema::build(&config).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (shutdown, signal) = oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            serve(listener, schema, &config, async {
+                signal.await.ok();
+            })
+            .await
+        });
+        TestServer {
+            addr,
+            shutdown,
+            task,
+        }
+    }
+
+    // minimal http/1.1 client, the connection is closed after the response
+    async fn send(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "{} {} HTTP/1.1\r\nhost: {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            method,
+            path,
+            addr,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, body.to_string())
+    }
+
+    async fn send_json(
+        addr: SocketAddr,
+        method: &str,
+        path: &str,
+        body: &str,
+    ) -> (u16, serde_json::Value) {
+        let (status, body) = send(addr, method, path, body).await;
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_graphql() {
+        let server = start(ServerConfig::default()).await;
+        assert_eq!(
+            send_json(server.addr, "POST", "/graphql", r#"{"query":"{ hello }"}"#).await,
+            (200, serde_json::json!({ "data": { "hello": "world" } })),
+        );
+        assert_eq!(
+            send_json(server.addr, "GET", "/graphql?query=%7B%20hello%20%7D", "").await,
+            (200, serde_json::json!({ "data": { "hello": "world" } })),
+        );
+        let (status, res) = send_json(
+            server.addr,
+            "GET",
+            "/graphql?query=mutation%20%7B%20hello%20%7D",
+            "",
+        )
+        .await;
+        assert_eq!(status, 405);
+        assert_eq!(
+            res["errors"][0]["message"],
+            "mutations can't be sent with GET"
+        );
+        assert_eq!(
+            send_json(server.addr, "GET", "/health", "").await,
+            (200, serde_json::json!({ "status": "ok" })),
+        );
+        let (status, page) = send(server.addr, "GET", "/", "").await;
+        assert_eq!(status, 200);
+        assert!(page.contains("graphiql"));
+        server.shutdown.send(()).unwrap();
+        server.task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_root_value() {
+        let server = start(ServerConfig {
+            schema: "expand_object".to_string(),
+            ..Default::default()
+        })
+        .await;
+        let (status, res) = send_json(
+            server.addr,
+            "POST",
+            "/graphql",
+            r#"{"query":"{ me { name } }"}"#,
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(
+            res,
+            serde_json::json!({ "data": { "me": { "name": "John" } } })
+        );
+        server.shutdown.send(()).unwrap();
+        server.task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_limits() {
+        let server = start(ServerConfig {
+            schema: "expand_object".to_string(),
+            limit_depth: Some(2),
+            max_body_bytes: 64,
+            ..Default::default()
+        })
+        .await;
+        let query = r#"{"query":"{ me { avatar { url } } }"}"#;
+        let (status, res) = send_json(server.addr, "POST", "/graphql", query).await;
+        assert_eq!(status, 200);
+        assert_eq!(res["errors"][0]["message"], "Query is nested too deep.");
+        let query = format!(r#"{{"query":"{{ me {{ name }} }}{}"}}"#, " ".repeat(64));
+        let (status, _) = send(server.addr, "POST", "/graphql", &query).await;
+        assert_eq!(status, 413);
+        server.shutdown.send(()).unwrap();
+        server.task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown() {
+        let server = start(ServerConfig::default()).await;
+        server.shutdown.send(()).unwrap();
+        server.task.await.unwrap().unwrap();
+        assert!(TcpStream::connect(server.addr).await.is_err());
+    }
+
+    #[test]
+    fn test_unknown_schema() {
+        let config = ServerConfig {
+            schema: "missing".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            ServedSchema::build(&config).err().unwrap().to_string(),
+            "unknown schema module missing, expected one of hello, expand_object",
+        );
+    }
+}
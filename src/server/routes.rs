@@ -0,0 +1,88 @@
+use crate::server::config::ServerConfig;
+use crate::server::schemas::ServedSchema;
+use async_graphql::dynamic::DynamicRequestExt;
+use async_graphql::http::{parse_query_string, GraphiQLSource};
+use async_graphql::parser::types::{DocumentOperations, OperationType};
+use async_graphql::{Request, Response, ServerError};
+use axum::extract::{DefaultBodyLimit, RawQuery, State};
+use axum::http::StatusCode;
+use axum::response::Html;
+use axum::routing::get;
+use axum::{Json, Router};
+use std::sync::Arc;
+
+pub const GRAPHQL_PATH: &str = "/graphql";
+
+pub fn router(schema: Arc<ServedSchema>, config: &ServerConfig) -> Router {
+    Router::new()
+        .route("/", get(graphiql))
+        .route(GRAPHQL_PATH, get(graphql_get).post(graphql_post))
+        .route("/health", get(health))
+        .layer(DefaultBodyLimit::max(config.max_body_bytes))
+        .with_state(schema)
+}
+
+async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint(GRAPHQL_PATH).finish())
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+async fn graphql_post(
+    State(schema): State<Arc<ServedSchema>>,
+    Json(request): Json<Request>,
+) -> Json<Response> {
+    Json(execute(&schema, request).await)
+}
+
+/// queries only, mutations change state and must be posted
+async fn graphql_get(
+    State(schema): State<Arc<ServedSchema>>,
+    RawQuery(query): RawQuery,
+) -> (StatusCode, Json<Response>) {
+    let request = match parse_query_string(query.as_deref().unwrap_or_default()) {
+        Ok(request) => request,
+        Err(err) => return error(StatusCode::BAD_REQUEST, err.to_string()),
+    };
+    if is_mutation(&request) {
+        return error(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "mutations can't be sent with GET".to_string(),
+        );
+    }
+    (StatusCode::OK, Json(execute(&schema, request).await))
+}
+
+async fn execute(schema: &ServedSchema, request: Request) -> Response {
+    match schema.root_value {
+        Some(root_value) => {
+            schema
+                .handle
+                .execute(request.root_value(root_value()))
+                .await
+        }
+        None => schema.handle.execute(request).await,
+    }
+}
+
+// a document that doesn't parse is reported by the execution
+fn is_mutation(request: &Request) -> bool {
+    let Ok(document) = async_graphql::parser::parse_query(&request.query) else {
+        return false;
+    };
+    let operation = match &document.operations {
+        DocumentOperations::Single(operation) => Some(operation),
+        DocumentOperations::Multiple(operations) => match &request.operation_name {
+            Some(name) => operations.get(name.as_str()),
+            None => operations.values().next(),
+        },
+    };
+    operation.is_some_and(|operation| operation.node.ty == OperationType::Mutation)
+}
+
+fn error(status: StatusCode, message: String) -> (StatusCode, Json<Response>) {
+    let response = Response::from_errors(vec![ServerError::new(message, None)]);
+    (status, Json(response))
+}
@@ -0,0 +1,73 @@
+use crate::schema::handle::{SchemaBuildError, SchemaHandle};
+use crate::schema::registry::Registry;
+use crate::schema::{expand_object, hello};
+use crate::server::config::ServerConfig;
+use async_graphql::dynamic::{self, FieldValue};
+
+/// schema module the server can serve, selected with `--schema`
+pub struct SchemaModule {
+    pub name: &'static str,
+    pub query: &'static str,
+    pub registry: fn() -> Registry,
+    /// root value added to every request, for modules resolving the query from a rust value
+    pub root_value: Option<fn() -> FieldValue<'static>>,
+}
+
+pub const SCHEMA_MODULES: &[SchemaModule] = &[
+    SchemaModule {
+        name: "hello",
+        query: "Query",
+        registry: hello::create_registry,
+        root_value: None,
+    },
+    SchemaModule {
+        name: "expand_object",
+        query: "Query",
+        registry: expand_object::create_registry,
+        root_value: Some(expand_object::root_value),
+    },
+];
+
+#[derive(thiserror::Error, Debug)]
+pub enum ServeSchemaError {
+    #[error("unknown schema module {0}, expected one of {1}")]
+    UnknownModule(String, String),
+    #[error(transparent)]
+    Build(#[from] SchemaBuildError),
+}
+
+/// schema built for the server with the limits of the config, requests are served through the
+/// handle
+pub struct ServedSchema {
+    pub handle: SchemaHandle,
+    pub root_value: Option<fn() -> FieldValue<'static>>,
+}
+
+impl ServedSchema {
+    pub fn build(config: &ServerConfig) -> Result<Self, ServeSchemaError> {
+        let module = SCHEMA_MODULES
+            .iter()
+            .find(|module| module.name == config.schema)
+            .ok_or_else(|| {
+                let names = SCHEMA_MODULES
+                    .iter()
+                    .map(|module| module.name)
+                    .collect::<Vec<_>>();
+                ServeSchemaError::UnknownModule(config.schema.clone(), names.join(", "))
+            })?;
+        let schema_builder = dynamic::Schema::build(module.query, None, None);
+        let schema_builder = match config.limit_depth {
+            Some(depth) => schema_builder.limit_depth(depth),
+            None => schema_builder,
+        };
+        let schema_builder = match config.limit_complexity {
+            Some(complexity) => schema_builder.limit_complexity(complexity),
+            None => schema_builder,
+        };
+        let handle = SchemaHandle::build((module.registry)(), schema_builder)?;
+        Ok(Self {
+            handle,
+            root_value: module.root_value,
+        })
+    }
+}